@@ -1,11 +1,18 @@
 use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
-use crate::models::ChatMessage;
-use crate::llm::{LLMFactory, LLMResponse, StreamChunk};
+use crate::models::{AppSettings, ChatMessage, DEFAULT_SUMMARY_LANGUAGE};
+use crate::llm::{ConnectionReport, LLMFactory, LLMProvider, LLMResponse, StreamChunk, Usage};
 use crate::config::commands::ConfigManagerState;
-use std::fs;
-use std::path::PathBuf;
+use crate::db::DbState;
+use crate::error::{CommandError, ErrorKind};
+use crate::github::{CallBudget, GitHubHost};
+use std::collections::HashMap;
+use crate::i18n::{tr, ErrorCode};
+use crate::batch::{BatchProgress, BatchRecorder, BatchReport};
+use futures_util::StreamExt;
+use tokio_util::sync::CancellationToken;
 use tauri::Manager;
+use crate::insight_cache::InsightKey;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RepoInfo {
@@ -23,137 +30,1386 @@ pub struct RepoInfo {
 pub enum StreamPayload {
     Token(String),
     Error(String),
+    /// 丢弃已收到的内容（质量自检触发重新生成时发送）
+    Reset,
+    /// 模型的推理过程，与正文分开展示
+    Reasoning(String),
+    /// 因 GitHub 请求额度用尽而跳过的上下文
+    ContextSkipped(Vec<String>),
+    /// 本次生成消耗的 token（提供商报告使用量时在 Done 之前发送）
+    Usage {
+        prompt_tokens: u32,
+        completion_tokens: u32,
+        total_tokens: u32,
+    },
     Done,
 }
 
+/// 发送使用量统计，提供商未报告时不发送
+fn send_usage(on_event: &Channel<StreamPayload>, usage: Option<Usage>) {
+    if let Some(usage) = usage {
+        let _ = on_event.send(StreamPayload::Usage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+        });
+    }
+}
+
+/// 累加使用量，任意一方缺失时保留另一方
+fn add_usage(total: &mut Option<Usage>, usage: Option<Usage>) {
+    if let Some(usage) = usage {
+        total.get_or_insert_with(Usage::default).add(&usage);
+    }
+}
+
+/// 总结篇幅
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SummaryLength {
+    /// 3 句话的要点速览
+    Short,
+    /// 默认的三维度总结
+    #[default]
+    Medium,
+    /// 完整的多维度拆解
+    Long,
+}
+
+impl SummaryLength {
+    /// 提示词中关于篇幅与维度的要求
+    fn instructions(&self) -> &'static str {
+        match self {
+            SummaryLength::Short => "请只用 3 句话概括：它是什么、解决了什么核心痛点、适合谁用。不要使用标题或列表。",
+            SummaryLength::Medium => "请包含以下维度：\n1. 核心技术架构\n2. 解决了什么核心痛点\n3. 适合谁用以及如何快速上手（3句话以内）\n请使用 Markdown 格式。",
+            SummaryLength::Long => "请使用 Markdown 二级标题逐一详细展开以下维度：\n1. 核心技术架构（关键模块与数据流）\n2. 解决了什么核心痛点\n3. 与同类项目相比的优势与不足\n4. 适合谁用以及如何快速上手（附安装或使用示例）\n5. 使用前需要注意的风险（维护状态、许可证、兼容性等）",
+        }
+    }
+
+    /// 回复的最大 token 数，None 表示沿用模型配置
+    fn max_tokens(&self) -> Option<u32> {
+        match self {
+            SummaryLength::Short => Some(400),
+            SummaryLength::Medium => None,
+            SummaryLength::Long => Some(4096),
+        }
+    }
+
+    /// 缓存变体后缀，默认篇幅为空以兼容已有缓存
+    pub(crate) fn cache_suffix(&self) -> &'static str {
+        match self {
+            SummaryLength::Short => "_short",
+            SummaryLength::Medium => "",
+            SummaryLength::Long => "_long",
+        }
+    }
+
+    pub(crate) const ALL: [SummaryLength; 3] = [SummaryLength::Short, SummaryLength::Medium, SummaryLength::Long];
+}
+
+/// 单次总结的生成选项
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SummaryOptions {
+    pub(crate) deep_mode: bool,
+    pub(crate) high_quality: bool,
+    pub(crate) length: SummaryLength,
+}
+
+impl SummaryOptions {
+    /// 页脚中展示的模式说明
+    pub(crate) fn mode_label(&self) -> String {
+        let mut parts = vec![if self.deep_mode { "deep mode" } else { "standard mode" }];
+        match self.length {
+            SummaryLength::Short => parts.push("short"),
+            SummaryLength::Medium => {}
+            SummaryLength::Long => parts.push("long"),
+        }
+        if self.high_quality {
+            parts.push("high quality");
+        }
+        parts.join(", ")
+    }
+}
+
+/// 页脚与正文之间的分隔标记，便于计算内容哈希等场景剔除页脚
+pub(crate) const FOOTER_MARKER: &str = "\n\n<!-- github-capture:footer -->\n";
+
+/// 按模板生成总结页脚，支持 `{model}`、`{mode}`、`{date}` 占位符
+fn render_footer(template: &str, model: &str, mode: &str, date: &str) -> String {
+    let footer = template
+        .replace("{model}", model)
+        .replace("{mode}", mode)
+        .replace("{date}", date);
+    format!("{}{}", FOOTER_MARKER, footer)
+}
+
+/// 总结使用的系统提示词，非默认输出语言时附加回答语言要求
+fn summary_system_prompt(language: &str) -> String {
+    let base = "你是一个资深的软件架构师和技术布道者，擅长简明扼要地总结技术项目。";
+    let language_name = match language {
+        DEFAULT_SUMMARY_LANGUAGE => return base.to_string(),
+        "en" => "English",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "fr" => "French",
+        "de" => "German",
+        "es" => "Spanish",
+        "pt" => "Portuguese",
+        "ru" => "Russian",
+        "zh-tw" | "zh-hant" => "Traditional Chinese",
+        other => return format!("{}\nRespond in the language whose ISO 639-1 code is \"{}\".", base, other),
+    };
+    format!("{}\nRespond in {}.", base, language_name)
+}
+
+/// 按模板生成总结提示词，只扫描模板一次，替换后的内容（例如 README 中的花括号）不会再被解析
+fn render_prompt_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut prompt = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        prompt.push_str(&rest[..start]);
+        let candidate = &rest[start + 1..];
+        let value = candidate.find('}').and_then(|end| {
+            let key = &candidate[..end];
+            values.iter().find(|(k, _)| *k == key).map(|(_, v)| (*v, end))
+        });
+        match value {
+            Some((value, end)) => {
+                prompt.push_str(value);
+                rest = &candidate[end + 1..];
+            }
+            None => {
+                prompt.push('{');
+                rest = candidate;
+            }
+        }
+    }
+    prompt.push_str(rest);
+    prompt
+}
+
+/// 拆分总结正文与页脚
+pub(crate) fn split_footer(content: &str) -> (&str, Option<&str>) {
+    match content.find(FOOTER_MARKER) {
+        Some(pos) => (&content[..pos], Some(&content[pos + FOOTER_MARKER.len()..])),
+        None => (content, None),
+    }
+}
+
+/// `summarize_repo` 的模型与总结选项，均可省略
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummarizeParams {
+    pub api_key: Option<String>,
+    pub model_config_id: Option<String>,
+    pub deep_context: Option<bool>,
+    pub force_refresh: Option<bool>,
+    pub high_quality: Option<bool>,
+    pub length: Option<SummaryLength>,
+    /// 提供时可通过 `cancel_summary` 中途取消
+    pub request_id: Option<String>,
+}
+
 /// 向后兼容的仓库总结命令
 /// 
-/// 增加了 deep_context 和 force_refresh 参数支持
+/// 增加了 deep_context、force_refresh、high_quality 和 length 参数支持
 #[tauri::command]
 pub async fn summarize_repo(
     repo: RepoInfo,
-    api_key: Option<String>,
-    model_config_id: Option<String>,
+    params: SummarizeParams,
+    on_event: Channel<StreamPayload>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), CommandError> {
+    let SummarizeParams { api_key, model_config_id, deep_context, force_refresh, high_quality, length, request_id } = params;
+    let length = length.unwrap_or_default();
+    let options = SummaryOptions {
+        deep_mode: deep_context.unwrap_or(false),
+        // 质量自检按完整维度打分，不适用于要点速览
+        high_quality: high_quality.unwrap_or(false) && length != SummaryLength::Short,
+        length,
+    };
+    // 确定使用哪种模式
+    let model = if let Some(config_id) = model_config_id {
+        SummaryModel::Config(config_id)
+    } else if let Some(api_key) = api_key {
+        SummaryModel::ApiKey(api_key)
+    } else {
+        return Err(CommandError::config(tr(ErrorCode::MissingCredentials, &[])));
+    };
+    let cache_mode = if force_refresh.unwrap_or(false) { CacheMode::Refresh } else { CacheMode::Use };
+    // 提供请求 ID 时可通过 `cancel_summary` 中途取消
+    let cancellations = app_handle.try_state::<SummaryCancellations>();
+    let cancel = request_id.as_deref().zip(cancellations.as_deref()).map(|(id, c)| c.register(id));
+    let request = SummaryRequest { cancel, ..SummaryRequest::new(model, options, cache_mode) };
+    let result = run_summary(&repo, request, on_event, &config_manager, &app_handle).await;
+    if let (Some(id), Some(cancellations)) = (request_id.as_deref(), cancellations.as_deref()) {
+        cancellations.remove(id);
+    }
+    if result.is_ok() {
+        auto_favorite(&repo, &config_manager, &app_handle).await;
+    }
+    result.map(|_| ())
+}
+
+/// 进行中的总结请求的取消令牌，按请求 ID 保存（由 Tauri 托管）
+#[derive(Default)]
+pub struct SummaryCancellations(std::sync::Mutex<HashMap<String, CancellationToken>>);
+
+impl SummaryCancellations {
+    /// 为请求创建取消令牌；同一 ID 的旧请求会被取消
+    fn register(&self, request_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Ok(mut tokens) = self.0.lock() {
+            if let Some(previous) = tokens.insert(request_id.to_string(), token.clone()) {
+                previous.cancel();
+            }
+        }
+        token
+    }
+
+    fn remove(&self, request_id: &str) {
+        if let Ok(mut tokens) = self.0.lock() {
+            tokens.remove(request_id);
+        }
+    }
+
+    /// 取消请求，请求不存在（已结束）时返回 false
+    fn cancel(&self, request_id: &str) -> bool {
+        let token = self.0.lock().ok().and_then(|mut tokens| tokens.remove(request_id));
+        token.map(|t| t.cancel()).is_some()
+    }
+}
+
+/// 取消进行中的总结：停止读取模型输出并关闭连接，已生成的部分不会写入缓存
+#[tauri::command]
+pub fn cancel_summary(request_id: String, cancellations: tauri::State<'_, SummaryCancellations>) -> bool {
+    cancellations.cancel(&request_id)
+}
+
+/// 已取消时返回 `Cancelled` 错误
+fn ensure_not_cancelled(cancel: Option<&CancellationToken>) -> Result<(), CommandError> {
+    match cancel {
+        Some(token) if token.is_cancelled() => Err(CommandError::cancelled()),
+        _ => Ok(()),
+    }
+}
+
+/// 开启 `auto_favorite_on_summarize` 时，将成功总结的仓库加入收藏（已收藏则跳过）
+async fn auto_favorite(repo: &RepoInfo, config_manager: &tauri::State<'_, ConfigManagerState>, app_handle: &tauri::AppHandle) {
+    let enabled = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .map(|s| s.auto_favorite_on_summarize)
+        .unwrap_or(false);
+    let Some(pool) = app_handle.try_state::<DbState>().filter(|_| enabled) else {
+        return;
+    };
+    if let Err(e) = crate::db::ensure_favorite(&pool, repo).await {
+        log::warn!("自动收藏失败 {}: {}", repo.url, e);
+    }
+}
+
+/// 总结结果的缓存方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheMode {
+    /// 命中缓存时直接返回
+    Use,
+    /// 忽略已有缓存并重新生成
+    Refresh,
+    /// 多模型对比：不读取也不覆盖主缓存，结果按模型配置另存
+    Comparison,
+}
+
+/// 总结使用的模型来源
+enum SummaryModel {
+    /// 配置管理器中的模型配置 ID
+    Config(String),
+    /// 直接提供的 OpenAI API Key（旧模式，不缓存）
+    ApiKey(String),
+}
+
+/// 一次总结请求：模型来源、生成选项、缓存方式与可选的取消令牌
+struct SummaryRequest {
+    model: SummaryModel,
+    options: SummaryOptions,
+    cache_mode: CacheMode,
+    cancel: Option<CancellationToken>,
+}
+
+impl SummaryRequest {
+    /// 创建不可取消的请求
+    fn new(model: SummaryModel, options: SummaryOptions, cache_mode: CacheMode) -> Self {
+        Self { model, options, cache_mode, cancel: None }
+    }
+}
+
+/// 抓取仓库上下文并生成总结（命中缓存时直接返回缓存内容），返回最终展示的总结
+async fn run_summary(
+    repo: &RepoInfo,
+    request: SummaryRequest,
+    on_event: Channel<StreamPayload>,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+    app_handle: &tauri::AppHandle,
+) -> Result<String, CommandError> {
+    let SummaryOptions { deep_mode, length, .. } = request.options;
+    let (model, cache_mode) = (&request.model, request.cache_mode);
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+
+    // 1. 检查缓存（如果不强制刷新且允许缓存）
+    if cache_mode == CacheMode::Use && settings.cache_insights {
+        if let Some(mut cached) = get_cached_insight_internal(repo, length, &settings, app_handle).await {
+            // 关闭页脚后，已缓存的页脚也不再展示
+            if !settings.summary_footer {
+                cached = split_footer(&cached).0.to_string();
+            }
+            let _ = on_event.send(StreamPayload::Token(cached.clone()));
+            let _ = on_event.send(StreamPayload::Done);
+            return Ok(cached);
+        }
+    }
+    // 离线模式下只能读取已有缓存
+    crate::offline::ensure_online().map_err(CommandError::network)?;
+
+    let context = gather_summary_context(repo, model, request.options, &settings, &on_event, config_manager).await?;
+    let messages = vec![
+        ChatMessage::system(&summary_system_prompt(&settings.summary_language)),
+        ChatMessage::user(&summary_prompt(repo, &context, deep_mode, length, &settings)),
+    ];
+
+    match model {
+        SummaryModel::Config(config_id) => {
+            // 关闭洞察缓存时不写入任何内容
+            let cache_key = match cache_mode {
+                _ if !settings.cache_insights => None,
+                CacheMode::Comparison => Some(InsightKey::comparison(&repo.url, length, &settings.summary_language, config_id)),
+                CacheMode::Use | CacheMode::Refresh => Some(InsightKey::primary(&repo.url, length, &settings.summary_language)),
+            };
+            summarize_and_cache(repo, config_id, messages, &request, cache_key, on_event, config_manager).await
+        }
+        // 旧模式暂不支持缓存，保持原有逻辑
+        SummaryModel::ApiKey(api_key) => {
+            summarize_with_api_key(api_key, messages, &request, on_event, config_manager).await
+        }
+    }
+}
+
+/// 总结提示词中的仓库上下文（已按模型上下文窗口裁剪）
+struct SummaryContext {
+    readme: String,
+    tree: String,
+    extra: String,
+}
+
+/// 抓取总结所需的仓库上下文：README，深度模式下还包括目录结构和技术栈配置文件
+async fn gather_summary_context(
+    repo: &RepoInfo,
+    model: &SummaryModel,
+    options: SummaryOptions,
+    settings: &AppSettings,
+    on_event: &Channel<StreamPayload>,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+) -> Result<SummaryContext, CommandError> {
+    let SummaryOptions { deep_mode, length, .. } = options;
+    // 根据配置的 GitHub 主机解析仓库，避免请求错误的 API
+    let host = GitHubHost::from_settings(settings);
+    let (owner, repo_name) = host.parse_repo_url(&repo.url)?;
+    // 单次总结的 GitHub 请求额度，用尽后跳过剩余上下文
+    let mut budget = CallBudget::new(settings.max_github_calls_per_summary);
+    let mut skipped = Vec::new();
+
+    // 私有或已删除的仓库无法抓取内容，提前报错而不是让模型凭名字臆测
+    if budget.try_take() {
+        crate::github::ensure_repo_accessible(&host, &owner, &repo_name)
+            .await
+            .map_err(CommandError::not_found)?;
+    }
+
+    // 2. 获取基础上下文：README
+    // 如果启用深度模式，不再限制 README 长度
+    // 深度模式按模型上下文窗口计算 README 的长度上限
+    let context_length = resolve_context_length(model, config_manager).await;
+    let reserved_output = length.max_tokens().unwrap_or(DEFAULT_OUTPUT_RESERVE);
+    let readme_limit = if deep_mode {
+        Some(context_char_budget(context_length, reserved_output))
+    } else {
+        Some(2000)
+    };
+    let mut readme_content = if budget.is_exhausted() {
+        skipped.push("README.md".to_string());
+        String::new()
+    } else {
+        fetch_file_content(&host, &owner, &repo_name, "README.md", readme_limit, &mut budget).await.unwrap_or_default()
+    };
+    
+    // 3. 获取深度上下文：文件树和核心配置（如果启用）
+    let mut tree = String::new();
+    let mut extra_context = String::new();
+    if deep_mode {
+        let root_entries = if budget.try_take() {
+            fetch_root_entries(&host, &owner, &repo_name).await
+        } else {
+            skipped.push("目录结构".to_string());
+            None
+        };
+
+        if let Some(entries) = &root_entries {
+            tree = format_tree(entries);
+        }
+
+        // 根据根目录文件识别技术生态
+        let root_files: Vec<String> = root_entries
+            .iter()
+            .flatten()
+            .filter(|e| !e.is_dir)
+            .map(|e| e.name.clone())
+            .collect();
+        let ecosystems = detect_ecosystems(&root_files);
+        if !ecosystems.is_empty() {
+            extra_context.push_str(&format!("\n\n技术栈识别：这看起来是一个 {} 项目", ecosystems.join(" + ")));
+        }
+
+        // 抓取技术栈配置文件（已知根目录列表时只抓取存在的文件），多语言项目最多收集 MAX_CONTEXT_FILES 个
+        let mut collected = 0;
+        let mut snippet_chars = 0;
+        for file in settings.context_files() {
+            if collected >= MAX_CONTEXT_FILES || snippet_chars >= CONTEXT_FILES_TOTAL_CHARS {
+                break;
+            }
+            if root_entries.is_some() && !root_files.contains(&file) {
+                continue;
+            }
+            if budget.is_exhausted() {
+                // 额度用尽时记录一次即可
+                skipped.push(file);
+                break;
+            }
+            // 完整抓取以便解析依赖，提示词中只放入片段
+            if let Some(content) = fetch_file_content(&host, &owner, &repo_name, &file, None, &mut budget).await {
+                collected += 1;
+                let snippet = config_snippet(&content, snippet_chars);
+                snippet_chars += snippet.chars().count();
+                extra_context.push_str(&format!("\n\n配置文件 {} 内容片段：\n---\n{}\n---", file, snippet));
+                let dependencies = crate::deps::parse_dependencies(&file, &content);
+                if !dependencies.is_empty() {
+                    extra_context.push_str(&format!("\n\n关键依赖（来自 {}）：{}", file, dependencies.join(", ")));
+                }
+            }
+        }
+    }
+
+    if !skipped.is_empty() {
+        let _ = on_event.send(StreamPayload::ContextSkipped(skipped));
+    }
+
+    // 4. 按模型上下文窗口裁剪上下文：先裁剪目录结构与配置文件，最后才裁剪 README
+    let fixed_tokens: usize = [
+        summary_system_prompt(&settings.summary_language).as_str(),
+        &repo.description,
+        length.instructions(),
+        settings.summary_prompt_template.as_deref().unwrap_or_default(),
+    ]
+    .iter()
+    .map(|text| crate::tokens::estimate_tokens(text))
+    .sum();
+    let available = (context_length as usize)
+        .saturating_sub(reserved_output as usize + fixed_tokens + PROMPT_MARGIN_TOKENS);
+    let trimmed = crate::tokens::fit_sections(&mut [&mut tree, &mut extra_context, &mut readme_content], available);
+    if trimmed.iter().any(|tokens| *tokens > 0) {
+        log::info!(
+            "{}/{} 的上下文超出模型窗口（{} tokens），已裁剪约：目录结构 {} tokens，配置文件 {} tokens，README {} tokens",
+            repo.author, repo.name, context_length, trimmed[0], trimmed[1], trimmed[2]
+        );
+    }
+
+    Ok(SummaryContext { readme: readme_content, tree, extra: extra_context })
+}
+
+/// 构造总结的用户提示词，设置了自定义模板时按模板渲染
+fn summary_prompt(repo: &RepoInfo, context: &SummaryContext, deep_mode: bool, length: SummaryLength, settings: &AppSettings) -> String {
+    let SummaryContext { readme: readme_content, tree, extra: extra_context } = context;
+    let readme_prompt = if !readme_content.is_empty() {
+        format!("\n\n项目 README 内容{}：\n---\n{}\n---", 
+            if deep_mode { "（完整）" } else { "（片段）" },
+            readme_content
+        )
+    } else {
+        "".to_string()
+    };
+
+    match settings.summary_prompt_template.as_deref() {
+        Some(template) => render_prompt_template(template, &[
+            ("author", &repo.author),
+            ("name", &repo.name),
+            ("description", &repo.description),
+            ("language", &repo.language),
+            ("readme", readme_content),
+            ("tree", tree),
+            ("context", extra_context.trim()),
+            ("instructions", length.instructions()),
+        ]),
+        None => {
+            let tree_prompt = if tree.is_empty() {
+                String::new()
+            } else {
+                format!("\n\n项目目录结构（部分）：\n---\n{}\n---", tree)
+            };
+            format!(
+                "请对以下 GitHub 项目进行深入浅出的深度总结：\n项目：{}/{}\n描述：{}\n语言：{}{}{}{}\n\n{}",
+                repo.author, repo.name, repo.description, repo.language, readme_prompt, tree_prompt, extra_context, length.instructions()
+            )
+        }
+    }
+}
+
+/// 多模型对比时的流式事件，按模型配置 ID 区分
+#[derive(Clone, Serialize)]
+pub struct ComparisonEvent {
+    pub config_id: String,
+    pub payload: serde_json::Value,
+}
+
+/// 使用多个模型配置并行总结同一个仓库，便于对比各模型的总结效果
+///
+/// 结果另存为对比稿，不会覆盖主缓存；选定后调用 `adopt_comparison_summary` 采用。
+#[tauri::command]
+pub async fn summarize_with_providers(
+    repo: RepoInfo,
+    config_ids: Vec<String>,
     deep_context: Option<bool>,
+    length: Option<SummaryLength>,
+    on_event: Channel<ComparisonEvent>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchReport<String>, String> {
+    crate::offline::ensure_online()?;
+    let options = SummaryOptions {
+        deep_mode: deep_context.unwrap_or(false),
+        high_quality: false,
+        length: length.unwrap_or_default(),
+    };
+    let (repo, config_manager, app_handle) = (&repo, &config_manager, &app_handle);
+    let tasks = config_ids.into_iter().map(|config_id| {
+        // 将各模型的流式事件加上配置 ID 后转发给前端
+        let outer = on_event.clone();
+        let tag = config_id.clone();
+        let forward = Channel::new(move |body| {
+            if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                if let Ok(payload) = serde_json::from_str(&json) {
+                    let _ = outer.send(ComparisonEvent { config_id: tag.clone(), payload });
+                }
+            }
+            Ok(())
+        });
+        async move {
+            let model = SummaryModel::Config(config_id.clone());
+            let request = SummaryRequest::new(model, options, CacheMode::Comparison);
+            let result = run_summary(repo, request, forward, config_manager, app_handle).await;
+            (config_id.clone(), result.map(|_| config_id).map_err(String::from))
+        }
+    });
+
+    let results = futures_util::future::join_all(tasks).await;
+    let mut recorder = BatchRecorder::new(results.len(), None);
+    for (config_id, result) in results {
+        recorder.record(&config_id, result);
+    }
+    Ok(recorder.finish())
+}
+
+/// 采用某个模型的对比稿作为仓库的主缓存
+#[tauri::command]
+pub async fn adopt_comparison_summary(
+    repo: RepoInfo,
+    config_id: String,
+    length: Option<SummaryLength>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let length = length.unwrap_or_default();
+    let settings = insight_settings(&config_manager).await;
+    let pool = app_handle.try_state::<DbState>().ok_or("数据库不可用，无法保存洞察")?;
+    let source = InsightKey::comparison(&repo.url, length, &settings.summary_language, &config_id);
+    let target = InsightKey::primary(&repo.url, length, &settings.summary_language);
+    if !crate::insight_cache::copy(&pool, &source, &target).await? {
+        return Err(format!("找不到模型 {} 的对比稿", config_id));
+    }
+    // 默认篇幅的主缓存同时更新收藏的洞察来源，模型信息取自被采用的对比稿
+    if length == SummaryLength::Medium {
+        let metadata = crate::insight_cache::load_metadata(&pool, &source).await;
+        let content = crate::insight_cache::load(&pool, &target, None).await;
+        if let (Some(metadata), Some(content)) = (metadata, content) {
+            if let Err(e) = crate::db::save_insight(
+                &pool, &repo.url, &content, &metadata.model, &metadata.provider, &metadata.mode_label(), &metadata.language,
+            ).await {
+                log::warn!("保存洞察来源失败: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 为当前趋势榜的仓库预先生成洞察，用户浏览时即可直接读取缓存
+///
+/// 已有缓存的仓库会被跳过；按 `max_concurrent_summaries` 设置限制并发。
+#[tauri::command]
+pub async fn prewarm_trending_insights(
+    language: Option<String>,
+    since: String,
+    model_config_id: String,
+    on_progress: Channel<BatchProgress>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchReport<String>, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    if !settings.cache_insights {
+        return Err("已关闭洞察缓存，无法预热".to_string());
+    }
+
+    let trending = crate::trending::fetch_trending(language, &since, None).await?;
+    let mut pending = Vec::new();
+    for item in trending {
+        let repo = RepoInfo {
+            author: item.author,
+            name: item.name,
+            description: item.description,
+            language: item.language,
+            url: item.url,
+            stars: Some(item.stars),
+            forks: Some(item.forks),
+        };
+        if !has_cached_insight(&repo, SummaryLength::Medium, &settings, &app_handle).await {
+            pending.push(repo);
+        }
+    }
+
+    let options = SummaryOptions { deep_mode: false, high_quality: false, length: SummaryLength::Medium };
+    let mut recorder = BatchRecorder::new(pending.len(), Some(&on_progress));
+    let (config_manager, app_handle) = (&config_manager, &app_handle);
+    let mut tasks = futures_util::stream::iter(pending.into_iter().map(|repo| {
+        let model = SummaryModel::Config(model_config_id.clone());
+        // 预热不需要把内容推送给前端
+        let discard = Channel::new(|_| Ok(()));
+        async move {
+            let request = SummaryRequest::new(model, options, CacheMode::Refresh);
+            let result = run_summary(&repo, request, discard, config_manager, app_handle).await;
+            (repo.url.clone(), result.map(|_| repo.url).map_err(String::from))
+        }
+    }))
+    .buffer_unordered(settings.max_concurrent_summaries.max(1));
+
+    while let Some((url, result)) = tasks.next().await {
+        recorder.record(&url, result);
+    }
+    Ok(recorder.finish())
+}
+
+/// 批量总结时按仓库 url 区分的事件
+#[derive(Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum BatchSummaryEvent {
+    /// 已有缓存，跳过生成
+    Skipped { url: String },
+    /// 单个仓库的流式事件（内容同 `StreamPayload`）
+    Stream { url: String, payload: serde_json::Value },
+    /// 单个仓库总结完成
+    Finished { url: String },
+    /// 单个仓库总结失败，不影响其他仓库
+    Failed { url: String, reason: String },
+}
+
+/// 批量总结多个仓库（例如趋势榜当前页），每个仓库的流式输出按 url 转发，前端可分别更新卡片
+///
+/// 已有缓存的仓库会被跳过（`force_refresh` 时重新生成），不计入返回结果；
+/// 并发数默认取 `max_concurrent_summaries` 设置。
+#[tauri::command]
+pub async fn summarize_repos_batch(
+    repos: Vec<RepoInfo>,
+    model_config_id: String,
+    concurrency: Option<usize>,
     force_refresh: Option<bool>,
+    on_event: Channel<BatchSummaryEvent>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchReport<String>, String> {
+    crate::offline::ensure_online()?;
+    let settings = insight_settings(&config_manager).await;
+    let force_refresh = force_refresh.unwrap_or(false);
+
+    let mut pending = Vec::new();
+    for repo in repos {
+        if !force_refresh && has_cached_insight(&repo, SummaryLength::Medium, &settings, &app_handle).await {
+            let _ = on_event.send(BatchSummaryEvent::Skipped { url: repo.url });
+        } else {
+            pending.push(repo);
+        }
+    }
+
+    let options = SummaryOptions { deep_mode: false, high_quality: false, length: SummaryLength::Medium };
+    let semaphore = tokio::sync::Semaphore::new(concurrency.unwrap_or(settings.max_concurrent_summaries).max(1));
+    let (semaphore, config_manager, app_handle) = (&semaphore, &config_manager, &app_handle);
+    let mut tasks: futures_util::stream::FuturesUnordered<_> = pending.into_iter().map(|repo| {
+        let outer = on_event.clone();
+        let url = repo.url.clone();
+        let model = SummaryModel::Config(model_config_id.clone());
+        let forward = Channel::new(move |body| {
+            if let tauri::ipc::InvokeResponseBody::Json(json) = body {
+                if let Ok(payload) = serde_json::from_str(&json) {
+                    let _ = outer.send(BatchSummaryEvent::Stream { url: url.clone(), payload });
+                }
+            }
+            Ok(())
+        });
+        async move {
+            // 信号量不会被关闭，许可在任务结束时释放
+            let _permit = semaphore.acquire().await;
+            let request = SummaryRequest::new(model, options, CacheMode::Refresh);
+            let result = run_summary(&repo, request, forward, config_manager, app_handle).await;
+            (repo.url.clone(), result.map(|_| repo.url).map_err(String::from))
+        }
+    }).collect();
+
+    // 每个仓库完成时立即通知前端，而不是等整批结束
+    let mut recorder = BatchRecorder::new(tasks.len(), None);
+    while let Some((url, result)) = tasks.next().await {
+        let event = match &result {
+            Ok(_) => BatchSummaryEvent::Finished { url: url.clone() },
+            Err(reason) => BatchSummaryEvent::Failed { url: url.clone(), reason: reason.clone() },
+        };
+        let _ = on_event.send(event);
+        recorder.record(&url, result);
+    }
+    Ok(recorder.finish())
+}
+
+/// 摘要中每个仓库的洞察最多引用的字符数
+const DIGEST_INSIGHT_CHARS: usize = 1500;
+
+/// 将多个仓库的洞察汇总为一份可分享的 Markdown 摘要（如“本周值得关注的开源项目”）
+///
+/// 优先使用已缓存的洞察，缺失的按 `generate_missing`（默认开启）先生成；
+/// 收集进度通过 `on_progress` 报告，摘要正文通过 `on_event` 流式输出，
+/// 无法获取洞察的仓库会在摘要末尾列出。
+#[tauri::command]
+pub async fn generate_digest(
+    urls: Vec<String>,
+    model_config_id: String,
+    generate_missing: Option<bool>,
+    on_progress: Channel<BatchProgress>,
+    on_event: Channel<StreamPayload>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BatchReport<String>, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    let host = GitHubHost::from_settings(&settings);
+    let generate_missing = generate_missing.unwrap_or(true);
+
+    let urls: Vec<String> = urls.into_iter().map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect();
+    let mut recorder = BatchRecorder::new(urls.len(), Some(&on_progress));
+    let (host, settings, model_config_id, config_manager, app_handle) =
+        (&host, &settings, &model_config_id, &config_manager, &app_handle);
+    let mut tasks = futures_util::stream::iter(urls.into_iter().map(|url| async move {
+        let result = gather_digest_insight(&url, host, model_config_id, generate_missing, settings, config_manager, app_handle).await;
+        (url, result)
+    }))
+    .buffered(settings.max_concurrent_summaries.max(1));
+
+    let mut sections = Vec::new();
+    while let Some((url, result)) = tasks.next().await {
+        if let Ok((full_name, insight)) = &result {
+            let excerpt: String = insight.chars().take(DIGEST_INSIGHT_CHARS).collect();
+            sections.push(format!("## {}\n链接：{}\n{}", full_name, url, excerpt));
+        }
+        recorder.record(&url, result.map(|_| url.clone()));
+    }
+    let report = recorder.finish();
+    if sections.is_empty() {
+        return Err("没有可用于生成摘要的洞察".to_string());
+    }
+
+    let prompt = format!(
+        "请将以下 {} 个 GitHub 项目的洞察整理为一份可分享的 Markdown 摘要（类似“本周值得关注的开源项目”）：\n\
+         先用一段话概括整体趋势，再为每个项目写 2-3 句推荐理由并附上链接。\n\n{}",
+        sections.len(),
+        sections.join("\n\n")
+    );
+    let messages = vec![
+        ChatMessage::system("你是一名技术编辑，擅长把多个开源项目整理成简洁、易读的推荐周刊。"),
+        ChatMessage::user(&prompt),
+    ];
+
+    let manager = config_manager.lock().await;
+    let configs = manager.get_all_model_configs().await.map_err(|e| e.to_string())?;
+    let config = configs.into_iter().find(|c| &c.id == model_config_id).ok_or_else(|| tr(ErrorCode::ModelConfigNotFound, &[model_config_id]))?;
+    let provider = LLMFactory::create_provider(&config).map_err(|e| e.to_string())?;
+    let provider = crate::audit::wrap_provider(provider, &config, &manager).await;
+    drop(manager);
+
+    let (_, usage) = stream_completion(provider.as_ref(), &config.default_model, messages, &on_event).await?;
+    if !report.failed.is_empty() {
+        let missing: Vec<String> = report.failed.iter().map(|f| format!("- {}（{}）", f.item, f.reason)).collect();
+        let note = format!("\n\n---\n以下仓库暂无洞察，未收录在本摘要中：\n{}", missing.join("\n"));
+        let _ = on_event.send(StreamPayload::Token(note));
+    }
+    send_usage(&on_event, usage);
+    let _ = on_event.send(StreamPayload::Done);
+    Ok(report)
+}
+
+/// 获取摘要所需的单个仓库洞察，返回（仓库全名, 不含页脚的洞察）
+async fn gather_digest_insight(
+    url: &str,
+    host: &GitHubHost,
+    model_config_id: &str,
+    generate_missing: bool,
+    settings: &crate::models::AppSettings,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+    app_handle: &tauri::AppHandle,
+) -> Result<(String, String), String> {
+    let (owner, name) = host.parse_repo_url(url)?;
+    let full_name = format!("{}/{}", owner, name);
+    let mut repo = RepoInfo {
+        author: owner,
+        name,
+        description: String::new(),
+        language: String::new(),
+        url: url.to_string(),
+        stars: None,
+        forks: None,
+    };
+    if settings.cache_insights {
+        if let Some(cached) = get_cached_insight_internal(&repo, SummaryLength::Medium, settings, app_handle).await {
+            return Ok((full_name, split_footer(&cached).0.to_string()));
+        }
+    }
+    if !generate_missing {
+        return Err("尚未生成洞察".to_string());
+    }
+
+    let metadata = crate::github::fetch_repo_metadata(host, &repo.author, &repo.name).await?;
+    repo.description = metadata.description.unwrap_or_default();
+    repo.language = metadata.language.unwrap_or_default();
+    repo.stars = Some(crate::search::format_number(metadata.stargazers_count));
+    repo.forks = Some(crate::search::format_number(metadata.forks_count));
+
+    let options = SummaryOptions { deep_mode: false, high_quality: false, length: SummaryLength::Medium };
+    let model = SummaryModel::Config(model_config_id.to_string());
+    // 逐个仓库的生成过程不推送给前端，只有最终摘要流式输出
+    let discard = Channel::new(|_| Ok(()));
+    let request = SummaryRequest::new(model, options, CacheMode::Use);
+    let insight = run_summary(&repo, request, discard, config_manager, app_handle).await?;
+    Ok((full_name, split_footer(&insight).0.to_string()))
+}
+
+/// 续写开头用于检测重叠的缓冲长度（字符）
+const CONTINUATION_OVERLAP_WINDOW: usize = 200;
+/// 至少重叠这么多字符才视为重复，避免误删恰好相同的单个字符
+const MIN_OVERLAP_CHARS: usize = 8;
+
+/// 续写被截断的总结：将已有内容作为助手消息发回，让模型从中断处继续，
+/// 续写内容流式输出并追加到缓存
+#[tauri::command]
+pub async fn continue_summary(
+    url: String,
+    model_config_id: String,
+    length: Option<SummaryLength>,
     on_event: Channel<StreamPayload>,
     config_manager: tauri::State<'_, ConfigManagerState>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let deep_mode = deep_context.unwrap_or(false);
-    let refresh = force_refresh.unwrap_or(false);
+    crate::offline::ensure_online()?;
+    let length = length.unwrap_or_default();
+    let manager = config_manager.lock().await;
+    let settings = manager.get_app_settings().await.unwrap_or_default();
+    let (owner, name) = GitHubHost::from_settings(&settings).parse_repo_url(&url)?;
+    let pool = app_handle.try_state::<DbState>().ok_or("数据库不可用，无法读取洞察")?;
+    crate::insight_cache::import_legacy(&pool, &app_handle, &url, &owner, &name).await;
+    let cache_key = InsightKey::primary(&url, length, &settings.summary_language);
+    let cached = crate::insight_cache::load(&pool, &cache_key, None)
+        .await
+        .ok_or("没有可续写的洞察，请先生成总结")?;
+    let (partial, footer) = split_footer(&cached);
+    let metadata = crate::insight_cache::load_metadata(&pool, &cache_key).await;
+    // 按原总结的模式重建提示词，没有生成信息的早期缓存按普通模式处理
+    let options = match &metadata {
+        Some(m) => SummaryOptions { deep_mode: m.deep_mode, high_quality: m.high_quality, length },
+        None => SummaryOptions { deep_mode: false, high_quality: false, length },
+    };
+
+    let configs = manager.get_all_model_configs().await.map_err(|e| e.to_string())?;
+    let config = configs.into_iter().find(|c| c.id == model_config_id).ok_or_else(|| tr(ErrorCode::ModelConfigNotFound, &[&model_config_id]))?;
+    let provider = LLMFactory::create_provider(&config).map_err(|e| e.to_string())?;
+    let provider = crate::audit::wrap_provider(provider, &config, &manager).await;
+    drop(manager);
+
+    let repo_metadata = crate::github::fetch_repo_metadata(&GitHubHost::from_settings(&settings), &owner, &name).await?;
+    let repo = RepoInfo {
+        author: owner,
+        name,
+        description: repo_metadata.description.unwrap_or_default(),
+        language: repo_metadata.language.unwrap_or_default(),
+        url: url.clone(),
+        stars: None,
+        forks: None,
+    };
+    let model = SummaryModel::Config(model_config_id.clone());
+    let context = gather_summary_context(&repo, &model, options, &settings, &on_event, &config_manager).await?;
+    let messages = vec![
+        ChatMessage::system(&summary_system_prompt(&settings.summary_language)),
+        ChatMessage::user(&summary_prompt(&repo, &context, options.deep_mode, length, &settings)),
+        ChatMessage::assistant(partial),
+        ChatMessage::user("你的上一条回答因长度限制被截断了。请从中断处直接继续输出剩余内容，不要重复已经写过的部分，也不要添加开场白。"),
+    ];
+    let continuation = stream_continuation(provider.as_ref(), &config.default_model, messages, partial, &on_event).await?;
+    let continuation = if settings.strip_reasoning { strip_think_blocks(&continuation).0 } else { continuation };
+    let _ = on_event.send(StreamPayload::Done);
+
+    let footer = footer.map(|f| format!("{}{}", FOOTER_MARKER, f)).unwrap_or_default();
+    let content = format!("{}{}{}", partial, continuation, footer);
+    let metadata = metadata.map(|mut metadata| {
+        metadata.chars = content.chars().count();
+        metadata
+    });
+    let updated = crate::insight_cache::update_content(&pool, &cache_key, &content, metadata.as_ref()).await;
+    // 默认篇幅的主缓存同时更新收藏的洞察来源，沿用原总结的模型信息
+    if updated && cache_key == InsightKey::primary(&url, SummaryLength::Medium, &settings.summary_language) {
+        let (model, provider) = match &metadata {
+            Some(m) => (m.model.clone(), m.provider.clone()),
+            None => (config.default_model.clone(), config.provider.display_name()),
+        };
+        if let Err(e) = crate::db::save_insight(
+            &pool, &url, &content, &model, &provider, &options.mode_label(), &repo.language,
+        ).await {
+            log::warn!("保存洞察来源失败: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// 流式输出续写内容，开头与已有内容重复的部分会被去除
+async fn stream_continuation(
+    provider: &dyn LLMProvider,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    partial: &str,
+    on_event: &Channel<StreamPayload>,
+) -> Result<String, String> {
+    let response = provider.chat_completion(messages, model, true)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut seam = SeamDeduper::new(partial);
+    let mut continuation = String::new();
+    let mut emit = |text: String| {
+        if !text.is_empty() {
+            continuation.push_str(&text);
+            let _ = on_event.send(StreamPayload::Token(text));
+        }
+    };
+    match response {
+        LLMResponse::Completion { content, .. } => emit(trim_overlap(partial, &content).to_string()),
+        LLMResponse::ToolCalls { .. } => return Err("预期文本响应，但收到工具调用".to_string()),
+        LLMResponse::Stream { mut stream } => {
+            while let Some(chunk) = stream.recv().await {
+                match chunk {
+                    StreamChunk::Text(text) => {
+                        if let Some(text) = seam.push(&text) {
+                            emit(text);
+                        }
+                    }
+                    StreamChunk::Error(err) => {
+                        let _ = on_event.send(StreamPayload::Error(err));
+                        return Err("流式响应错误".to_string());
+                    }
+                    StreamChunk::Reasoning(_) | StreamChunk::Usage(_) => {}
+                    StreamChunk::Done => break,
+                }
+            }
+            if let Some(text) = seam.finish() {
+                emit(text);
+            }
+        }
+    }
+    Ok(continuation)
+}
+
+/// 续写接缝处的去重：先缓冲续写开头，确定重叠部分后再输出
+struct SeamDeduper<'a> {
+    partial: &'a str,
+    buffer: Option<String>,
+}
+
+impl<'a> SeamDeduper<'a> {
+    fn new(partial: &'a str) -> Self {
+        Self { partial, buffer: Some(String::new()) }
+    }
+
+    /// 追加一段续写，返回可以立即输出的内容
+    fn push(&mut self, text: &str) -> Option<String> {
+        match &mut self.buffer {
+            None => Some(text.to_string()),
+            Some(buffer) => {
+                buffer.push_str(text);
+                if buffer.chars().count() < CONTINUATION_OVERLAP_WINDOW {
+                    return None;
+                }
+                self.finish()
+            }
+        }
+    }
+
+    /// 结束缓冲，返回去重后的剩余内容
+    fn finish(&mut self) -> Option<String> {
+        self.buffer.take().map(|buffer| trim_overlap(self.partial, &buffer).to_string())
+    }
+}
+
+/// 去除续写开头与已有内容末尾重复的部分
+fn trim_overlap<'a>(partial: &str, continuation: &'a str) -> &'a str {
+    let mut overlap = 0;
+    for (count, (index, ch)) in continuation.char_indices().enumerate() {
+        let end = index + ch.len_utf8();
+        if end > partial.len() {
+            break;
+        }
+        if count + 1 >= MIN_OVERLAP_CHARS && partial.ends_with(&continuation[..end]) {
+            overlap = end;
+        }
+    }
+    &continuation[overlap..]
+}
+
+/// 为模型输出预留的 token 数（未限制 max_tokens 时）
+const DEFAULT_OUTPUT_RESERVE: u32 = 4096;
+
+/// 深度模式的上下文评估结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepContextEstimate {
+    /// 模型的上下文窗口（token）
+    pub context_length: u32,
+    /// 深度模式下 README 可用的字符预算
+    pub budget_chars: usize,
+    /// README 的实际字符数
+    pub readme_chars: usize,
+    /// 仓库根目录的条目数
+    pub root_entries: usize,
+    /// README 能否完整放入上下文（否则会被截断）
+    pub fits: bool,
+}
+
+/// 在运行深度模式前评估仓库上下文能否放入所选模型的上下文窗口，
+/// 便于前端提示切换更大上下文的模型或改用快速模式
+#[tauri::command]
+pub async fn can_do_deep_context(
+    repo: RepoInfo,
+    model_config_id: String,
+    length: Option<SummaryLength>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<DeepContextEstimate, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    let host = GitHubHost::from_settings(&settings);
+    let (owner, name) = host.parse_repo_url(&repo.url)?;
+
+    let model = SummaryModel::Config(model_config_id);
+    let context_length = resolve_context_length(&model, &config_manager).await;
+    let reserved_output = length.unwrap_or_default().max_tokens().unwrap_or(DEFAULT_OUTPUT_RESERVE);
+    let budget_chars = context_char_budget(context_length, reserved_output);
+
+    let mut budget = CallBudget::new(settings.max_github_calls_per_summary);
+    crate::github::ensure_repo_accessible(&host, &owner, &name).await?;
+    let readme_chars = fetch_file_content(&host, &owner, &name, "README.md", None, &mut budget)
+        .await
+        .map(|readme| readme.chars().count())
+        .unwrap_or(0);
+    let root_entries = fetch_root_entries(&host, &owner, &name).await.map(|entries| entries.len()).unwrap_or(0);
+
+    Ok(DeepContextEstimate {
+        context_length,
+        budget_chars,
+        readme_chars,
+        root_entries,
+        fits: readme_chars <= budget_chars,
+    })
+}
+
+/// 获取模型的上下文窗口：优先使用模型列表缓存中的值，否则按提供商给出默认值
+async fn resolve_context_length(model: &SummaryModel, config_manager: &tauri::State<'_, ConfigManagerState>) -> u32 {
+    use crate::models::ModelProvider;
+
+    let manager = config_manager.lock().await;
+    let config = match model {
+        SummaryModel::Config(config_id) => manager.get_all_model_configs()
+            .await
+            .ok()
+            .and_then(|configs| configs.into_iter().find(|c| &c.id == config_id)),
+        SummaryModel::ApiKey(_) => None,
+    };
+    let Some(config) = config else {
+        let provider = ModelProvider::OpenAI;
+        return provider.fallback_context_length(&provider.default_model_name());
+    };
+
+    manager.get_cached_models(&config.provider)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|models| models.into_iter().find(|m| m.id == config.default_model))
+        .and_then(|m| m.context_length)
+        .unwrap_or_else(|| config.provider.fallback_context_length(&config.default_model))
+}
+
+/// 提示词中固定文字（标题、分隔符等）的估算余量
+const PROMPT_MARGIN_TOKENS: usize = 200;
+
+/// 深度模式最多放入提示词的配置文件数
+const MAX_CONTEXT_FILES: usize = 3;
+/// 单个配置文件片段的字符上限
+const CONTEXT_FILE_SNIPPET_CHARS: usize = 1500;
+/// 所有配置文件片段合计的字符上限，与目录结构一起计入 [`context_char_budget`] 预留的提示词开销
+const CONTEXT_FILES_TOTAL_CHARS: usize = 3000;
+
+/// 截取配置文件片段，`used` 为已放入提示词的片段字符数
+fn config_snippet(content: &str, used: usize) -> String {
+    let limit = CONTEXT_FILE_SNIPPET_CHARS.min(CONTEXT_FILES_TOTAL_CHARS.saturating_sub(used));
+    content.chars().take(limit).collect()
+}
+
+/// 根据上下文窗口计算可用于仓库上下文的字符数
+fn context_char_budget(context_length: u32, reserved_output: u32) -> usize {
+    // 系统提示词、目录结构与配置片段大致占用的 token
+    const PROMPT_OVERHEAD_TOKENS: u32 = 4000;
+    // 中英混合文本按每 token 约 2 个字符保守估算
+    const CHARS_PER_TOKEN: usize = 2;
+    let available = context_length
+        .saturating_sub(reserved_output)
+        .saturating_sub(PROMPT_OVERHEAD_TOKENS);
+    (available as usize * CHARS_PER_TOKEN).max(2000)
+}
+
+/// 专门用于带缓存的总结逻辑
+async fn summarize_and_cache(
+    repo: &RepoInfo,
+    config_id: &str,
+    messages: Vec<ChatMessage>,
+    request: &SummaryRequest,
+    cache_key: Option<InsightKey>,
+    on_event: Channel<StreamPayload>,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+) -> Result<String, CommandError> {
+    let SummaryRequest { options, cancel, .. } = request;
+    let manager_lock = config_manager.lock().await;
+    let configs = manager_lock.get_all_model_configs().await.map_err(|e| CommandError::config(e.to_string()))?;
+    let mut config = configs
+        .into_iter()
+        .find(|c| c.id == config_id)
+        .ok_or_else(|| CommandError::not_found(tr(ErrorCode::ModelConfigNotFound, &[&config_id])))?;
+    if let Some(max_tokens) = options.length.max_tokens() {
+        config.max_tokens = Some(max_tokens);
+    }
+    let config = &config;
+    let mut provider = LLMFactory::create_provider(config)?;
+    if let Some(token) = &cancel {
+        provider.set_cancellation_token(token.clone());
+    }
+    let provider = crate::audit::wrap_provider(provider, config, &manager_lock).await;
+    let settings = manager_lock.get_app_settings().await.unwrap_or_default();
+    let app_handle = manager_lock.app_handle().clone();
+    // 生成期间不持有配置锁，避免并发总结互相阻塞
+    drop(manager_lock);
 
-    // 1. 检查缓存（如果不强制刷新）
-    if !refresh {
-        if let Some(cached) = get_cached_insight_internal(&repo, &app_handle).await {
-            let _ = on_event.send(StreamPayload::Token(cached));
-            let _ = on_event.send(StreamPayload::Done);
-            return Ok(());
-        }
+    let (full_insight, usage) = generate_summary(provider.as_ref(), &config.default_model, messages, options.high_quality, &on_event).await?;
+    // 取消后流会提前结束，残缺的内容不能写入缓存
+    ensure_not_cancelled(cancel.as_ref())?;
+
+    // 可选的来源页脚：同时发送给前端并随缓存保存
+    let footer = settings.summary_footer.then(|| {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        render_footer(settings.footer_template(), &config.default_model, &options.mode_label(), &date)
+    });
+    if let Some(footer) = &footer {
+        let _ = on_event.send(StreamPayload::Token(footer.clone()));
     }
+    send_usage(&on_event, usage);
+    let _ = on_event.send(StreamPayload::Done);
 
-    // 2. 获取基础上下文：README
-    // 如果启用深度模式，不再限制 README 长度
-    let readme_limit = if deep_mode { None } else { Some(2000) };
-    let readme_content = fetch_readme_with_limit(&repo.author, &repo.name, readme_limit).await.unwrap_or_default();
-    
-    // 3. 获取深度上下文：文件树和核心配置（如果启用）
-    let mut extra_context = String::new();
-    if deep_mode {
-        if let Some(tree) = fetch_tree(&repo.author, &repo.name).await {
-            extra_context.push_str("\n\n项目目录结构（部分）：\n---\n");
-            extra_context.push_str(&tree);
-            extra_context.push_str("\n---");
-        }
-        
-        // 尝试抓取技术栈配置文件
-        let config_files = ["package.json", "Cargo.toml", "go.mod", "requirements.txt", "pom.xml"];
-        for file in config_files {
-            if let Some(content) = fetch_file_content(&repo.author, &repo.name, file, Some(1500)).await {
-                extra_context.push_str(&format!("\n\n配置文件 {} 内容片段：\n---\n{}\n---", file, content));
-                break; // 拿到一个核心配置即可
+    let footer = footer.unwrap_or_default();
+    let (content, reasoning) = if settings.strip_reasoning {
+        let (answer, reasoning) = strip_think_blocks(&full_insight);
+        (format!("{}{}", answer, footer), reasoning)
+    } else {
+        (format!("{}{}", full_insight, footer), None)
+    };
+    let (Some(cache_key), Some(pool)) = (cache_key, app_handle.try_state::<DbState>()) else {
+        return Ok(content);
+    };
+    let metadata = crate::insight_cache::InsightMetadata {
+        repo: format!("{}/{}", repo.author, repo.name),
+        language: repo.language.clone(),
+        config_id: config_id.to_string(),
+        model: config.default_model.clone(),
+        provider: config.provider.display_name(),
+        deep_mode: options.deep_mode,
+        high_quality: options.high_quality,
+        length: options.length,
+        generated_at: chrono::Utc::now().timestamp(),
+        chars: content.chars().count(),
+    };
+    if crate::insight_cache::store(&pool, &cache_key, &content, reasoning.as_deref(), Some(&metadata)).await {
+        // 默认篇幅的主缓存同时记录到收藏的洞察来源（对比稿与其他篇幅不记录）
+        if cache_key == InsightKey::primary(&repo.url, SummaryLength::Medium, &settings.summary_language) {
+            let provider = config.provider.display_name();
+            if let Err(e) = crate::db::save_insight(
+                &pool, &repo.url, &content, &config.default_model, &provider, &options.mode_label(), &repo.language,
+            ).await {
+                log::warn!("保存洞察来源失败: {}", e);
             }
         }
     }
+    Ok(content)
+}
 
-    let readme_prompt = if !readme_content.is_empty() {
-        format!("\n\n项目 README 内容{}：\n---\n{}\n---", 
-            if deep_mode { "（完整）" } else { "（片段）" },
-            readme_content
-        )
-    } else {
-        "".to_string()
-    };
+/// 拆分推理模型输出中的 `<think>...</think>` 块，返回（最终回答, 思考过程）
+fn strip_think_blocks(text: &str) -> (String, Option<String>) {
+    const OPEN: &str = "<think>";
+    const CLOSE: &str = "</think>";
 
-    let prompt = format!(
-        "请对以下 GitHub 项目进行深入浅出的深度总结：\n项目：{}/{}\n描述：{}\n语言：{}{}{}\n\n请包含以下维度：\n1. 核心技术架构\n2. 解决了什么核心痛点\n3. 适合谁用以及如何快速上手（3句话以内）\n请使用 Markdown 格式。",
-        repo.author, repo.name, repo.description, repo.language, readme_prompt, extra_context
-    );
+    let mut answer = String::new();
+    let mut reasoning = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        answer.push_str(&rest[..start]);
+        let after = &rest[start + OPEN.len()..];
+        match after.find(CLOSE) {
+            Some(end) => {
+                reasoning.push(after[..end].trim().to_string());
+                rest = &after[end + CLOSE.len()..];
+            }
+            None => {
+                // 未闭合（输出被截断），剩余部分都视为思考过程
+                reasoning.push(after.trim().to_string());
+                rest = "";
+            }
+        }
+    }
+    answer.push_str(rest);
 
-    let messages = vec![
-        ChatMessage::system("你是一个资深的软件架构师和技术布道者，擅长简明扼要地总结技术项目。"),
-        ChatMessage::user(&prompt),
-    ];
+    let reasoning = reasoning.join("\n\n");
+    let reasoning = if reasoning.trim().is_empty() { None } else { Some(reasoning) };
+    (answer.trim().to_string(), reasoning)
+}
 
-    // 确定使用哪种模式
-    let result = if let Some(config_id) = model_config_id {
-        summarize_and_cache(config_id, messages, on_event, &config_manager, &repo, &app_handle).await
-    } else if let Some(api_key) = api_key {
-        // 旧模式暂不支持缓存，保持原有逻辑
-        summarize_with_api_key(api_key, messages, on_event).await
-    } else {
-        return Err("必须提供 API Key 或模型配置 ID".to_string());
+/// 生成总结；开启高质量模式时会进行一次自检，不达标则用更明确的提示词重试一次
+///
+/// 返回总结与各次请求（含自检）合计的使用量。
+async fn generate_summary(
+    provider: &dyn LLMProvider,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    high_quality: bool,
+    on_event: &Channel<StreamPayload>,
+) -> Result<(String, Option<Usage>), CommandError> {
+    let (summary, mut usage) = stream_completion(provider, model, messages.clone(), on_event).await?;
+    if !high_quality {
+        return Ok((summary, usage));
+    }
+
+    let (score, rating_usage) = rate_summary(provider, model, &summary).await;
+    add_usage(&mut usage, rating_usage);
+    let score = match score {
+        Some(score) => score,
+        None => return Ok((summary, usage)), // 自检失败不影响已生成的结果
     };
+    if score >= QUALITY_PASS_SCORE {
+        return Ok((summary, usage));
+    }
+
+    log::info!("总结自检得分 {}，使用更明确的提示词重新生成", score);
+    let mut retry_messages = messages;
+    retry_messages.push(ChatMessage::assistant(&summary));
+    retry_messages.push(ChatMessage::user(
+        "上面的总结不够完整。请重新输出一份完整的总结，必须使用 Markdown 二级标题分别覆盖以下每个维度，且每个维度都要有具体内容：\n1. 核心技术架构\n2. 解决了什么核心痛点\n3. 适合谁用以及如何快速上手（3句话以内）",
+    ));
 
-    result.map_err(|e| e.to_string())
+    let _ = on_event.send(StreamPayload::Reset);
+    let (summary, retry_usage) = stream_completion(provider, model, retry_messages, on_event).await?;
+    add_usage(&mut usage, retry_usage);
+    Ok((summary, usage))
 }
 
-/// 专门用于带缓存的总结逻辑
-async fn summarize_and_cache(
-    config_id: String,
-    messages: Vec<ChatMessage>,
-    on_event: Channel<StreamPayload>,
-    config_manager: &tauri::State<'_, ConfigManagerState>,
-    repo: &RepoInfo,
-    app_handle: &tauri::AppHandle,
-) -> Result<(), String> {
-    let manager_lock = config_manager.lock().await;
-    let configs = manager_lock.get_all_model_configs().await.map_err(|e| e.to_string())?;
-    let config = configs.iter().find(|c| c.id == config_id).ok_or_else(|| format!("找不到模型配置: {}", config_id))?;
-    let provider = LLMFactory::create_provider(config).map_err(|e| e.to_string())?;
+/// 质量自检的及格分（满分 10）
+const QUALITY_PASS_SCORE: u32 = 7;
 
-    let response = provider.chat_completion(messages, &config.default_model, true)
-        .await
-        .map_err(|e| e.to_string())?;
+/// 调用模型为总结的完整度打分（1-10），返回分数（失败时为 None）与使用量
+async fn rate_summary(provider: &dyn LLMProvider, model: &str, summary: &str) -> (Option<u32>, Option<Usage>) {
+    let prompt = format!(
+        "请评估下面这份 GitHub 项目总结是否完整覆盖了以下维度：\n1. 核心技术架构\n2. 解决了什么核心痛点\n3. 适合谁用以及如何快速上手\n\n只返回一个 1 到 10 的整数分数，不要输出其他内容。\n\n总结：\n---\n{}\n---",
+        summary
+    );
+    match provider.chat_completion(vec![ChatMessage::user(&prompt)], model, false).await {
+        Ok(LLMResponse::Completion { content, usage, .. }) => (parse_score(&content), usage),
+        _ => (None, None),
+    }
+}
 
-    let mut full_insight = String::new();
+/// 从模型回复中提取第一个整数分数
+fn parse_score(text: &str) -> Option<u32> {
+    let digits: String = text
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse::<u32>().ok().filter(|score| *score <= 10)
+}
+
+/// 执行一次流式补全，将文本块转发到前端并返回完整内容与使用量（不发送 Done）
+async fn stream_completion(
+    provider: &dyn LLMProvider,
+    model: &str,
+    messages: Vec<ChatMessage>,
+    on_event: &Channel<StreamPayload>,
+) -> Result<(String, Option<Usage>), CommandError> {
+    let response = provider.chat_completion(messages, model, true).await?;
 
     match response {
-        LLMResponse::Completion { content, .. } => {
+        LLMResponse::Completion { content, usage, .. } => {
             let _ = on_event.send(StreamPayload::Token(content.clone()));
-            let _ = on_event.send(StreamPayload::Done);
-            save_cache(repo, &content, app_handle).await;
-            Ok(())
+            Ok((content, usage))
         }
+        LLMResponse::ToolCalls { .. } => Err(CommandError::new(ErrorKind::Parse, "预期文本响应，但收到工具调用")),
         LLMResponse::Stream { mut stream } => {
+            let mut full_text = String::new();
+            let mut usage = None;
             while let Some(chunk) = stream.recv().await {
                 match chunk {
                     StreamChunk::Text(text) => {
-                        full_insight.push_str(&text);
+                        full_text.push_str(&text);
                         let _ = on_event.send(StreamPayload::Token(text));
                     }
                     StreamChunk::Error(err) => {
                         let _ = on_event.send(StreamPayload::Error(err));
-                        return Err("流式响应错误".to_string());
+                        return Err(CommandError::network("流式响应错误"));
                     }
-                    StreamChunk::Done => {
-                        let _ = on_event.send(StreamPayload::Done);
-                        save_cache(repo, &full_insight, app_handle).await;
-                        break;
+                    StreamChunk::Reasoning(text) => {
+                        let _ = on_event.send(StreamPayload::Reasoning(text));
                     }
+                    StreamChunk::Usage(reported) => usage = Some(reported),
+                    StreamChunk::Done => break,
                 }
             }
-            Ok(())
+            Ok((full_text, usage))
         }
     }
 }
@@ -162,63 +1418,134 @@ async fn summarize_and_cache(
 #[tauri::command]
 pub async fn get_cached_insight(
     repo: RepoInfo,
+    length: Option<SummaryLength>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
     app_handle: tauri::AppHandle,
 ) -> Result<Option<String>, String> {
-    Ok(get_cached_insight_internal(&repo, &app_handle).await)
+    let settings = insight_settings(&config_manager).await;
+    if !settings.cache_insights {
+        return Ok(None);
+    }
+    Ok(get_cached_insight_internal(&repo, length.unwrap_or_default(), &settings, &app_handle).await)
 }
 
 /// 批量检查仓库是否已有本地洞察
+///
+/// 只返回存在未过期洞察的仓库地址；需要篇幅与生成时间时使用 `check_insights_map`。
 #[tauri::command]
 pub async fn check_insights_batch(
     repos: Vec<RepoInfo>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
     app_handle: tauri::AppHandle,
 ) -> Result<Vec<String>, String> {
-    let mut exists = Vec::new();
-    for repo in repos {
-        if let Some(path) = get_cache_path(&repo, &app_handle).await {
-            if path.exists() {
-                exists.push(repo.url);
-            }
-        }
+    let settings = insight_settings(&config_manager).await;
+    if !settings.cache_insights {
+        return Ok(Vec::new());
     }
+    let now = chrono::Utc::now().timestamp();
+    let exists = lookup_cached_insights(&repos, &settings.summary_language, &app_handle)
+        .await
+        .into_iter()
+        .filter(|insight| insight.length == SummaryLength::Medium)
+        .filter(|insight| !crate::insight_cache::is_stale(insight.generated_at, now, settings.insight_ttl_days))
+        .map(|insight| insight.repo_url)
+        .collect();
     Ok(exists)
 }
 
-async fn get_cached_insight_internal(repo: &RepoInfo, app_handle: &tauri::AppHandle) -> Option<String> {
-    let cache_path = get_cache_path(repo, app_handle).await?;
-    if cache_path.exists() {
-        fs::read_to_string(cache_path).ok()
+/// 单个仓库的洞察缓存状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InsightStatus {
+    /// 是否已有默认篇幅的洞察
+    pub cached: bool,
+    /// 已缓存的所有篇幅
+    pub lengths: Vec<SummaryLength>,
+    /// 默认篇幅洞察的生成时间（Unix 秒）
+    pub generated_at: Option<i64>,
+    /// 距生成已过去的天数
+    pub age_days: Option<u64>,
+    /// 默认篇幅洞察是否已超过有效期（`insight_ttl_days`），读取时会重新生成
+    pub stale: bool,
+}
+
+/// 批量获取仓库的洞察状态，按仓库地址索引，便于前端直接对应到列表项
+#[tauri::command]
+pub async fn check_insights_map(
+    repos: Vec<RepoInfo>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<HashMap<String, InsightStatus>, String> {
+    let settings = insight_settings(&config_manager).await;
+    let cached = if settings.cache_insights {
+        lookup_cached_insights(&repos, &settings.summary_language, &app_handle).await
     } else {
-        None
+        Vec::new()
+    };
+    let now = chrono::Utc::now().timestamp();
+    let mut statuses: HashMap<String, InsightStatus> = repos
+        .into_iter()
+        .map(|repo| (repo.url, InsightStatus::default()))
+        .collect();
+    for insight in cached {
+        let Some(status) = statuses.get_mut(&insight.repo_url) else {
+            continue;
+        };
+        status.lengths.push(insight.length);
+        if insight.length == SummaryLength::Medium {
+            status.cached = true;
+            status.generated_at = Some(insight.generated_at);
+            status.age_days = Some((now - insight.generated_at).max(0) as u64 / (24 * 60 * 60));
+            status.stale = crate::insight_cache::is_stale(insight.generated_at, now, settings.insight_ttl_days);
+        }
     }
+    for status in statuses.values_mut() {
+        status.lengths.sort_by_key(|length| SummaryLength::ALL.iter().position(|l| l == length));
+    }
+    Ok(statuses)
 }
 
-async fn save_cache(repo: &RepoInfo, content: &str, app_handle: &tauri::AppHandle) {
-    let trimmed_content = content.trim();
-    if trimmed_content.is_empty() || trimmed_content.len() < 10 {
-        return; // 不缓存过短或空的内容
+/// 一次查询批量获取仓库的主缓存，旧文件缓存先迁移到数据库
+async fn lookup_cached_insights(repos: &[RepoInfo], language: &str, app_handle: &tauri::AppHandle) -> Vec<crate::insight_cache::CachedInsight> {
+    let Some(pool) = app_handle.try_state::<DbState>() else {
+        return Vec::new();
+    };
+    for repo in repos {
+        crate::insight_cache::import_legacy(&pool, app_handle, &repo.url, &repo.author, &repo.name).await;
     }
+    let urls: Vec<String> = repos.iter().map(|repo| repo.url.clone()).collect();
+    crate::insight_cache::cached_insights(&pool, &urls, language).await
+}
 
-    if let Some(cache_path) = get_cache_path(repo, app_handle).await {
-        if let Some(parent) = cache_path.parent() {
-            let _ = fs::create_dir_all(parent);
-        }
-        if let Err(e) = fs::write(&cache_path, content) {
-            eprintln!("Failed to save cache to {:?}: {}", cache_path, e);
-        }
-    }
+/// 读取洞察缓存相关的设置（是否启用、有效期、输出语言）
+async fn insight_settings(config_manager: &tauri::State<'_, ConfigManagerState>) -> AppSettings {
+    config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default()
 }
 
-async fn get_cache_path(repo: &RepoInfo, app_handle: &tauri::AppHandle) -> Option<PathBuf> {
-    let mut path = app_handle.path().app_data_dir().ok()?;
-    path.push("ai_insights");
-    
+/// 读取仓库某个篇幅、当前输出语言的缓存洞察，超过 `insight_ttl_days` 的视为未命中（数据库不可用时视为未缓存）
+async fn get_cached_insight_internal(repo: &RepoInfo, length: SummaryLength, settings: &AppSettings, app_handle: &tauri::AppHandle) -> Option<String> {
+    let pool = app_handle.try_state::<DbState>()?;
+    crate::insight_cache::import_legacy(&pool, app_handle, &repo.url, &repo.author, &repo.name).await;
+    let key = InsightKey::primary(&repo.url, length, &settings.summary_language);
+    crate::insight_cache::load(&pool, &key, settings.insight_ttl_days).await
+}
+
+/// 仓库是否已有某个篇幅、当前输出语言的未过期缓存洞察（不记录访问）
+async fn has_cached_insight(repo: &RepoInfo, length: SummaryLength, settings: &AppSettings, app_handle: &tauri::AppHandle) -> bool {
+    let Some(pool) = app_handle.try_state::<DbState>() else {
+        return false;
+    };
+    crate::insight_cache::import_legacy(&pool, app_handle, &repo.url, &repo.author, &repo.name).await;
+    let key = InsightKey::primary(&repo.url, length, &settings.summary_language);
+    crate::insight_cache::exists(&pool, &key, settings.insight_ttl_days).await
+}
+
+/// 旧版本洞察缓存的文件名（不同篇幅分别缓存），仅用于迁移旧缓存
+pub(crate) fn cache_file_name(author: &str, name: &str, length: SummaryLength) -> String {
     // 清理并标准化文件名以避免特殊字符或大小写带来的不匹配
-    let author_clean = sanitize_filename(&repo.author);
-    let name_clean = sanitize_filename(&repo.name);
-    
-    path.push(format!("{}_{}.md", author_clean, name_clean));
-    Some(path)
+    format!("{}_{}{}.md", sanitize_filename(author), sanitize_filename(name), length.cache_suffix())
 }
 
 fn sanitize_filename(name: &str) -> String {
@@ -230,68 +1557,266 @@ fn sanitize_filename(name: &str) -> String {
 
 /// 使用直接提供的 API Key 进行总结（向后兼容）
 async fn summarize_with_api_key(
-    api_key: String,
+    api_key: &str,
     messages: Vec<ChatMessage>,
+    request: &SummaryRequest,
     on_event: Channel<StreamPayload>,
-) -> Result<(), String> {
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+) -> Result<String, CommandError> {
+    let SummaryRequest { options, cancel, .. } = request;
     // 创建临时的 OpenAI 配置
     use crate::models::{ModelConfig, ModelProvider};
 
-    let config = ModelConfig::new(
+    let mut config = ModelConfig::new(
         "临时 OpenAI 配置".to_string(),
         ModelProvider::OpenAI,
         ModelProvider::OpenAI.default_api_base_url(),
-        api_key,
+        api_key.to_string(),
         ModelProvider::OpenAI.default_model_name(),
     );
+    config.max_tokens = options.length.max_tokens();
 
     // 创建 LLM 提供商
-    let provider = LLMFactory::create_provider(&config)
+    let mut provider = LLMFactory::create_provider(&config)?;
+    if let Some(token) = &cancel {
+        provider.set_cancellation_token(token.clone());
+    }
+    let provider = crate::audit::wrap_provider(provider, &config, &*config_manager.lock().await).await;
+
+    let (summary, usage) = generate_summary(provider.as_ref(), &config.default_model, messages, options.high_quality, &on_event).await?;
+    ensure_not_cancelled(cancel.as_ref())?;
+    send_usage(&on_event, usage);
+    let _ = on_event.send(StreamPayload::Done);
+    Ok(summary)
+}
+
+/// 为仓库生成一句话标语
+///
+/// 已收藏的仓库会缓存到 `repos.tagline`，仓库描述变化后自动重新生成。
+#[tauri::command]
+pub async fn generate_tagline(
+    repo: RepoInfo,
+    model_config_id: String,
+    force_refresh: Option<bool>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    db: tauri::State<'_, DbState>,
+) -> Result<String, String> {
+    if !force_refresh.unwrap_or(false) {
+        if let Some(cached) = crate::db::get_cached_tagline(db.inner(), &repo.url, &repo.description).await {
+            return Ok(cached);
+        }
+    }
+
+    crate::offline::ensure_online()?;
+    let tagline = request_tagline(&repo, &model_config_id, &config_manager).await?;
+    crate::db::save_tagline(db.inner(), &repo.url, &tagline).await?;
+    Ok(tagline)
+}
+
+/// 批量生成的单条标语
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedTagline {
+    pub url: String,
+    pub tagline: String,
+}
+
+/// 为所有尚无标语的收藏批量生成标语，单个仓库失败不会中断批次
+#[tauri::command]
+pub async fn generate_favorite_taglines(
+    model_config_id: String,
+    force_refresh: Option<bool>,
+    on_progress: Channel<BatchProgress>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    db: tauri::State<'_, DbState>,
+    maintenance: tauri::State<'_, crate::db::DbMaintenanceLock>,
+) -> Result<BatchReport<GeneratedTagline>, String> {
+    crate::offline::ensure_online()?;
+    let _guard = maintenance.0.read().await;
+    let sql = if force_refresh.unwrap_or(false) {
+        "SELECT author, name, COALESCE(description, ''), COALESCE(language, ''), url FROM repos"
+    } else {
+        "SELECT author, name, COALESCE(description, ''), COALESCE(language, ''), url FROM repos WHERE COALESCE(tagline, '') = ''"
+    };
+    let rows: Vec<(String, String, String, String, String)> = sqlx::query_as(sql)
+        .fetch_all(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut recorder = BatchRecorder::new(rows.len(), Some(&on_progress));
+    for (author, name, description, language, url) in rows {
+        let repo = RepoInfo { author, name, description, language, url, stars: None, forks: None };
+        let result = match request_tagline(&repo, &model_config_id, &config_manager).await {
+            Ok(tagline) => crate::db::save_tagline(db.inner(), &repo.url, &tagline)
+                .await
+                .map(|_| GeneratedTagline { url: repo.url.clone(), tagline }),
+            Err(e) => Err(e),
+        };
+        recorder.record(&repo.url, result);
+    }
+    Ok(recorder.finish())
+}
+
+/// 调用模型生成一句话标语
+async fn request_tagline(
+    repo: &RepoInfo,
+    config_id: &str,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+) -> Result<String, String> {
+    let manager = config_manager.lock().await;
+    let configs = manager.get_all_model_configs().await.map_err(|e| e.to_string())?;
+    let config = configs.iter().find(|c| c.id == config_id).ok_or_else(|| tr(ErrorCode::ModelConfigNotFound, &[&config_id]))?;
+    let provider = LLMFactory::create_provider(config).map_err(|e| e.to_string())?;
+    let provider = crate::audit::wrap_provider(provider, config, &manager).await;
+
+    let prompt = format!(
+        "请为以下 GitHub 项目写一句简短有力的中文标语（不超过 30 个字），突出它最大的亮点，不要照抄原描述，只返回标语本身：\n项目：{}/{}\n描述：{}\n语言：{}",
+        repo.author, repo.name, repo.description, repo.language
+    );
+    let response = provider.chat_completion(vec![ChatMessage::user(&prompt)], &config.default_model, false)
+        .await
         .map_err(|e| e.to_string())?;
 
-    // 执行聊天补全（流式）
+    match response {
+        LLMResponse::Completion { content, .. } => {
+            Ok(content.trim().trim_matches(|c| c == '"' || c == '“' || c == '”').trim().to_string())
+        }
+        LLMResponse::Stream { .. } => Err("预期非流式响应，但收到流式响应".to_string()),
+        LLMResponse::ToolCalls { .. } => Err("预期文本响应，但收到工具调用".to_string()),
+    }
+}
+
+/// 测试模型连接：发送一次简短对话，返回是否正常回复及延迟
+#[tauri::command]
+pub async fn test_model_connection(
+    model_config_id: String,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<ConnectionReport, CommandError> {
+    crate::offline::ensure_online().map_err(CommandError::network)?;
+    let manager = config_manager.lock().await;
+    let config = find_model_config(&manager, &model_config_id).await?;
+    drop(manager);
+    let provider = LLMFactory::create_probe_provider(&config)?;
+    Ok(provider.ping(&config.default_model).await?)
+}
+
+/// 按 ID 查找模型配置
+pub(crate) async fn find_model_config(manager: &crate::config::ConfigManager, config_id: &str) -> Result<crate::models::ModelConfig, CommandError> {
+    let configs = manager.get_all_model_configs().await.map_err(|e| CommandError::config(e.to_string()))?;
+    configs
+        .into_iter()
+        .find(|c| c.id == config_id)
+        .ok_or_else(|| CommandError::not_found(tr(ErrorCode::ModelConfigNotFound, &[&config_id])))
+}
+
+/// 流式输出测试报告
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StreamingTestReport {
+    /// 收到的文本块数量
+    pub chunks: usize,
+    /// 内容互不相同的文本块数量
+    pub distinct_chunks: usize,
+    /// 首个文本块到达耗时（毫秒）
+    pub first_chunk_ms: Option<u64>,
+    /// 总耗时（毫秒）
+    pub total_ms: u64,
+    /// 是否收到结束信号
+    pub received_done: bool,
+    pub passed: bool,
+    /// 未通过时的原因
+    pub issue: Option<String>,
+}
+
+impl StreamingTestReport {
+    /// 根据收到的内容判断流式输出是否正常
+    fn evaluate(&mut self) {
+        self.issue = if self.chunks == 0 {
+            Some("没有收到任何文本块".to_string())
+        } else if self.distinct_chunks < 2 {
+            Some("只收到一个文本块，网关可能缓冲了完整响应后一次性返回".to_string())
+        } else if !self.received_done {
+            Some("没有收到结束信号，SSE 流可能被中断".to_string())
+        } else {
+            None
+        };
+        self.passed = self.issue.is_none();
+    }
+}
+
+/// 流式测试的最长等待时间
+const STREAMING_TEST_TIMEOUT_SECS: u64 = 60;
+
+/// 测试模型的流式输出：至少收到两个不同的文本块并以结束信号收尾才算通过
+#[tauri::command]
+pub async fn test_streaming(
+    model_config_id: String,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<StreamingTestReport, String> {
+    crate::offline::ensure_online()?;
+    let config = {
+        let manager = config_manager.lock().await;
+        let configs = manager.get_all_model_configs().await.map_err(|e| e.to_string())?;
+        configs.into_iter().find(|c| c.id == model_config_id).ok_or_else(|| tr(ErrorCode::ModelConfigNotFound, &[&model_config_id]))?
+    };
+    let provider = LLMFactory::create_provider(&config).map_err(|e| e.to_string())?;
+
+    let messages = vec![ChatMessage::user("请从 1 数到 10，每行一个数字，不要输出其他内容。")];
+    let started = std::time::Instant::now();
     let response = provider.chat_completion(messages, &config.default_model, true)
         .await
         .map_err(|e| e.to_string())?;
 
+    let mut report = StreamingTestReport::default();
     match response {
         LLMResponse::Completion { content, .. } => {
-            let _ = on_event.send(StreamPayload::Token(content));
-            let _ = on_event.send(StreamPayload::Done);
-            Ok(())
+            // 提供商直接返回了完整响应，视为不支持流式
+            report.chunks = 1;
+            report.distinct_chunks = 1;
+            report.first_chunk_ms = Some(started.elapsed().as_millis() as u64);
+            report.received_done = !content.is_empty();
         }
+        LLMResponse::ToolCalls { .. } => report.issue = Some("预期文本响应，但收到工具调用".to_string()),
         LLMResponse::Stream { mut stream } => {
-            while let Some(chunk) = stream.recv().await {
+            let mut seen = std::collections::HashSet::new();
+            let deadline = tokio::time::sleep(std::time::Duration::from_secs(STREAMING_TEST_TIMEOUT_SECS));
+            tokio::pin!(deadline);
+            loop {
+                let chunk = tokio::select! {
+                    chunk = stream.recv() => chunk,
+                    _ = &mut deadline => {
+                        report.issue = Some("等待流式响应超时".to_string());
+                        break;
+                    }
+                };
                 match chunk {
-                    StreamChunk::Text(text) => {
-                        let _ = on_event.send(StreamPayload::Token(text));
+                    Some(StreamChunk::Text(text)) => {
+                        if report.first_chunk_ms.is_none() {
+                            report.first_chunk_ms = Some(started.elapsed().as_millis() as u64);
+                        }
+                        report.chunks += 1;
+                        seen.insert(text);
                     }
-                    StreamChunk::Error(err) => {
-                        let _ = on_event.send(StreamPayload::Error(err));
-                        return Err("流式响应错误".to_string());
+                    Some(StreamChunk::Error(err)) => {
+                        report.total_ms = started.elapsed().as_millis() as u64;
+                        report.distinct_chunks = seen.len();
+                        report.issue = Some(format!("流式响应错误: {}", err));
+                        return Ok(report);
                     }
-                    StreamChunk::Done => {
-                        let _ = on_event.send(StreamPayload::Done);
+                    Some(StreamChunk::Reasoning(_)) | Some(StreamChunk::Usage(_)) => {}
+                    Some(StreamChunk::Done) => {
+                        report.received_done = true;
                         break;
                     }
+                    None => break,
                 }
             }
-            Ok(())
+            report.distinct_chunks = seen.len();
         }
     }
-}
-
-/// 测试模型连接
-#[tauri::command]
-pub async fn test_model_connection(
-    model_config_id: String,
-    config_manager: tauri::State<'_, ConfigManagerState>,
-) -> Result<(), String> {
-    let manager = config_manager.lock().await;
-    let configs = manager.get_all_model_configs().await.map_err(|e| e.to_string())?;
-    let config = configs.iter().find(|c| c.id == model_config_id).ok_or_else(|| format!("找不到模型配置: {}", model_config_id))?;
-    let provider = LLMFactory::create_provider(config).map_err(|e| e.to_string())?;
-    provider.test_connection().await.map_err(|e| e.to_string())
+    report.total_ms = started.elapsed().as_millis() as u64;
+    if report.issue.is_none() {
+        report.evaluate();
+    }
+    Ok(report)
 }
 
 /// 获取模型列表
@@ -299,47 +1824,123 @@ pub async fn test_model_connection(
 pub async fn list_models(
     model_config_id: String,
     config_manager: tauri::State<'_, ConfigManagerState>,
-) -> Result<Vec<crate::models::ModelInfo>, String> {
+) -> Result<Vec<crate::models::ModelInfo>, CommandError> {
     let manager = config_manager.lock().await;
-    let configs = manager.get_all_model_configs().await.map_err(|e| e.to_string())?;
-    let config = configs.iter().find(|c| c.id == model_config_id).ok_or_else(|| format!("找不到模型配置: {}", model_config_id))?;
-    let provider = LLMFactory::create_provider(config).map_err(|e| e.to_string())?;
-    provider.list_models().await.map_err(|e| e.to_string())
+    let config = find_model_config(&manager, &model_config_id).await?;
+    // 离线模式下返回缓存的模型列表
+    if crate::offline::is_offline() {
+        return match manager.get_cached_models(&config.provider).await {
+            Ok(Some(models)) => Ok(models),
+            _ => Err(CommandError::network(tr(ErrorCode::OfflineMode, &[]))),
+        };
+    }
+    let provider = LLMFactory::create_provider(&config)?;
+    let models = provider.list_models().await.map_err(CommandError::from)?;
+    // 缓存模型列表，供深度模式按上下文窗口计算预算
+    if let Err(e) = manager.update_model_cache(&config.provider, models.clone(), MODEL_CACHE_HOURS).await {
+        log::warn!("缓存模型列表失败: {}", e);
+    }
+    Ok(models)
+}
+
+/// 模型列表缓存有效期（小时）
+const MODEL_CACHE_HOURS: i64 = 24;
+
+/// 仓库根目录条目
+pub(crate) struct RootEntry {
+    pub name: String,
+    pub is_dir: bool,
 }
 
-/// 获取 GitHub 仓库的文件树结构
-async fn fetch_tree(author: &str, name: &str) -> Option<String> {
-    let client = reqwest::Client::builder().user_agent("github-capture").build().ok()?;
+/// 获取 GitHub 仓库根目录的条目列表
+pub(crate) async fn fetch_root_entries(host: &GitHubHost, author: &str, name: &str) -> Option<Vec<RootEntry>> {
+    let client = crate::github::http_client();
     
     // 我们先尝试获取默认分支的 1 层深度目录
-    let url = format!("https://api.github.com/repos/{}/{}/contents/", author, name);
-    if let Ok(resp) = client.get(&url).send().await {
-        if let Ok(items) = resp.json::<Vec<serde_json::Value>>().await {
-            let mut tree = String::new();
-            for (i, item) in items.iter().take(50).enumerate() {
-                let name = item["name"].as_str().unwrap_or("");
-                let kind = if item["type"] == "dir" { "[DIR]" } else { "[FILE]" };
-                tree.push_str(&format!("{} {}\n", kind, name));
-                if i >= 49 { tree.push_str("... (已省略更多文件)"); }
-            }
-            return Some(tree);
+    let url = format!("{}/repos/{}/{}/contents/", host.api_base(), author, name);
+    let resp = crate::github::send_with_retry(host.authorize(client.get(&url))).await.ok()?;
+    let items = resp.json::<Vec<serde_json::Value>>().await.ok()?;
+    Some(items.iter().map(|item| RootEntry {
+        name: item["name"].as_str().unwrap_or("").to_string(),
+        is_dir: item["type"] == "dir",
+    }).collect())
+}
+
+/// 将根目录条目格式化为文件树文本
+fn format_tree(entries: &[RootEntry]) -> String {
+    let mut tree = String::new();
+    for (i, entry) in entries.iter().take(50).enumerate() {
+        let kind = if entry.is_dir { "[DIR]" } else { "[FILE]" };
+        tree.push_str(&format!("{} {}\n", kind, entry.name));
+        if i >= 49 { tree.push_str("... (已省略更多文件)"); }
+    }
+    tree
+}
+
+/// 根据根目录中存在的文件推断项目所属的技术生态
+fn detect_ecosystems(files: &[String]) -> Vec<&'static str> {
+    const MARKERS: &[(&str, &str)] = &[
+        ("package.json", "Node.js"),
+        ("Cargo.toml", "Rust"),
+        ("go.mod", "Go"),
+        ("requirements.txt", "Python"),
+        ("pyproject.toml", "Python"),
+        ("pom.xml", "Java"),
+        ("build.gradle", "Java"),
+        ("build.gradle.kts", "Java"),
+        ("Gemfile", "Ruby"),
+        ("composer.json", "PHP"),
+        ("pubspec.yaml", "Dart"),
+        ("deno.json", "Deno"),
+        ("Dockerfile", "Docker"),
+        ("docker-compose.yml", "Docker"),
+    ];
+
+    let mut ecosystems = Vec::new();
+    for (file, ecosystem) in MARKERS {
+        if files.iter().any(|f| f == file) && !ecosystems.contains(ecosystem) {
+            ecosystems.push(*ecosystem);
         }
     }
-    None
+    ecosystems
 }
 
 /// 获取单个文件的原始内容，可选限制长度
-async fn fetch_file_content(author: &str, name: &str, path: &str, limit: Option<usize>) -> Option<String> {
-    let client = reqwest::Client::builder().user_agent("github-capture").build().ok()?;
-    let urls = [
-        format!("https://raw.githubusercontent.com/{}/{}/refs/heads/main/{}", author, name, path),
-        format!("https://raw.githubusercontent.com/{}/{}/refs/heads/master/{}", author, name, path),
-    ];
+pub(crate) async fn fetch_file_content(
+    host: &GitHubHost,
+    author: &str,
+    name: &str,
+    path: &str,
+    limit: Option<usize>,
+    budget: &mut CallBudget,
+) -> Option<String> {
+    let client = crate::github::http_client();
+
+    // 同一仓库的默认分支只解析一次；无法解析时回退到 main/master
+    let branch = match crate::github::cached_default_branch(host, author, name) {
+        Some(branch) => branch,
+        None if budget.try_take() => crate::github::resolve_default_branch(host, author, name).await,
+        None => None,
+    };
+    let urls: Vec<String> = match branch {
+        Some(branch) => vec![host.raw_file_url(author, name, &branch, path)],
+        None => ["main", "master"].iter().map(|b| host.raw_file_url(author, name, b, path)).collect(),
+    };
 
     for url in urls {
-        if let Ok(resp) = client.get(&url).send().await {
+        if !budget.try_take() {
+            break;
+        }
+        if let Ok(resp) = crate::github::send_with_retry(host.authorize(client.get(&url))).await {
             if resp.status().is_success() {
-                if let Ok(text) = resp.text().await {
+                let content_type = resp
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_string());
+                if let Ok(bytes) = resp.bytes().await {
+                    // 二进制文件直接放弃，避免把乱码喂给模型
+                    let text = decode_text(&bytes, content_type.as_deref())?;
                     return Some(match limit {
                         Some(l) => text.chars().take(l).collect(),
                         None => text
@@ -351,11 +1952,211 @@ async fn fetch_file_content(author: &str, name: &str, path: &str, limit: Option<
     None
 }
 
+/// 按响应声明的字符集解码文件内容；二进制内容返回 None
+///
+/// raw.githubusercontent.com 总是声明 utf-8，因此声明与实际不符时，
+/// 会依次尝试 BOM 和 GB18030，最后退回有损 UTF-8 解码。
+fn decode_text(bytes: &[u8], content_type: Option<&str>) -> Option<String> {
+    if bytes.iter().take(8000).any(|b| *b == 0) {
+        return None;
+    }
+
+    if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Some(text.into_owned());
+    }
+
+    let declared = content_type
+        .and_then(|ct| ct.split(';').find_map(|part| part.trim().strip_prefix("charset=")))
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim_matches('"').as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+
+    if let Some(text) = declared.decode_without_bom_handling_and_without_replacement(bytes) {
+        return Some(text.into_owned());
+    }
+    if let Some(text) = encoding_rs::GB18030.decode_without_bom_handling_and_without_replacement(bytes) {
+        return Some(text.into_owned());
+    }
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
 /// 尝试获取 GitHub 仓库的 README 内容
-async fn fetch_readme_with_limit(author: &str, name: &str, limit: Option<usize>) -> Option<String> {
-    fetch_file_content(author, name, "README.md", limit).await
+pub(crate) async fn fetch_readme_with_limit(host: &GitHubHost, author: &str, name: &str, limit: Option<usize>) -> Option<String> {
+    fetch_file_content(host, author, name, "README.md", limit, &mut CallBudget::unlimited()).await
+}
+
+pub(crate) async fn fetch_readme(host: &GitHubHost, author: &str, name: &str) -> Option<String> {
+    fetch_readme_with_limit(host, author, name, Some(1500)).await
 }
 
-async fn fetch_readme(author: &str, name: &str) -> Option<String> {
-    fetch_readme_with_limit(author, name, Some(1500)).await
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_cancellations() {
+        let cancellations = SummaryCancellations::default();
+        let first = cancellations.register("req-1");
+        // 同一 ID 重新发起时取消旧请求
+        let second = cancellations.register("req-1");
+        assert!(first.is_cancelled());
+        assert!(ensure_not_cancelled(Some(&second)).is_ok());
+
+        assert!(cancellations.cancel("req-1"));
+        assert!(second.is_cancelled());
+        assert_eq!(ensure_not_cancelled(Some(&second)).unwrap_err().kind, ErrorKind::Cancelled);
+        // 已结束的请求无法再取消
+        assert!(!cancellations.cancel("req-1"));
+        assert!(ensure_not_cancelled(None).is_ok());
+    }
+
+    #[test]
+    fn test_continuation_seam() {
+        let partial = "## 核心架构\n项目采用插件化设计，核心模块负责调度";
+        assert_eq!(trim_overlap(partial, "核心模块负责调度，插件通过 trait 注册。"), "，插件通过 trait 注册。");
+        assert_eq!(trim_overlap(partial, "，插件通过 trait 注册。"), "，插件通过 trait 注册。");
+        // 过短的重叠不视为重复
+        assert_eq!(trim_overlap("abc", "c and more"), "c and more");
+
+        let mut seam = SeamDeduper::new(partial);
+        assert_eq!(seam.push("核心模块负责调度"), None);
+        assert_eq!(seam.finish().as_deref(), Some(""));
+        assert_eq!(seam.push("后续内容"), Some("后续内容".to_string()));
+    }
+
+    #[test]
+    fn test_detect_ecosystems() {
+        let files = vec![
+            "pyproject.toml".to_string(),
+            "requirements.txt".to_string(),
+            "Dockerfile".to_string(),
+            "README.md".to_string(),
+        ];
+        assert_eq!(detect_ecosystems(&files), vec!["Python", "Docker"]);
+        assert!(detect_ecosystems(&["README.md".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_strip_think_blocks() {
+        let (answer, reasoning) = strip_think_blocks("<think>先分析 README</think>\n## 核心架构\n内容");
+        assert_eq!(answer, "## 核心架构\n内容");
+        assert_eq!(reasoning.as_deref(), Some("先分析 README"));
+
+        let (answer, reasoning) = strip_think_blocks("## 总结");
+        assert_eq!(answer, "## 总结");
+        assert!(reasoning.is_none());
+
+        let (answer, reasoning) = strip_think_blocks("<think>被截断的思考");
+        assert!(answer.is_empty());
+        assert_eq!(reasoning.as_deref(), Some("被截断的思考"));
+    }
+
+    #[test]
+    fn test_decode_text() {
+        assert_eq!(decode_text("你好".as_bytes(), Some("text/plain; charset=utf-8")).as_deref(), Some("你好"));
+        // GBK 编码的 "中文" 被错误声明为 utf-8
+        assert_eq!(decode_text(&[0xD6, 0xD0, 0xCE, 0xC4], Some("text/plain; charset=utf-8")).as_deref(), Some("中文"));
+        assert_eq!(decode_text(&[0xD6, 0xD0, 0xCE, 0xC4], Some("text/plain; charset=gbk")).as_deref(), Some("中文"));
+        assert_eq!(decode_text(&[0xEF, 0xBB, 0xBF, b'h', b'i'], None).as_deref(), Some("hi"));
+        assert!(decode_text(&[0x89, b'P', b'N', b'G', 0x00, 0x01], None).is_none());
+    }
+
+    #[test]
+    fn test_cache_file_name_per_length() {
+        assert_eq!(cache_file_name("Foo", "Bar.js", SummaryLength::Medium), "foo_barjs.md");
+        assert_eq!(cache_file_name("Foo", "Bar.js", SummaryLength::Short), "foo_barjs_short.md");
+        assert_eq!(cache_file_name("Foo", "Bar.js", SummaryLength::Long), "foo_barjs_long.md");
+    }
+
+    #[test]
+    fn test_streaming_report_evaluate() {
+        let mut buffered = StreamingTestReport { chunks: 1, distinct_chunks: 1, received_done: true, ..Default::default() };
+        buffered.evaluate();
+        assert!(!buffered.passed);
+
+        let mut healthy = StreamingTestReport { chunks: 10, distinct_chunks: 10, received_done: true, ..Default::default() };
+        healthy.evaluate();
+        assert!(healthy.passed);
+
+        let mut cut_off = StreamingTestReport { chunks: 5, distinct_chunks: 5, received_done: false, ..Default::default() };
+        cut_off.evaluate();
+        assert!(!cut_off.passed);
+    }
+
+    #[test]
+    fn test_config_snippet() {
+        let content = "x".repeat(2000);
+        assert_eq!(config_snippet(&content, 0).len(), CONTEXT_FILE_SNIPPET_CHARS);
+        assert_eq!(config_snippet(&content, 2500).len(), CONTEXT_FILES_TOTAL_CHARS - 2500);
+        assert_eq!(config_snippet(&content, CONTEXT_FILES_TOTAL_CHARS), "");
+        assert_eq!(config_snippet("[package]", 0), "[package]");
+    }
+
+    #[test]
+    fn test_summary_system_prompt() {
+        assert!(!summary_system_prompt(DEFAULT_SUMMARY_LANGUAGE).contains("Respond"));
+        assert!(summary_system_prompt("en").ends_with("\nRespond in English."));
+        assert!(summary_system_prompt("it").ends_with("ISO 639-1 code is \"it\"."));
+    }
+
+    #[test]
+    fn test_summary_prompt() {
+        let repo = RepoInfo {
+            author: "astral-sh".to_string(),
+            name: "uv".to_string(),
+            description: "Python 包管理器".to_string(),
+            language: "Rust".to_string(),
+            url: "https://github.com/astral-sh/uv".to_string(),
+            stars: None,
+            forks: None,
+        };
+        let context = SummaryContext { readme: "README".to_string(), tree: String::new(), extra: String::new() };
+        let settings = AppSettings::default();
+        let deep = summary_prompt(&repo, &context, true, SummaryLength::Long, &settings);
+        assert!(deep.contains("项目 README 内容（完整）"));
+        assert!(deep.ends_with(SummaryLength::Long.instructions()));
+        let short = summary_prompt(&repo, &context, false, SummaryLength::Short, &settings);
+        assert!(short.contains("项目 README 内容（片段）"));
+        assert!(short.ends_with(SummaryLength::Short.instructions()));
+    }
+
+    #[test]
+    fn test_render_prompt_template() {
+        let values = [("name", "uv"), ("readme", "Use {name} like {this}"), ("tree", "")];
+        assert_eq!(
+            render_prompt_template("Summarize {name}.\nREADME: {readme}\nTree: {tree}{unknown} {", &values),
+            "Summarize uv.\nREADME: Use {name} like {this}\nTree: {unknown} {"
+        );
+    }
+
+    #[test]
+    fn test_footer_round_trip() {
+        let footer = render_footer(crate::models::DEFAULT_FOOTER_TEMPLATE, "deepseek-chat", "deep mode", "2025-01-15");
+        let content = format!("## 总结{}", footer);
+        let (body, footer) = split_footer(&content);
+        assert_eq!(body, "## 总结");
+        assert_eq!(footer, Some("—Generated by deepseek-chat (deep mode) on 2025-01-15"));
+        assert_eq!(split_footer("## 总结"), ("## 总结", None));
+    }
+
+    #[test]
+    fn test_context_char_budget() {
+        use crate::models::ModelProvider;
+
+        let gemini = ModelProvider::Google.fallback_context_length("gemini-1.5-pro");
+        let haiku = ModelProvider::Anthropic.fallback_context_length("claude-3-haiku-20240307");
+        assert_eq!(gemini, 1_000_000);
+        assert_eq!(haiku, 200_000);
+        assert!(context_char_budget(gemini, 4096) > context_char_budget(haiku, 4096));
+        // 上下文过小时仍保留最低限度的 README
+        assert_eq!(context_char_budget(4096, 4096), 2000);
+    }
+
+    #[test]
+    fn test_parse_score() {
+        assert_eq!(parse_score("8"), Some(8));
+        assert_eq!(parse_score("评分：6/10"), Some(6));
+        assert_eq!(parse_score("no score"), None);
+        assert_eq!(parse_score("42"), None);
+    }
+}
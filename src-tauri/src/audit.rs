@@ -0,0 +1,322 @@
+//! LLM 请求审计日志
+//!
+//! 用户开启后，将发送给外部模型提供商的完整提示词与响应（密钥已脱敏）
+//! 以 JSON Lines 格式追加写入本地文件，超过大小上限后轮转。
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use crate::config::ConfigManager;
+use crate::llm::{ConnectionReport, LLMError, LLMProvider, LLMResponse, StreamChunk};
+use crate::models::{AppConfig, ChatMessage, ModelConfig, ModelInfo, ToolDef};
+
+/// 单个审计文件的大小上限，超过后轮转
+const MAX_AUDIT_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// 一条审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub config_id: String,
+    pub provider: String,
+    pub model: String,
+    pub stream: bool,
+    pub messages: Vec<ChatMessage>,
+    pub response: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 审计日志文件
+pub struct AuditLogger {
+    dir: PathBuf,
+}
+
+impl AuditLogger {
+    /// 基于应用数据目录创建审计日志
+    pub fn for_app(app_handle: &tauri::AppHandle) -> Option<Self> {
+        let dir = app_handle.path().app_data_dir().ok()?.join("audit");
+        Some(Self { dir })
+    }
+
+    fn current_path(&self) -> PathBuf {
+        self.dir.join("llm_audit.jsonl")
+    }
+
+    fn rotated_path(&self) -> PathBuf {
+        self.dir.join("llm_audit.1.jsonl")
+    }
+
+    /// 追加一条记录
+    pub fn record(&self, entry: &AuditEntry) {
+        if let Err(e) = self.try_record(entry) {
+            log::warn!("写入审计日志失败: {}", e);
+        }
+    }
+
+    fn try_record(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.current_path();
+        if fs::metadata(&path).map(|m| m.len() >= MAX_AUDIT_FILE_BYTES).unwrap_or(false) {
+            fs::rename(&path, self.rotated_path())?;
+        }
+
+        let line = serde_json::to_string(entry)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// 读取最近的记录（最新的在前）
+    pub fn read_recent(&self, limit: usize) -> Vec<AuditEntry> {
+        let mut entries: Vec<AuditEntry> = [self.rotated_path(), self.current_path()]
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .flat_map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        entries.reverse();
+        entries.truncate(limit);
+        entries
+    }
+
+    /// 清空全部审计记录
+    pub fn clear(&self) -> std::io::Result<()> {
+        for path in [self.current_path(), self.rotated_path()] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 如果用户开启了审计，则为提供商包装审计记录
+pub async fn wrap_provider(
+    provider: Box<dyn LLMProvider>,
+    config: &ModelConfig,
+    manager: &ConfigManager,
+) -> Box<dyn LLMProvider> {
+    let app_config = manager.load_config().await.unwrap_or_default();
+    if !app_config.settings.audit_llm_requests {
+        return provider;
+    }
+    // 未保存的临时配置不在列表中，单独加入其密钥
+    let mut secrets = config_secrets(&app_config);
+    secrets.push(config.api_key.clone());
+
+    match AuditLogger::for_app(manager.app_handle()) {
+        Some(logger) => Box::new(AuditedProvider {
+            inner: provider,
+            logger: Arc::new(logger),
+            config_id: config.id.clone(),
+            provider_name: config.provider.display_name(),
+            secrets,
+            buffer_size: crate::llm::stream_buffer_size(config),
+        }),
+        None => provider,
+    }
+}
+
+/// 记录请求与响应的提供商包装器
+struct AuditedProvider {
+    inner: Box<dyn LLMProvider>,
+    logger: Arc<AuditLogger>,
+    config_id: String,
+    provider_name: String,
+    secrets: Vec<String>,
+    buffer_size: usize,
+}
+
+impl AuditedProvider {
+    fn entry(&self, messages: &[ChatMessage], model: &str, stream: bool) -> AuditEntry {
+        AuditEntry {
+            timestamp: chrono::Utc::now(),
+            config_id: self.config_id.clone(),
+            provider: self.provider_name.clone(),
+            model: model.to_string(),
+            stream,
+            messages: messages
+                .iter()
+                .map(|m| ChatMessage::new(&m.role, &redact(&m.content, &self.secrets)))
+                .collect(),
+            response: None,
+            error: None,
+        }
+    }
+
+    /// 写入响应或错误；流式响应透传并在结束后写入
+    fn record_response(
+        &self,
+        mut entry: AuditEntry,
+        result: Result<LLMResponse, LLMError>,
+    ) -> Result<LLMResponse, LLMError> {
+        match result {
+            Ok(LLMResponse::Completion { content, model, usage }) => {
+                entry.response = Some(redact(&content, &self.secrets));
+                self.logger.record(&entry);
+                Ok(LLMResponse::Completion { content, model, usage })
+            }
+            Ok(LLMResponse::ToolCalls { calls, content, model, usage }) => {
+                let recorded = serde_json::to_string(&calls).unwrap_or_default();
+                entry.response = Some(redact(&recorded, &self.secrets));
+                self.logger.record(&entry);
+                Ok(LLMResponse::ToolCalls { calls, content, model, usage })
+            }
+            Ok(LLMResponse::Stream { mut stream }) => {
+                // 透传流式响应，同时累积完整文本，结束后写入审计记录
+                let (tx, rx) = mpsc::channel(self.buffer_size);
+                let logger = self.logger.clone();
+                let secrets = self.secrets.clone();
+
+                tokio::spawn(async move {
+                    let mut content = String::new();
+                    while let Some(chunk) = stream.recv().await {
+                        match &chunk {
+                            StreamChunk::Text(text) => content.push_str(text),
+                            StreamChunk::Error(err) => entry.error = Some(redact(err, &secrets)),
+                            StreamChunk::Reasoning(_) | StreamChunk::Usage(_) | StreamChunk::Done => {}
+                        }
+                        let done = matches!(chunk, StreamChunk::Done);
+                        let _ = tx.send(chunk).await;
+                        if done {
+                            break;
+                        }
+                    }
+                    entry.response = Some(redact(&content, &secrets));
+                    logger.record(&entry);
+                });
+
+                Ok(LLMResponse::Stream { stream: rx })
+            }
+            Err(e) => {
+                entry.error = Some(redact(&e.to_string(), &self.secrets));
+                self.logger.record(&entry);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LLMProvider for AuditedProvider {
+    async fn chat_completion(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        stream: bool,
+    ) -> Result<LLMResponse, LLMError> {
+        let entry = self.entry(&messages, model, stream);
+        let result = self.inner.chat_completion(messages, model, stream).await;
+        self.record_response(entry, result)
+    }
+
+    async fn chat_completion_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        tools: Vec<ToolDef>,
+    ) -> Result<LLMResponse, LLMError> {
+        let entry = self.entry(&messages, model, false);
+        let result = self.inner.chat_completion_with_tools(messages, model, tools).await;
+        self.record_response(entry, result)
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
+        self.inner.list_models().await
+    }
+
+    async fn ping(&self, model: &str) -> Result<ConnectionReport, LLMError> {
+        self.inner.ping(model).await
+    }
+
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.inner.set_cancellation_token(token);
+    }
+}
+
+/// 配置中的全部密钥：各模型配置的 API Key 与 GitHub 令牌
+pub(crate) fn config_secrets(config: &AppConfig) -> Vec<String> {
+    config
+        .model_configs
+        .iter()
+        .map(|c| c.api_key.clone())
+        .chain(config.settings.github_token.clone())
+        .filter(|secret| !secret.trim().is_empty())
+        .collect()
+}
+
+/// 常见密钥前缀，匹配到的长 token 一律脱敏
+const SECRET_PREFIXES: &[&str] = &["sk-", "ghp_", "gho_", "github_pat_", "AIza"];
+
+/// 脱敏：替换已知密钥以及形似密钥的 token
+pub(crate) fn redact(text: &str, secrets: &[String]) -> String {
+    let mut result = text.to_string();
+    for secret in secrets {
+        if !secret.is_empty() {
+            result = result.replace(secret.as_str(), "[REDACTED]");
+        }
+    }
+
+    let mut output = String::with_capacity(result.len());
+    let mut rest = result.as_str();
+    while let Some((start, prefix)) = SECRET_PREFIXES
+        .iter()
+        .filter_map(|p| rest.find(p).map(|i| (i, *p)))
+        .min_by_key(|(i, _)| *i)
+    {
+        let token_len = rest[start + prefix.len()..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len() - start - prefix.len());
+        output.push_str(&rest[..start]);
+        if token_len >= 16 {
+            output.push_str("[REDACTED]");
+        } else {
+            output.push_str(&rest[start..start + prefix.len() + token_len]);
+        }
+        rest = &rest[start + prefix.len() + token_len..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 读取最近的审计记录
+#[tauri::command]
+pub async fn get_audit_entries(
+    limit: Option<usize>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<AuditEntry>, String> {
+    let logger = AuditLogger::for_app(&app_handle).ok_or("无法定位应用数据目录")?;
+    Ok(logger.read_recent(limit.unwrap_or(50)))
+}
+
+/// 清空审计记录
+#[tauri::command]
+pub async fn clear_audit_log(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let logger = AuditLogger::for_app(&app_handle).ok_or("无法定位应用数据目录")?;
+    logger.clear().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let secrets = vec!["my-secret-key".to_string()];
+        assert_eq!(redact("key is my-secret-key.", &secrets), "key is [REDACTED].");
+        assert_eq!(
+            redact("token sk-abcdefghijklmnopqrstuvwxyz end", &[]),
+            "token [REDACTED] end"
+        );
+        // 过短的片段不视为密钥
+        assert_eq!(redact("use sk-learn here", &[]), "use sk-learn here");
+    }
+}
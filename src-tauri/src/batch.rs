@@ -0,0 +1,97 @@
+//! 批量任务的统一结果与进度事件
+//!
+//! 单项失败（仓库 404、模型报错等）只记录到失败列表，不会中断整个批次。
+
+use serde::Serialize;
+use tauri::ipc::Channel;
+
+/// 批量任务中单项失败的原因
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFailure {
+    pub item: String,
+    pub reason: String,
+}
+
+/// 批量任务结果：成功项与失败项分别返回
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport<T> {
+    pub succeeded: Vec<T>,
+    pub failed: Vec<BatchFailure>,
+}
+
+/// 批量任务进度事件
+#[derive(Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum BatchProgress {
+    Succeeded { item: String, index: usize, total: usize },
+    Failed { item: String, reason: String, index: usize, total: usize },
+    Finished { succeeded: usize, failed: usize },
+}
+
+/// 逐项收集批量结果，并在每项完成时发送进度事件
+pub struct BatchRecorder<'a, T> {
+    report: BatchReport<T>,
+    total: usize,
+    progress: Option<&'a Channel<BatchProgress>>,
+}
+
+impl<'a, T> BatchRecorder<'a, T> {
+    pub fn new(total: usize, progress: Option<&'a Channel<BatchProgress>>) -> Self {
+        Self {
+            report: BatchReport { succeeded: Vec::new(), failed: Vec::new() },
+            total,
+            progress,
+        }
+    }
+
+    /// 记录一项结果
+    pub fn record(&mut self, item: &str, result: Result<T, String>) {
+        let index = self.report.succeeded.len() + self.report.failed.len();
+        let event = match result {
+            Ok(value) => {
+                self.report.succeeded.push(value);
+                BatchProgress::Succeeded { item: item.to_string(), index, total: self.total }
+            }
+            Err(reason) => {
+                log::warn!("批量任务失败 {}: {}", item, reason);
+                self.report.failed.push(BatchFailure { item: item.to_string(), reason: reason.clone() });
+                BatchProgress::Failed { item: item.to_string(), reason, index, total: self.total }
+            }
+        };
+        self.send(event);
+    }
+
+    /// 结束批次并返回汇总结果
+    pub fn finish(self) -> BatchReport<T> {
+        self.send(BatchProgress::Finished {
+            succeeded: self.report.succeeded.len(),
+            failed: self.report.failed.len(),
+        });
+        self.report
+    }
+
+    fn send(&self, event: BatchProgress) {
+        if let Some(progress) = self.progress {
+            let _ = progress.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_keeps_going_after_failure() {
+        let mut recorder = BatchRecorder::new(3, None);
+        recorder.record("a", Ok(1));
+        recorder.record("b", Err("404".to_string()));
+        recorder.record("c", Ok(3));
+        let report = recorder.finish();
+
+        assert_eq!(report.succeeded, vec![1, 3]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].item, "b");
+        assert_eq!(report.failed[0].reason, "404");
+    }
+}
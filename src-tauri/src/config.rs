@@ -2,9 +2,9 @@
 //!
 //! 负责管理应用配置，包括模型配置的加载、保存、迁移和缓存。
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use serde_json::to_value;
-use crate::models::{AppConfig, ModelConfig, ModelProvider, ModelInfo, ModelConfigUpdate};
+use crate::models::{AppConfig, AppSettings, AppSettingsUpdate, ModelConfig, ModelProvider, ModelInfo, ModelCacheEntry, ModelConfigUpdate, RecommendedModel};
 
 /// 配置管理器
 pub struct ConfigManager {
@@ -24,6 +24,16 @@ impl ConfigManager {
         Ok(manager)
     }
 
+    /// 创建不执行旧配置迁移的配置管理器（迁移失败时的降级方案）
+    pub fn without_migration(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+
+    /// 获取应用句柄
+    pub fn app_handle(&self) -> &AppHandle {
+        &self.app_handle
+    }
+
     /// 加载应用配置
     pub async fn load_config(&self) -> Result<AppConfig, ConfigError> {
         let store = tauri_plugin_store::StoreBuilder::new(&self.app_handle, "settings.json")
@@ -46,6 +56,9 @@ impl ConfigManager {
         let value = to_value(config)?;
         store.set("app_config", value);
         store.save()?;
+        if let Some(errors) = self.app_handle.try_state::<crate::diagnostics::RecentErrors>() {
+            errors.set_secrets(crate::audit::config_secrets(config));
+        }
         Ok(())
     }
 
@@ -111,6 +124,7 @@ impl ConfigManager {
 
     /// 添加新的模型配置
     pub async fn add_model_config(&self, model_config: ModelConfig) -> Result<(), ConfigError> {
+        model_config.validate().map_err(ConfigError::Validation)?;
         let mut config = self.load_config().await?;
         config.add_config(model_config);
         self.save_config(&config).await
@@ -123,6 +137,12 @@ impl ConfigManager {
         updates: ModelConfigUpdate,
     ) -> Result<bool, ConfigError> {
         let mut config = self.load_config().await?;
+        // 先在副本上应用更新并校验，避免保存无效配置
+        if let Some(existing) = config.model_configs.iter().find(|c| c.id == config_id) {
+            let mut updated = existing.clone();
+            updated.update(updates.clone());
+            updated.validate().map_err(ConfigError::Validation)?;
+        }
         let success = config.update_config(config_id, updates);
         if success {
             self.save_config(&config).await?;
@@ -130,6 +150,18 @@ impl ConfigManager {
         Ok(success)
     }
 
+    /// 复制模型配置并保存，返回新配置
+    pub async fn duplicate_model_config(&self, config_id: &str) -> Result<ModelConfig, ConfigError> {
+        let mut config = self.load_config().await?;
+        let copy = config.model_configs.iter()
+            .find(|c| c.id == config_id)
+            .ok_or(ConfigError::ConfigNotFound)?
+            .duplicate();
+        config.add_config(copy.clone());
+        self.save_config(&config).await?;
+        Ok(copy)
+    }
+
     /// 删除模型配置
     pub async fn delete_model_config(&self, config_id: &str) -> Result<bool, ConfigError> {
         let mut config = self.load_config().await?;
@@ -160,19 +192,7 @@ impl ConfigManager {
         cache_hours: i64,
     ) -> Result<(), ConfigError> {
         let mut config = self.load_config().await?;
-
-        let cache_key = match provider {
-            ModelProvider::OpenAI => "openai".to_string(),
-            ModelProvider::Anthropic => "anthropic".to_string(),
-            ModelProvider::Google => "google".to_string(),
-            ModelProvider::DeepSeek => "deepseek".to_string(),
-            ModelProvider::AzureOpenAI => "azure_openai".to_string(),
-            ModelProvider::Custom(name) => format!("custom_{}", name),
-        };
-
-        config.model_cache.insert(cache_key, models);
-        config.update_cache_expiry(cache_hours);
-
+        config.model_cache.insert(model_cache_key(provider), ModelCacheEntry::new(models, cache_hours));
         self.save_config(&config).await
     }
 
@@ -182,30 +202,56 @@ impl ConfigManager {
         provider: &ModelProvider,
     ) -> Result<Option<Vec<ModelInfo>>, ConfigError> {
         let config = self.load_config().await?;
+        Ok(config.model_cache.get(&model_cache_key(provider))
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| entry.models.clone()))
+    }
 
-        if config.is_cache_expired() {
-            return Ok(None);
-        }
-
-        let cache_key = match provider {
-            ModelProvider::OpenAI => "openai".to_string(),
-            ModelProvider::Anthropic => "anthropic".to_string(),
-            ModelProvider::Google => "google".to_string(),
-            ModelProvider::DeepSeek => "deepseek".to_string(),
-            ModelProvider::AzureOpenAI => "azure_openai".to_string(),
-            ModelProvider::Custom(name) => format!("custom_{}", name),
-        };
-
-        Ok(config.model_cache.get(&cache_key).cloned())
+    /// 只清除某个提供商的模型缓存，其他提供商的缓存保留
+    pub async fn clear_model_cache_for(&self, provider: &ModelProvider) -> Result<(), ConfigError> {
+        let mut config = self.load_config().await?;
+        config.model_cache.remove(&model_cache_key(provider));
+        self.save_config(&config).await
     }
 
     /// 清除模型缓存
     pub async fn clear_model_cache(&self) -> Result<(), ConfigError> {
         let mut config = self.load_config().await?;
         config.model_cache.clear();
-        config.cache_expires_at = None;
         self.save_config(&config).await
     }
+
+    /// 获取全局应用设置
+    pub async fn get_app_settings(&self) -> Result<AppSettings, ConfigError> {
+        let config = self.load_config().await?;
+        Ok(config.settings)
+    }
+
+    /// 更新全局应用设置
+    pub async fn update_app_settings(
+        &self,
+        updates: AppSettingsUpdate,
+    ) -> Result<AppSettings, ConfigError> {
+        let mut config = self.load_config().await?;
+        config.settings.update(updates);
+        self.save_config(&config).await?;
+        crate::i18n::set_language(crate::i18n::Language::from_code(config.settings.ui_language.as_deref()));
+        crate::offline::set_offline_mode(config.settings.offline_mode);
+        crate::trending::load_topic_rules(config.settings.topic_rules.clone());
+        Ok(config.settings)
+    }
+}
+
+/// 模型列表缓存的键
+fn model_cache_key(provider: &ModelProvider) -> String {
+    match provider {
+        ModelProvider::OpenAI => "openai".to_string(),
+        ModelProvider::Anthropic => "anthropic".to_string(),
+        ModelProvider::Google => "google".to_string(),
+        ModelProvider::DeepSeek => "deepseek".to_string(),
+        ModelProvider::AzureOpenAI => "azure_openai".to_string(),
+        ModelProvider::Custom(name) => format!("custom_{}", name),
+    }
 }
 
 /// 配置错误类型
@@ -219,6 +265,8 @@ pub enum ConfigError {
     MigrationError(String),
     #[error("配置不存在")]
     ConfigNotFound,
+    #[error("{0}")]
+    Validation(String),
 }
 
 impl From<tauri_plugin_store::Error> for ConfigError {
@@ -302,6 +350,18 @@ pub mod commands {
             .map_err(|e| e.to_string())
     }
 
+    /// 复制模型配置（例如同一提供商换用另一个模型），返回新配置
+    #[tauri::command]
+    pub async fn duplicate_model_config(
+        manager: State<'_, ConfigManagerState>,
+        config_id: String,
+    ) -> Result<ModelConfig, String> {
+        let manager = manager.lock().await;
+        manager.duplicate_model_config(&config_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     /// 删除模型配置
     #[tauri::command]
     pub async fn delete_model_config(
@@ -314,6 +374,18 @@ pub mod commands {
             .map_err(|e| e.to_string())
     }
 
+    /// 清除单个提供商的模型缓存
+    #[tauri::command]
+    pub async fn clear_model_cache_for(
+        manager: State<'_, ConfigManagerState>,
+        provider: ModelProvider,
+    ) -> Result<(), String> {
+        let manager = manager.lock().await;
+        manager.clear_model_cache_for(&provider)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     /// 清除模型缓存
     #[tauri::command]
     pub async fn clear_model_cache(
@@ -324,4 +396,39 @@ pub mod commands {
             .await
             .map_err(|e| e.to_string())
     }
+
+    /// 获取提供商的推荐模型列表
+    #[tauri::command]
+    pub fn get_recommended_models(provider: ModelProvider) -> Vec<RecommendedModel> {
+        provider.recommended_models()
+    }
+
+    /// 检查 API Key 格式是否与提供商匹配，返回警告信息（启发式判断，不会拒绝保存）
+    #[tauri::command]
+    pub fn validate_key_format(provider: ModelProvider, api_key: String) -> Option<String> {
+        provider.key_format_warning(&api_key)
+    }
+
+    /// 获取全局应用设置
+    #[tauri::command]
+    pub async fn get_app_settings(
+        manager: State<'_, ConfigManagerState>,
+    ) -> Result<AppSettings, String> {
+        let manager = manager.lock().await;
+        manager.get_app_settings()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// 更新全局应用设置
+    #[tauri::command]
+    pub async fn update_app_settings(
+        manager: State<'_, ConfigManagerState>,
+        updates: AppSettingsUpdate,
+    ) -> Result<AppSettings, String> {
+        let manager = manager.lock().await;
+        manager.update_app_settings(updates)
+            .await
+            .map_err(|e| e.to_string())
+    }
 }
\ No newline at end of file
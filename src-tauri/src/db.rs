@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqlitePool;
 use tauri_plugin_sql::{Migration, MigrationKind};
 
@@ -40,6 +41,120 @@ pub fn get_migrations() -> Vec<Migration> {
                 ALTER TABLE repos ADD COLUMN forks TEXT;
             ",
             kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 3,
+            description: "add tagline to repos table",
+            sql: "
+                ALTER TABLE repos ADD COLUMN tagline TEXT;
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "create hidden_repos table",
+            sql: "
+                CREATE TABLE IF NOT EXISTS hidden_repos (
+                    url TEXT PRIMARY KEY,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "add provenance columns to insights table",
+            sql: "
+                ALTER TABLE insights ADD COLUMN model TEXT DEFAULT 'unknown';
+                ALTER TABLE insights ADD COLUMN provider TEXT DEFAULT 'unknown';
+                ALTER TABLE insights ADD COLUMN mode TEXT DEFAULT 'unknown';
+                ALTER TABLE insights ADD COLUMN language TEXT;
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "create insight_cache table",
+            // insights 只记录已收藏仓库（外键引用 repos），所有仓库的洞察缓存单独存放
+            sql: "
+                CREATE TABLE IF NOT EXISTS insight_cache (
+                    repo_url TEXT NOT NULL,
+                    variant TEXT NOT NULL,
+                    content TEXT NOT NULL,
+                    reasoning TEXT,
+                    metadata TEXT,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    accessed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (repo_url, variant)
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "create tags and repo_tags tables",
+            // 取消收藏时由外键级联删除该仓库的标签关联
+            sql: "
+                CREATE TABLE IF NOT EXISTS tags (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS repo_tags (
+                    repo_url TEXT NOT NULL,
+                    tag_id INTEGER NOT NULL,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (repo_url, tag_id),
+                    FOREIGN KEY(repo_url) REFERENCES repos(url) ON DELETE CASCADE,
+                    FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "add note to repos",
+            // 取消收藏时备注暂存到 favorite_note_archive，重新收藏时恢复
+            sql: "
+                ALTER TABLE repos ADD COLUMN note TEXT;
+                CREATE TABLE IF NOT EXISTS favorite_note_archive (
+                    url TEXT PRIMARY KEY,
+                    note TEXT NOT NULL,
+                    archived_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "add numeric stars_count to repos",
+            // stars 以文本保存（如 `12,345`、`1.2k`），回填规则与 `trending::parse_star_count` 一致，
+            // 非数字开头的值（如 `n/a`）保持为 NULL
+            sql: "
+                ALTER TABLE repos ADD COLUMN stars_count INTEGER;
+                UPDATE repos SET stars_count = CASE
+                    WHEN NOT replace(trim(stars), ',', '') GLOB '[0-9]*' THEN NULL
+                    WHEN lower(trim(stars)) LIKE '%k' THEN CAST(ROUND(CAST(rtrim(replace(lower(trim(stars)), ',', ''), 'k') AS REAL) * 1000) AS INTEGER)
+                    WHEN lower(trim(stars)) LIKE '%m' THEN CAST(ROUND(CAST(rtrim(replace(lower(trim(stars)), ',', ''), 'm') AS REAL) * 1000000) AS INTEGER)
+                    ELSE CAST(replace(trim(stars), ',', '') AS INTEGER)
+                END
+                WHERE stars_count IS NULL AND trim(COALESCE(stars, '')) != '';
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "create query_rewrites table",
+            sql: "
+                CREATE TABLE IF NOT EXISTS query_rewrites (
+                    query TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    rewritten TEXT NOT NULL,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (query, model)
+                );
+            ",
+            kind: MigrationKind::Up,
         }
     ]
 }
@@ -64,6 +179,13 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
 
 pub type DbState = SqlitePool;
 
+/// 数据库维护锁：VACUUM 持有写锁，批量写入持有读锁，保证二者不会并发执行
+#[derive(Default)]
+pub struct DbMaintenanceLock(pub tokio::sync::RwLock<()>);
+
+/// 数据库文件名
+pub const DB_FILE_NAME: &str = "github_capture.db";
+
 #[tauri::command]
 pub async fn toggle_favorite(
     repo: crate::ai::RepoInfo,
@@ -77,40 +199,498 @@ pub async fn toggle_favorite(
         .map_err(|e| e.to_string())?;
 
     if existing.is_some() {
-        // 删除
-        sqlx::query("DELETE FROM repos WHERE url = ?")
+        // 备注暂存，重新收藏时恢复
+        sqlx::query(
+            "INSERT OR REPLACE INTO favorite_note_archive (url, note)
+             SELECT url, note FROM repos WHERE url = ? AND note IS NOT NULL AND note != ''",
+        )
+        .bind(&repo.url)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+        // 删除（洞察记录引用了收藏，需先删除；标签关联随外键级联删除；洞察缓存保留）
+        sqlx::query("DELETE FROM insights WHERE repo_url = ?")
             .bind(&repo.url)
             .execute(db.inner())
             .await
             .map_err(|e| e.to_string())?;
-        Ok(false)
-    } else {
-        // 插入
-        sqlx::query("INSERT INTO repos (author, name, description, language, url, stars, forks) VALUES (?, ?, ?, ?, ?, ?, ?)")
-            .bind(&repo.author)
-            .bind(&repo.name)
-            .bind(&repo.description)
-            .bind(&repo.language)
+        sqlx::query("DELETE FROM repos WHERE url = ?")
             .bind(&repo.url)
-            .bind(&repo.stars)
-            .bind(&repo.forks)
             .execute(db.inner())
             .await
             .map_err(|e| e.to_string())?;
+        Ok(false)
+    } else {
+        // 插入
+        insert_favorite(db.inner(), &repo).await?;
         Ok(true)
     }
 }
 
+/// 插入一条收藏记录，之前取消收藏时暂存的备注一并恢复
+async fn insert_favorite(pool: &SqlitePool, repo: &crate::ai::RepoInfo) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO repos (author, name, description, language, url, stars, forks, stars_count, note)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, (SELECT note FROM favorite_note_archive WHERE url = ?5))",
+    )
+    .bind(&repo.author)
+    .bind(&repo.name)
+    .bind(&repo.description)
+    .bind(&repo.language)
+    .bind(&repo.url)
+    .bind(&repo.stars)
+    .bind(&repo.forks)
+    .bind(repo.stars.as_deref().and_then(crate::trending::parse_star_count).map(|n| n as i64))
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM favorite_note_archive WHERE url = ?")
+        .bind(&repo.url)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 设置收藏的备注（例如收藏的原因），内容为空时清除
+#[tauri::command]
+pub async fn set_favorite_note(
+    url: String,
+    note: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let note = Some(note.trim()).filter(|n| !n.is_empty());
+    let updated = sqlx::query("UPDATE repos SET note = ? WHERE url = ?")
+        .bind(note)
+        .bind(&url)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+    if updated == 0 {
+        return Err("仓库尚未收藏，无法添加备注".to_string());
+    }
+    Ok(())
+}
+
+/// 仓库尚未收藏时加入收藏，返回是否新增
+pub async fn ensure_favorite(pool: &SqlitePool, repo: &crate::ai::RepoInfo) -> Result<bool, String> {
+    let existing = sqlx::query("SELECT id FROM repos WHERE url = ?")
+        .bind(&repo.url)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if existing.is_some() {
+        return Ok(false);
+    }
+    insert_favorite(pool, repo).await?;
+    Ok(true)
+}
+
+/// 单个地址的导入结果
+#[derive(Debug, Serialize)]
+pub enum ImportStatus {
+    Imported,
+    Duplicate,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UrlImportResult {
+    pub url: String,
+    pub status: ImportStatus,
+}
+
+/// 从仓库地址列表批量导入收藏；导入失败的地址单独列出，不会中断批次
+#[tauri::command]
+pub async fn import_repos_from_urls(
+    urls: Vec<String>,
+    on_progress: tauri::ipc::Channel<crate::batch::BatchProgress>,
+    db: tauri::State<'_, DbState>,
+    maintenance: tauri::State<'_, DbMaintenanceLock>,
+    config_manager: tauri::State<'_, crate::config::commands::ConfigManagerState>,
+) -> Result<crate::batch::BatchReport<UrlImportResult>, String> {
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    crate::offline::ensure_online()?;
+    let host = crate::github::GitHubHost::from_settings(&settings);
+    let _guard = maintenance.0.read().await;
+
+    let urls: Vec<String> = urls.into_iter().map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect();
+    let mut recorder = crate::batch::BatchRecorder::new(urls.len(), Some(&on_progress));
+    for url in urls {
+        let result = import_repo_url(db.inner(), &host, &url).await.map(|imported| UrlImportResult {
+            url: url.clone(),
+            status: if imported { ImportStatus::Imported } else { ImportStatus::Duplicate },
+        });
+        recorder.record(&url, result);
+    }
+    Ok(recorder.finish())
+}
+
+/// 导入单个仓库地址，返回是否新增（已收藏时返回 false）
+async fn import_repo_url(pool: &SqlitePool, host: &crate::github::GitHubHost, url: &str) -> Result<bool, String> {
+    let (owner, name) = host.parse_repo_url(url)?;
+    let metadata = crate::github::fetch_repo_metadata(host, &owner, &name).await?;
+
+    // 以 GitHub 返回的规范地址去重，避免大小写或 .git 后缀造成重复
+    let existing = sqlx::query("SELECT id FROM repos WHERE url = ?")
+        .bind(&metadata.html_url)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if existing.is_some() {
+        return Ok(false);
+    }
+
+    let repo = crate::ai::RepoInfo {
+        author: metadata.owner.login,
+        name: metadata.name,
+        description: metadata.description.unwrap_or_default(),
+        language: metadata.language.unwrap_or_else(|| "Unknown".to_string()),
+        url: metadata.html_url,
+        stars: Some(crate::search::format_number(metadata.stargazers_count)),
+        forks: Some(crate::search::format_number(metadata.forks_count)),
+    };
+    insert_favorite(pool, &repo).await?;
+    Ok(true)
+}
+
+/// 收藏列表的排序字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FavoriteSort {
+    /// 收藏时间（默认最新在前）
+    #[default]
+    CreatedAt,
+    /// 星标数（默认从多到少，星标未知的排在最后）
+    Stars,
+    /// 仓库名称（默认按字母顺序）
+    Name,
+}
+
+/// 排序方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl FavoriteSort {
+    /// 生成 ORDER BY 子句，未指定方向时使用该字段的默认方向
+    fn order_by(self, order: Option<SortOrder>) -> String {
+        let default = if self == FavoriteSort::Name { SortOrder::Asc } else { SortOrder::Desc };
+        let direction = match order.unwrap_or(default) {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        match self {
+            FavoriteSort::CreatedAt => format!("created_at {}", direction),
+            FavoriteSort::Stars => format!("stars_count IS NULL, stars_count {}, created_at DESC", direction),
+            FavoriteSort::Name => format!("name COLLATE NOCASE {0}, author COLLATE NOCASE {0}", direction),
+        }
+    }
+}
+
+/// 收藏列表的筛选与排序条件
+#[derive(Debug, Default)]
+struct FavoriteFilter<'a> {
+    tag: Option<&'a str>,
+    /// 语言（与 `get_favorite_facets` 一致，未标注语言的仓库归为 `Unknown`）
+    language: Option<&'a str>,
+    sort: FavoriteSort,
+    order: Option<SortOrder>,
+}
+
+/// 获取收藏列表，可按标签、语言筛选，并按收藏时间、星标数或名称排序
 #[tauri::command]
 pub async fn get_favorites(
+    tag: Option<String>,
+    language: Option<String>,
+    sort: Option<FavoriteSort>,
+    order: Option<SortOrder>,
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<crate::trending::TrendingRepo>, String> {
+    let filter = FavoriteFilter {
+        tag: tag.as_deref().map(str::trim).filter(|t| !t.is_empty()),
+        language: language.as_deref().map(str::trim).filter(|l| !l.is_empty()),
+        sort: sort.unwrap_or_default(),
+        order,
+    };
+    query_favorites(db.inner(), &filter).await
+}
+
+/// 获取带指定标签的收藏
+#[tauri::command]
+pub async fn get_favorites_by_tag(
+    tag: String,
     db: tauri::State<'_, DbState>,
 ) -> Result<Vec<crate::trending::TrendingRepo>, String> {
-    let rows = sqlx::query_as::<_, crate::trending::TrendingRepo>("SELECT author, name, description, language, COALESCE(stars, '') as stars, COALESCE(forks, '') as forks, '' as stars_today, url, 'Favorite' as topic FROM repos ORDER BY created_at DESC")
+    let tag = normalize_tag(&tag)?;
+    query_favorites(db.inner(), &FavoriteFilter { tag: Some(&tag), ..Default::default() }).await
+}
+
+async fn query_favorites(
+    pool: &SqlitePool,
+    filter: &FavoriteFilter<'_>,
+) -> Result<Vec<crate::trending::TrendingRepo>, String> {
+    // 排序字段来自枚举，直接拼接到 SQL 中是安全的
+    let sql = format!(
+        "SELECT author, name, description, language, COALESCE(stars, '') as stars, COALESCE(forks, '') as forks, '' as stars_today, stars_count, url, 'Favorite' as topic, tagline, note FROM repos
+         WHERE (?1 IS NULL OR url IN (SELECT repo_tags.repo_url FROM repo_tags JOIN tags ON tags.id = repo_tags.tag_id WHERE tags.name = ?1))
+           AND (?2 IS NULL OR COALESCE(NULLIF(language, ''), 'Unknown') = ?2 COLLATE NOCASE)
+         ORDER BY {}",
+        filter.sort.order_by(filter.order),
+    );
+    sqlx::query_as::<_, crate::trending::TrendingRepo>(&sql)
+    .bind(filter.tag)
+    .bind(filter.language)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 收藏搜索中名称、描述、洞察内容命中的权重
+const NAME_MATCH_WEIGHT: u32 = 3;
+const DESCRIPTION_MATCH_WEIGHT: u32 = 2;
+const INSIGHT_MATCH_WEIGHT: u32 = 1;
+
+/// 在收藏的名称、描述与洞察内容中搜索，结果按命中权重排序（同分时最近收藏的在前）
+///
+/// 查询按空白拆分为多个关键词，每个关键词都必须命中，不区分大小写。
+#[tauri::command]
+pub async fn search_favorites(
+    query: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<crate::trending::TrendingRepo>, String> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let favorites = query_favorites(db.inner(), &FavoriteFilter::default()).await?;
+    if terms.is_empty() {
+        return Ok(favorites);
+    }
+
+    let insights: std::collections::HashMap<String, String> = sqlx::query_as("SELECT repo_url, content FROM insights")
         .fetch_all(db.inner())
         .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+
+    let mut scored: Vec<(u32, crate::trending::TrendingRepo)> = favorites
+        .into_iter()
+        .filter_map(|repo| {
+            let name = format!("{}/{}", repo.author, repo.name);
+            let insight = insights.get(&repo.url).map(String::as_str).unwrap_or("");
+            let score = match_score(&terms, &name, &repo.description, insight)?;
+            Some((score, repo))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    Ok(scored.into_iter().map(|(_, repo)| repo).collect())
+}
+
+/// 计算命中权重；有关键词在各字段中都未出现时返回 `None`（`terms` 需已转为小写）
+fn match_score(terms: &[String], name: &str, description: &str, insight: &str) -> Option<u32> {
+    let fields = [
+        (name.to_lowercase(), NAME_MATCH_WEIGHT),
+        (description.to_lowercase(), DESCRIPTION_MATCH_WEIGHT),
+        (insight.to_lowercase(), INSIGHT_MATCH_WEIGHT),
+    ];
+    terms.iter().try_fold(0, |total, term| {
+        let score: u32 = fields
+            .iter()
+            .filter(|(text, _)| text.contains(term.as_str()))
+            .map(|(_, weight)| weight)
+            .sum();
+        (score > 0).then_some(total + score)
+    })
+}
+
+/// 去除标签首尾空白，拒绝空标签
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("标签不能为空".to_string());
+    }
+    Ok(tag.to_string())
+}
+
+/// 为已收藏的仓库添加标签（标签名不区分大小写，重复添加不报错）
+#[tauri::command]
+pub async fn add_tag(
+    repo_url: String,
+    tag: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let tag = normalize_tag(&tag)?;
+    let existing = sqlx::query("SELECT id FROM repos WHERE url = ?")
+        .bind(&repo_url)
+        .fetch_optional(db.inner())
+        .await
         .map_err(|e| e.to_string())?;
+    if existing.is_none() {
+        return Err("仓库尚未收藏，无法添加标签".to_string());
+    }
 
-    Ok(rows)
+    sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+        .bind(&tag)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("INSERT OR IGNORE INTO repo_tags (repo_url, tag_id) SELECT ?, id FROM tags WHERE name = ?")
+        .bind(&repo_url)
+        .bind(&tag)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 移除仓库的标签，不再被任何仓库使用的标签一并删除
+#[tauri::command]
+pub async fn remove_tag(
+    repo_url: String,
+    tag: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    let tag = normalize_tag(&tag)?;
+    sqlx::query("DELETE FROM repo_tags WHERE repo_url = ? AND tag_id IN (SELECT id FROM tags WHERE name = ?)")
+        .bind(&repo_url)
+        .bind(&tag)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM repo_tags)")
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 获取仓库的所有标签（按名称排序）
+#[tauri::command]
+pub async fn get_tags(
+    repo_url: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT tags.name FROM tags JOIN repo_tags ON repo_tags.tag_id = tags.id WHERE repo_tags.repo_url = ? ORDER BY tags.name COLLATE NOCASE",
+    )
+    .bind(&repo_url)
+    .fetch_all(db.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 读取已收藏仓库缓存的标语；仓库描述变化后视为失效
+pub async fn get_cached_tagline(pool: &SqlitePool, url: &str, description: &str) -> Option<String> {
+    sqlx::query_scalar::<_, String>("SELECT tagline FROM repos WHERE url = ? AND COALESCE(description, '') = ? AND COALESCE(tagline, '') != ''")
+        .bind(url)
+        .bind(description)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// 洞察的生成来源
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct InsightProvenance {
+    pub repo_url: String,
+    pub model: Option<String>,
+    pub provider: Option<String>,
+    pub mode: Option<String>,
+    pub language: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// 未知来源（旧缓存迁移时使用）
+pub const UNKNOWN_PROVENANCE: &str = "unknown";
+
+/// 保存洞察及其来源（仅对已收藏的仓库生效）
+pub async fn save_insight(
+    pool: &SqlitePool,
+    url: &str,
+    content: &str,
+    model: &str,
+    provider: &str,
+    mode: &str,
+    language: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO insights (repo_url, content, updated_at, model, provider, mode, language)
+         SELECT ?, ?, CURRENT_TIMESTAMP, ?, ?, ?, ? WHERE EXISTS (SELECT 1 FROM repos WHERE url = ?)
+         ON CONFLICT(repo_url) DO UPDATE SET
+            content = excluded.content, updated_at = excluded.updated_at, model = excluded.model,
+            provider = excluded.provider, mode = excluded.mode, language = excluded.language",
+    )
+    .bind(url)
+    .bind(content)
+    .bind(model)
+    .bind(provider)
+    .bind(mode)
+    .bind(language)
+    .bind(url)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 将洞察缓存补录到 insights 表：已收藏仓库的默认篇幅洞察若尚无记录，
+/// 按生成信息写入，缺少生成信息的旧缓存记为 unknown
+pub async fn backfill_insights(pool: &SqlitePool) -> Result<usize, String> {
+    let rows = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+        "SELECT r.url, COALESCE(r.language, ''), c.content, c.metadata FROM repos r
+         JOIN insight_cache c ON c.repo_url = r.url AND c.variant = ?
+         WHERE r.url NOT IN (SELECT repo_url FROM insights)",
+    )
+    .bind(crate::ai::SummaryLength::Medium.cache_suffix())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut migrated = 0;
+    for (url, language, content, metadata) in rows {
+        let metadata = metadata.and_then(|json| serde_json::from_str::<crate::insight_cache::InsightMetadata>(&json).ok());
+        let (model, provider, mode) = match &metadata {
+            Some(m) => (m.model.as_str(), m.provider.as_str(), m.mode_label()),
+            None => (UNKNOWN_PROVENANCE, UNKNOWN_PROVENANCE, UNKNOWN_PROVENANCE.to_string()),
+        };
+        let provider = if provider.is_empty() { UNKNOWN_PROVENANCE } else { provider };
+        save_insight(pool, &url, &content, model, provider, &mode, &language).await?;
+        migrated += 1;
+    }
+    Ok(migrated)
+}
+
+/// 获取洞察的生成来源（仅已收藏的仓库有记录）
+#[tauri::command]
+pub async fn get_insight_provenance(
+    url: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<Option<InsightProvenance>, String> {
+    sqlx::query_as::<_, InsightProvenance>(
+        "SELECT repo_url, model, provider, mode, language, CAST(updated_at AS TEXT) AS updated_at
+         FROM insights WHERE repo_url = ?",
+    )
+    .bind(&url)
+    .fetch_optional(db.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 保存标语（仅对已收藏的仓库生效）
+pub async fn save_tagline(pool: &SqlitePool, url: &str, tagline: &str) -> Result<(), String> {
+    sqlx::query("UPDATE repos SET tagline = ? WHERE url = ?")
+        .bind(tagline)
+        .bind(url)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -126,3 +706,537 @@ pub async fn is_favorite(
 
     Ok(existing.is_some())
 }
+
+
+/// 将仓库从热门/搜索结果中隐藏
+#[tauri::command]
+pub async fn hide_repo(
+    url: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    sqlx::query("INSERT OR IGNORE INTO hidden_repos (url) VALUES (?)")
+        .bind(&url)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 取消隐藏仓库
+#[tauri::command]
+pub async fn unhide_repo(
+    url: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM hidden_repos WHERE url = ?")
+        .bind(&url)
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 获取所有已隐藏的仓库地址（最近隐藏的在前）
+#[tauri::command]
+pub async fn get_hidden_repos(
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<String>, String> {
+    sqlx::query_scalar::<_, String>("SELECT url FROM hidden_repos ORDER BY created_at DESC")
+        .fetch_all(db.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从列表中移除已隐藏的仓库
+pub async fn filter_hidden(
+    pool: &SqlitePool,
+    repos: Vec<crate::trending::TrendingRepo>,
+) -> Result<Vec<crate::trending::TrendingRepo>, String> {
+    let hidden: std::collections::HashSet<String> = sqlx::query_scalar::<_, String>("SELECT url FROM hidden_repos")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    Ok(repos.into_iter().filter(|r| !hidden.contains(&r.url)).collect())
+}
+
+/// 搜索历史记录
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SearchHistoryEntry {
+    pub id: i64,
+    pub query: String,
+    pub created_at: String,
+}
+
+/// 默认返回的搜索历史条数
+const DEFAULT_SEARCH_HISTORY_LIMIT: i64 = 50;
+/// 搜索历史最多保留的条数，超出时删除最旧的记录
+const MAX_SEARCH_HISTORY: i64 = 500;
+
+/// 保存一条搜索记录，与最近一条相同的查询不重复记录，并清理超出上限的旧记录
+pub async fn save_search(pool: &SqlitePool, query: &str) -> Result<(), String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO search_history (query)
+         SELECT ? WHERE COALESCE((SELECT query FROM search_history ORDER BY id DESC LIMIT 1), '') != ?",
+    )
+    .bind(query)
+    .bind(query)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM search_history WHERE id NOT IN (SELECT id FROM search_history ORDER BY id DESC LIMIT ?)")
+        .bind(MAX_SEARCH_HISTORY)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 记录一次搜索
+#[tauri::command]
+pub async fn record_search(
+    query: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    save_search(db.inner(), &query).await
+}
+
+/// 获取最近的搜索记录（最新的在前）
+#[tauri::command]
+pub async fn get_search_history(
+    limit: Option<i64>,
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<SearchHistoryEntry>, String> {
+    sqlx::query_as::<_, SearchHistoryEntry>(
+        "SELECT id, query, CAST(created_at AS TEXT) AS created_at FROM search_history ORDER BY id DESC LIMIT ?",
+    )
+    .bind(limit.filter(|n| *n > 0).unwrap_or(DEFAULT_SEARCH_HISTORY_LIMIT))
+    .fetch_all(db.inner())
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 清空搜索历史
+#[tauri::command]
+pub async fn clear_search_history(
+    db: tauri::State<'_, DbState>,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM search_history")
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 查询改写缓存的有效期，过期的结果不再返回并在写入时清理
+const QUERY_REWRITE_TTL: &str = "-30 days";
+/// 查询改写缓存最多保留的条数，超出时删除最旧的记录
+const MAX_QUERY_REWRITES: i64 = 500;
+
+/// 读取未过期的 AI 查询改写结果，`query` 应已规范化
+pub async fn get_query_rewrite(pool: &SqlitePool, query: &str, model: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT rewritten FROM query_rewrites WHERE query = ? AND model = ? AND created_at >= datetime('now', ?)",
+    )
+    .bind(query)
+    .bind(model)
+    .bind(QUERY_REWRITE_TTL)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 保存 AI 查询改写结果，同一查询与模型只保留最新一条，并清理过期与超出上限的记录
+pub async fn save_query_rewrite(pool: &SqlitePool, query: &str, model: &str, rewritten: &str) -> Result<(), String> {
+    sqlx::query("INSERT OR REPLACE INTO query_rewrites (query, model, rewritten) VALUES (?, ?, ?)")
+        .bind(query)
+        .bind(model)
+        .bind(rewritten)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query(
+        "DELETE FROM query_rewrites WHERE created_at < datetime('now', ?)
+         OR rowid NOT IN (SELECT rowid FROM query_rewrites ORDER BY created_at DESC LIMIT ?)",
+    )
+    .bind(QUERY_REWRITE_TTL)
+    .bind(MAX_QUERY_REWRITES)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 导出的单条收藏
+#[derive(Debug, Serialize)]
+pub struct FavoriteExport {
+    #[serde(flatten)]
+    pub repo: crate::trending::TrendingRepo,
+    /// 收藏时间（`repos.created_at`）
+    pub captured_at: String,
+    /// 已缓存的洞察内容
+    pub insight: Option<String>,
+}
+
+/// 导出收藏，`format` 为 `markdown` 或 `json`，返回序列化后的内容
+#[tauri::command]
+pub async fn export_favorites(
+    format: String,
+    db: tauri::State<'_, DbState>,
+) -> Result<String, String> {
+    let format = format.trim().to_lowercase();
+    if !matches!(format.as_str(), "markdown" | "md" | "json") {
+        return Err(format!("不支持的导出格式: {}，可选值: markdown, json", format));
+    }
+
+    let favorites = query_favorites(db.inner(), &FavoriteFilter::default()).await?;
+    let mut details: std::collections::HashMap<String, (String, Option<String>)> = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT repos.url, CAST(repos.created_at AS TEXT), insights.content FROM repos LEFT JOIN insights ON insights.repo_url = repos.url",
+    )
+    .fetch_all(db.inner())
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|(url, captured_at, insight)| (url, (captured_at, insight)))
+    .collect();
+
+    let entries: Vec<FavoriteExport> = favorites
+        .into_iter()
+        .map(|repo| {
+            let (captured_at, insight) = details.remove(&repo.url).unwrap_or_default();
+            FavoriteExport { repo, captured_at, insight }
+        })
+        .collect();
+
+    if format == "json" {
+        serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())
+    } else {
+        Ok(render_favorites_markdown(&entries))
+    }
+}
+
+/// 渲染 Markdown 格式的收藏列表，洞察内容内联在每个仓库下方
+fn render_favorites_markdown(entries: &[FavoriteExport]) -> String {
+    let mut out = format!("# GitHub 收藏（{} 个）\n", entries.len());
+    for entry in entries {
+        let repo = &entry.repo;
+        out.push_str(&format!("\n## [{}/{}]({})\n\n", repo.author, repo.name, repo.url));
+        if let Some(tagline) = repo.tagline.as_deref().filter(|t| !t.is_empty()) {
+            out.push_str(&format!("**{}**\n\n", tagline));
+        }
+        if let Some(note) = repo.note.as_deref().filter(|n| !n.is_empty()) {
+            out.push_str(&format!("备注：{}\n\n", note));
+        }
+        if !repo.description.is_empty() {
+            out.push_str(&format!("> {}\n\n", repo.description));
+        }
+        out.push_str(&format!("- 语言: {}\n", repo.language));
+        if !repo.stars.is_empty() {
+            out.push_str(&format!("- Stars: {}\n", repo.stars));
+        }
+        if !repo.forks.is_empty() {
+            out.push_str(&format!("- Forks: {}\n", repo.forks));
+        }
+        out.push_str(&format!("- 收藏时间: {}\n", entry.captured_at));
+        if let Some(insight) = entry.insight.as_deref().map(str::trim).filter(|i| !i.is_empty()) {
+            out.push_str(&format!("\n### 洞察\n\n{}\n", insight));
+        }
+    }
+    out
+}
+
+/// 导入文件中的单条收藏（兼容 `export_favorites` 导出的 JSON）
+#[derive(Debug, Deserialize)]
+struct FavoriteImport {
+    author: String,
+    name: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    stars: String,
+    #[serde(default)]
+    forks: String,
+    #[serde(default)]
+    tagline: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    captured_at: Option<String>,
+    #[serde(default)]
+    insight: Option<String>,
+}
+
+/// 收藏导入结果
+#[derive(Debug, Serialize)]
+pub struct FavoriteImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+/// 解析并校验导入内容，任一条目无效时整体报错
+fn parse_favorites_import(json: &str) -> Result<Vec<FavoriteImport>, String> {
+    let entries: Vec<FavoriteImport> = serde_json::from_str(json)
+        .map_err(|e| format!("导入文件格式无效: {}", e))?;
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.url.trim().is_empty() || entry.author.trim().is_empty() || entry.name.trim().is_empty() {
+            return Err(format!("导入文件第 {} 条收藏缺少 author、name 或 url", index + 1));
+        }
+    }
+    Ok(entries)
+}
+
+/// 从 `export_favorites` 导出的 JSON 导入收藏，已收藏的地址跳过；附带的洞察在尚无洞察时一并恢复
+///
+/// 内容先整体校验，写入在同一事务中完成，失败时不会留下部分导入的数据。
+#[tauri::command]
+pub async fn import_favorites(
+    json: String,
+    db: tauri::State<'_, DbState>,
+    maintenance: tauri::State<'_, DbMaintenanceLock>,
+) -> Result<FavoriteImportSummary, String> {
+    let entries = parse_favorites_import(&json)?;
+    let _guard = maintenance.0.read().await;
+
+    let mut tx = db.begin().await.map_err(|e| e.to_string())?;
+    let mut summary = FavoriteImportSummary { imported: 0, skipped: 0 };
+    for entry in &entries {
+        let url = entry.url.trim();
+        let inserted = sqlx::query(
+            "INSERT OR IGNORE INTO repos (author, name, description, language, url, stars, forks, stars_count, tagline, note, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP))",
+        )
+        .bind(&entry.author)
+        .bind(&entry.name)
+        .bind(&entry.description)
+        .bind(&entry.language)
+        .bind(url)
+        .bind(&entry.stars)
+        .bind(&entry.forks)
+        .bind(crate::trending::parse_star_count(&entry.stars).map(|n| n as i64))
+        .bind(&entry.tagline)
+        .bind(&entry.note)
+        .bind(&entry.captured_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected() > 0;
+
+        if inserted {
+            summary.imported += 1;
+        } else {
+            summary.skipped += 1;
+        }
+
+        if let Some(insight) = entry.insight.as_deref().filter(|i| !i.trim().is_empty()) {
+            sqlx::query("INSERT OR IGNORE INTO insights (repo_url, content) VALUES (?, ?)")
+                .bind(url)
+                .bind(insight)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(summary)
+}
+
+/// 收藏中某种语言的数量
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct LanguageFacet {
+    pub language: String,
+    pub count: i64,
+}
+
+/// 收藏中某个主题分类的数量
+#[derive(Debug, Serialize)]
+pub struct TopicFacet {
+    pub topic: String,
+    pub count: i64,
+}
+
+/// 收藏列表的筛选维度
+#[derive(Debug, Serialize)]
+pub struct FavoriteFacets {
+    pub languages: Vec<LanguageFacet>,
+    pub topics: Vec<TopicFacet>,
+}
+
+#[tauri::command]
+pub async fn get_favorite_facets(
+    db: tauri::State<'_, DbState>,
+) -> Result<FavoriteFacets, String> {
+    let languages = sqlx::query_as::<_, LanguageFacet>("SELECT COALESCE(NULLIF(language, ''), 'Unknown') as language, COUNT(*) as count FROM repos GROUP BY 1 ORDER BY count DESC, language ASC")
+        .fetch_all(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 收藏表不存储主题，按与热门列表相同的规则现场分类
+    let rows: Vec<(String, Option<String>)> = sqlx::query_as("SELECT name, description FROM repos")
+        .fetch_all(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut topics: Vec<TopicFacet> = Vec::new();
+    for (name, description) in rows {
+        let topic = crate::trending::get_topic(&name, description.as_deref().unwrap_or(""), &[]);
+        match topics.iter_mut().find(|t| t.topic == topic) {
+            Some(facet) => facet.count += 1,
+            None => topics.push(TopicFacet { topic, count: 1 }),
+        }
+    }
+    topics.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.topic.cmp(&b.topic)));
+
+    Ok(FavoriteFacets { languages, topics })
+}
+
+/// 数据库压缩结果
+#[derive(Debug, Serialize)]
+pub struct VacuumReport {
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+}
+
+/// 压缩数据库并截断 WAL 文件，返回压缩前后的文件大小
+#[tauri::command]
+pub async fn vacuum_database(
+    db: tauri::State<'_, DbState>,
+    maintenance: tauri::State<'_, DbMaintenanceLock>,
+    app_handle: tauri::AppHandle,
+) -> Result<VacuumReport, String> {
+    use tauri::Manager;
+
+    let db_path = app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join(DB_FILE_NAME);
+    let _guard = maintenance.0.write().await;
+
+    let before_bytes = database_size(&db_path);
+    sqlx::query("VACUUM")
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(db.inner())
+        .await
+        .map_err(|e| e.to_string())?;
+    let after_bytes = database_size(&db_path);
+
+    Ok(VacuumReport { before_bytes, after_bytes })
+}
+
+/// 数据库文件及其 WAL 文件的总大小
+fn database_size(db_path: &std::path::Path) -> u64 {
+    let wal_path = db_path.with_file_name(format!("{}-wal", DB_FILE_NAME));
+    [db_path.to_path_buf(), wal_path]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trending::{TrendingRepo, TrendingSource};
+
+    fn favorite(name: &str, insight: Option<&str>) -> FavoriteExport {
+        FavoriteExport {
+            repo: TrendingRepo {
+                author: "astral-sh".to_string(),
+                name: name.to_string(),
+                description: "An extremely fast Python package manager".to_string(),
+                language: "Rust".to_string(),
+                stars: "50k".to_string(),
+                forks: String::new(),
+                stars_today: String::new(),
+                stars_count: None,
+                forks_count: None,
+                url: format!("https://github.com/astral-sh/{}", name),
+                topic: "Favorite".to_string(),
+                built_by: Vec::new(),
+                topics: Vec::new(),
+                pushed_at: String::new(),
+                license: String::new(),
+                tagline: None,
+                note: None,
+                freshness_score: 0.0,
+                source: TrendingSource::default(),
+            },
+            captured_at: "2024-05-01 08:00:00".to_string(),
+            insight: insight.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_match_score() {
+        let terms = vec!["rust".to_string()];
+        assert_eq!(match_score(&terms, "rust-lang/rust", "Empowering everyone", ""), Some(3));
+        assert_eq!(match_score(&terms, "astral-sh/uv", "Python package manager written in Rust", "Rust 实现"), Some(3));
+        assert_eq!(match_score(&terms, "astral-sh/uv", "", "基于 Rust"), Some(1));
+        assert_eq!(match_score(&terms, "ollama/ollama", "Run LLMs locally", ""), None);
+
+        // 每个关键词都必须命中
+        let terms = vec!["rust".to_string(), "python".to_string()];
+        assert_eq!(match_score(&terms, "astral-sh/uv", "Python package manager written in Rust", ""), Some(4));
+        assert_eq!(match_score(&terms, "rust-lang/rust", "", ""), None);
+    }
+
+    #[test]
+    fn test_render_favorites_markdown() {
+        let markdown = render_favorites_markdown(&[favorite("uv", Some("核心架构：Rust 实现\n")), favorite("ruff", None)]);
+        assert!(markdown.starts_with("# GitHub 收藏（2 个）\n"));
+        assert!(markdown.contains("## [astral-sh/uv](https://github.com/astral-sh/uv)\n\n> An extremely fast Python package manager\n"));
+        assert!(markdown.contains("- Stars: 50k\n- 收藏时间: 2024-05-01 08:00:00\n\n### 洞察\n\n核心架构：Rust 实现\n"));
+        // 空字段与缺失的洞察不输出
+        assert!(!markdown.contains("Forks"));
+        assert_eq!(markdown.matches("### 洞察").count(), 1);
+
+        let mut noted = favorite("uv", None);
+        noted.repo.note = Some("对比 pip 的安装速度".to_string());
+        assert!(render_favorites_markdown(&[noted]).contains("备注：对比 pip 的安装速度\n"));
+
+        let json = serde_json::to_value(favorite("uv", None)).unwrap();
+        assert_eq!(json["name"], "uv");
+        assert_eq!(json["captured_at"], "2024-05-01 08:00:00");
+        assert!(json["insight"].is_null());
+    }
+
+    #[test]
+    fn test_favorite_sort_order_by() {
+        assert_eq!(FavoriteSort::default().order_by(None), "created_at DESC");
+        assert_eq!(FavoriteSort::Name.order_by(None), "name COLLATE NOCASE ASC, author COLLATE NOCASE ASC");
+        assert_eq!(FavoriteSort::Stars.order_by(Some(SortOrder::Asc)), "stars_count IS NULL, stars_count ASC, created_at DESC");
+
+        let sort: FavoriteSort = serde_json::from_str("\"created_at\"").unwrap();
+        assert_eq!(sort, FavoriteSort::CreatedAt);
+        assert!(serde_json::from_str::<FavoriteSort>("\"stars; DROP TABLE repos\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_favorites_import() {
+        let exported = serde_json::to_string(&[favorite("uv", Some("洞察"))]).unwrap();
+        let entries = parse_favorites_import(&exported).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].url, "https://github.com/astral-sh/uv");
+        assert_eq!(entries[0].captured_at.as_deref(), Some("2024-05-01 08:00:00"));
+        assert_eq!(entries[0].insight.as_deref(), Some("洞察"));
+
+        // 只有必填字段也可以导入
+        let entries = parse_favorites_import(r#"[{"author": "a", "name": "b", "url": "https://github.com/a/b"}]"#).unwrap();
+        assert_eq!(entries[0].language, "");
+
+        assert!(parse_favorites_import("{not json").unwrap_err().starts_with("导入文件格式无效"));
+        assert!(parse_favorites_import(r#"{"author": "a"}"#).is_err());
+        assert_eq!(
+            parse_favorites_import(r#"[{"author": "a", "name": "b", "url": "https://github.com/a/b"}, {"author": "a", "name": "c", "url": " "}]"#).unwrap_err(),
+            "导入文件第 2 条收藏缺少 author、name 或 url"
+        );
+    }
+}
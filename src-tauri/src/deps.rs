@@ -0,0 +1,243 @@
+//! 仓库依赖解析
+//!
+//! 从常见的包管理清单中提取直接依赖，为深度总结提供明确的技术栈信号。
+
+use serde::Serialize;
+use crate::ai::{fetch_file_content, fetch_root_entries};
+use crate::config::commands::ConfigManagerState;
+use crate::github::{CallBudget, GitHubHost};
+
+/// 支持解析的清单文件及其所属生态
+pub const MANIFESTS: &[(&str, &str)] = &[
+    ("package.json", "Node.js"),
+    ("Cargo.toml", "Rust"),
+    ("go.mod", "Go"),
+    ("requirements.txt", "Python"),
+    ("pyproject.toml", "Python"),
+    ("Gemfile", "Ruby"),
+    ("composer.json", "PHP"),
+];
+
+/// 单个清单中解析出的依赖
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoDependencies {
+    pub manifest: String,
+    pub ecosystem: String,
+    pub dependencies: Vec<String>,
+}
+
+/// 获取仓库根目录下所有受支持清单的直接依赖
+#[tauri::command]
+pub async fn get_repo_dependencies(
+    author: String,
+    name: String,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<Vec<RepoDependencies>, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    let host = GitHubHost::from_settings(&settings);
+
+    let entries = fetch_root_entries(&host, &author, &name)
+        .await
+        .ok_or_else(|| format!("无法读取仓库 {}/{} 的目录", author, name))?;
+
+    let mut result = Vec::new();
+    for (manifest, ecosystem) in MANIFESTS {
+        if !entries.iter().any(|e| !e.is_dir && e.name == *manifest) {
+            continue;
+        }
+        let Some(content) = fetch_file_content(&host, &author, &name, manifest, None, &mut CallBudget::unlimited()).await else {
+            continue;
+        };
+        let dependencies = parse_dependencies(manifest, &content);
+        if !dependencies.is_empty() {
+            result.push(RepoDependencies {
+                manifest: manifest.to_string(),
+                ecosystem: ecosystem.to_string(),
+                dependencies,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// 按清单类型解析直接依赖名称；不支持的清单返回空列表
+pub fn parse_dependencies(manifest: &str, content: &str) -> Vec<String> {
+    let mut deps = match manifest {
+        "package.json" => parse_json_keys(content, &["dependencies", "peerDependencies"]),
+        "composer.json" => parse_json_keys(content, &["require"])
+            .into_iter()
+            .filter(|d| d != "php" && !d.starts_with("ext-"))
+            .collect(),
+        "Cargo.toml" => parse_toml_table_keys(content, |section| {
+            section == "dependencies" || (section.ends_with(".dependencies") && !section.contains("dev-"))
+        }),
+        "pyproject.toml" => parse_pyproject(content),
+        "go.mod" => parse_go_mod(content),
+        "requirements.txt" => parse_requirements(content),
+        "Gemfile" => parse_gemfile(content),
+        _ => Vec::new(),
+    };
+    let mut seen = std::collections::HashSet::new();
+    deps.retain(|d| !d.is_empty() && seen.insert(d.clone()));
+    deps
+}
+
+/// JSON 清单：读取若干对象字段的键
+fn parse_json_keys(content: &str, fields: &[&str]) -> Vec<String> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .filter_map(|field| value[*field].as_object())
+        .flat_map(|deps| deps.keys().cloned())
+        .collect()
+}
+
+/// TOML 清单：读取满足条件的表中的键（简单按行解析，不处理内联表跨行等复杂写法）
+fn parse_toml_table_keys(content: &str, is_dep_table: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut deps = Vec::new();
+    let mut in_deps = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            let section = line.trim_matches(|c| c == '[' || c == ']').trim();
+            // `[dependencies.serde]` 形式的单个依赖表
+            if let Some(dep) = section.strip_prefix("dependencies.") {
+                deps.push(dep.trim_matches('"').to_string());
+                in_deps = false;
+            } else {
+                in_deps = is_dep_table(section);
+            }
+            continue;
+        }
+        if in_deps && !line.starts_with('#') {
+            if let Some((key, _)) = line.split_once('=') {
+                deps.push(key.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+    deps
+}
+
+/// pyproject.toml：支持 PEP 621 的 `dependencies = [...]` 与 Poetry 的依赖表
+fn parse_pyproject(content: &str) -> Vec<String> {
+    let mut deps = parse_toml_table_keys(content, |section| section == "tool.poetry.dependencies");
+    deps.retain(|d| d != "python");
+
+    let mut in_project = false;
+    let mut in_array = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_project = line == "[project]";
+            in_array = false;
+            continue;
+        }
+        if in_project && line.starts_with("dependencies") && line.contains('[') {
+            in_array = true;
+        }
+        if in_array {
+            for item in line.split(',') {
+                let item = item.trim().trim_start_matches("dependencies").trim().trim_start_matches('=').trim();
+                let item = item.trim_matches(|c| c == '[' || c == ']').trim().trim_matches(|c| c == '"' || c == '\'');
+                if !item.is_empty() {
+                    deps.push(requirement_name(item));
+                }
+            }
+            if line.contains(']') {
+                in_array = false;
+            }
+        }
+    }
+    deps
+}
+
+/// go.mod：`require` 单行与块写法，忽略间接依赖
+fn parse_go_mod(content: &str) -> Vec<String> {
+    let mut deps = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with("require (") {
+            in_block = true;
+            continue;
+        }
+        if in_block && line.starts_with(')') {
+            in_block = false;
+            continue;
+        }
+        let spec = if in_block {
+            line
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest
+        } else {
+            continue;
+        };
+        if spec.contains("// indirect") {
+            continue;
+        }
+        if let Some(module) = spec.split_whitespace().next() {
+            deps.push(module.to_string());
+        }
+    }
+    deps
+}
+
+/// requirements.txt：每行一个依赖，忽略注释与 pip 选项
+fn parse_requirements(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .map(requirement_name)
+        .collect()
+}
+
+/// 从 `requests>=2.0`、`uvicorn[standard]` 等写法中取出包名
+fn requirement_name(spec: &str) -> String {
+    spec.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .next()
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Gemfile：`gem 'rails', '~> 7.0'`
+fn parse_gemfile(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("gem "))
+        .filter_map(|rest| rest.split(',').next())
+        .map(|name| name.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dependencies() {
+        let cargo = "[package]\nname = \"app\"\n\n[dependencies]\nserde = { version = \"1\" }\ntokio = \"1\"\n\n[dev-dependencies]\ntempfile = \"3\"\n\n[dependencies.reqwest]\nversion = \"0.12\"\n";
+        assert_eq!(parse_dependencies("Cargo.toml", cargo), vec!["serde", "tokio", "reqwest"]);
+
+        let package = r#"{"dependencies": {"react": "^18"}, "devDependencies": {"vite": "^5"}}"#;
+        assert_eq!(parse_dependencies("package.json", package), vec!["react"]);
+
+        let go_mod = "module x\n\nrequire (\n\tgithub.com/gin-gonic/gin v1.9.1\n\tgolang.org/x/sys v0.1.0 // indirect\n)\nrequire github.com/spf13/cobra v1.8.0\n";
+        assert_eq!(parse_dependencies("go.mod", go_mod), vec!["github.com/gin-gonic/gin", "github.com/spf13/cobra"]);
+
+        let requirements = "# web\nfastapi>=0.100\nuvicorn[standard]\n-r dev.txt\n";
+        assert_eq!(parse_dependencies("requirements.txt", requirements), vec!["fastapi", "uvicorn"]);
+
+        let pyproject = "[project]\nname = \"x\"\ndependencies = [\n  \"httpx>=0.27\",\n  \"pydantic\",\n]\n";
+        assert_eq!(parse_dependencies("pyproject.toml", pyproject), vec!["httpx", "pydantic"]);
+
+        let gemfile = "source 'https://rubygems.org'\ngem 'rails', '~> 7.0'\ngem \"puma\"\n";
+        assert_eq!(parse_dependencies("Gemfile", gemfile), vec!["rails", "puma"]);
+    }
+}
@@ -0,0 +1,123 @@
+//! 最近错误记录
+//!
+//! 在内存中保留最近的命令错误（时间、来源命令与脱敏后的错误信息），
+//! 用户反馈“偶尔失败”时无需开启详细日志并重启即可查看。
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+use serde::Serialize;
+
+/// 最多保留的错误条数
+const MAX_RECENT_ERRORS: usize = 100;
+
+/// 一条错误记录
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorRecord {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// 出错的命令
+    pub context: String,
+    pub message: String,
+}
+
+/// 最近错误的环形缓冲区（由 Tauri 托管）
+#[derive(Default)]
+pub struct RecentErrors {
+    errors: Mutex<VecDeque<ErrorRecord>>,
+    /// 配置中的密钥，记录前从错误信息中脱敏
+    secrets: RwLock<Vec<String>>,
+}
+
+impl RecentErrors {
+    /// 更新需要脱敏的密钥（配置保存后调用）
+    pub fn set_secrets(&self, secrets: Vec<String>) {
+        if let Ok(mut current) = self.secrets.write() {
+            *current = secrets;
+        }
+    }
+
+    /// 记录一条错误，密钥会被脱敏，超出容量时丢弃最旧的记录
+    pub fn record(&self, context: &str, message: &str) {
+        let message = match self.secrets.read() {
+            Ok(secrets) => crate::audit::redact(message, &secrets),
+            Err(_) => crate::audit::redact(message, &[]),
+        };
+        let Ok(mut errors) = self.errors.lock() else {
+            return;
+        };
+        if errors.len() >= MAX_RECENT_ERRORS {
+            errors.pop_front();
+        }
+        errors.push_back(ErrorRecord {
+            timestamp: chrono::Utc::now(),
+            context: context.to_string(),
+            message,
+        });
+    }
+
+    /// 最近的错误，最新的在前
+    pub fn recent(&self, limit: usize) -> Vec<ErrorRecord> {
+        self.errors
+            .lock()
+            .map(|errors| errors.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.clear();
+        }
+    }
+}
+
+/// 记录前端调用命令失败时的错误
+///
+/// 前端在统一的 `invoke` 封装中捕获所有命令错误后调用，无需每个命令各自记录。
+#[tauri::command]
+pub async fn record_command_error(
+    command: String,
+    message: String,
+    errors: tauri::State<'_, RecentErrors>,
+) -> Result<(), String> {
+    errors.record(&command, &message);
+    Ok(())
+}
+
+/// 获取最近的命令错误
+#[tauri::command]
+pub async fn get_recent_errors(
+    limit: Option<usize>,
+    errors: tauri::State<'_, RecentErrors>,
+) -> Result<Vec<ErrorRecord>, String> {
+    Ok(errors.recent(limit.unwrap_or(MAX_RECENT_ERRORS)))
+}
+
+/// 清空最近错误
+#[tauri::command]
+pub async fn clear_recent_errors(errors: tauri::State<'_, RecentErrors>) -> Result<(), String> {
+    errors.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_errors_ring_buffer() {
+        let errors = RecentErrors::default();
+        for i in 0..MAX_RECENT_ERRORS + 5 {
+            errors.record("summarize_repo", &format!("error {}", i));
+        }
+        let recent = errors.recent(usize::MAX);
+        assert_eq!(recent.len(), MAX_RECENT_ERRORS);
+        assert_eq!(recent[0].message, format!("error {}", MAX_RECENT_ERRORS + 4));
+        assert_eq!(recent.last().unwrap().message, "error 5");
+
+        errors.record("list_models", "401 invalid key sk-abcdefghijklmnopqrstuvwx");
+        assert_eq!(errors.recent(1)[0].message, "401 invalid key [REDACTED]");
+
+        errors.set_secrets(vec!["my-github-token".to_string()]);
+        errors.record("get_trending", "bad credentials: my-github-token");
+        assert_eq!(errors.recent(1)[0].message, "bad credentials: [REDACTED]");
+    }
+}
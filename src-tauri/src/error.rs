@@ -0,0 +1,104 @@
+//! 命令错误
+//!
+//! 命令返回带分类的错误，前端可以按类型区分处理（例如认证失败时提示重新填写 API Key），
+//! 而不必解析错误文案。
+
+use serde::Serialize;
+use crate::llm::LLMError;
+
+/// 错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ErrorKind {
+    /// API Key 无效或无权限
+    Auth,
+    /// 网络错误、超时、服务端错误或离线模式
+    Network,
+    /// 额度不足或触发限流
+    Quota,
+    /// 配置缺失或无效
+    Config,
+    /// 响应解析失败
+    Parse,
+    /// 模型配置、模型或仓库不存在
+    NotFound,
+    /// 用户取消了请求
+    Cancelled,
+    /// 其他错误
+    Other,
+}
+
+/// 返回给前端的错误
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct CommandError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl CommandError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Config, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn cancelled() -> Self {
+        Self::new(ErrorKind::Cancelled, "已取消生成")
+    }
+}
+
+impl From<LLMError> for CommandError {
+    fn from(error: LLMError) -> Self {
+        let kind = match &error {
+            LLMError::AuthenticationFailed(_) => ErrorKind::Auth,
+            LLMError::NetworkError(_) => ErrorKind::Network,
+            LLMError::InsufficientQuota => ErrorKind::Quota,
+            LLMError::ConfigurationError(_) => ErrorKind::Config,
+            LLMError::ParseError(_) => ErrorKind::Parse,
+            LLMError::ModelUnavailable(_) => ErrorKind::NotFound,
+            LLMError::RequestFailed(_) | LLMError::Unknown(_) => ErrorKind::Other,
+        };
+        Self::new(kind, error.to_string())
+    }
+}
+
+/// 尚未分类的错误文案
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+}
+
+/// 供仍返回 `Result<_, String>` 的调用方使用
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_llm_error() {
+        let error = CommandError::from(LLMError::from_status_code(401, "invalid key"));
+        assert_eq!(error.kind, ErrorKind::Auth);
+        assert_eq!(error.message, "认证失败: invalid key");
+        assert_eq!(CommandError::from(LLMError::InsufficientQuota).kind, ErrorKind::Quota);
+        assert_eq!(CommandError::from(LLMError::from_status_code(502, "")).kind, ErrorKind::Network);
+
+        let json = serde_json::to_value(CommandError::not_found("missing")).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "NotFound", "message": "missing" }));
+    }
+}
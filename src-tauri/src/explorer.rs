@@ -0,0 +1,181 @@
+//! 仓库文件浏览
+//!
+//! 基于 GitHub contents API 逐层浏览仓库目录，方便用户在收藏或总结前先了解项目结构。
+
+use serde::Serialize;
+use crate::config::commands::ConfigManagerState;
+use crate::github::{fetch_repo_metadata, send_with_retry, status_error, GitHubHost};
+use crate::i18n::{tr, ErrorCode};
+
+/// contents API 单个目录最多返回的条目数，超过时改用 git trees API
+const CONTENTS_API_LIMIT: usize = 1000;
+
+/// 条目类型
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Dir,
+    File,
+    Symlink,
+    Submodule,
+}
+
+impl EntryKind {
+    /// 解析 contents API（dir/file/symlink/submodule）或 trees API（tree/blob/commit）的类型字段
+    fn parse(kind: &str) -> Self {
+        match kind {
+            "dir" | "tree" => EntryKind::Dir,
+            "symlink" => EntryKind::Symlink,
+            "submodule" | "commit" => EntryKind::Submodule,
+            _ => EntryKind::File,
+        }
+    }
+}
+
+/// 目录中的一个条目
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: EntryKind,
+    pub size: u64,
+}
+
+/// 目录浏览结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoDirectory {
+    pub branch: String,
+    pub path: String,
+    pub entries: Vec<RepoEntry>,
+    /// 目录过大、GitHub 只返回了部分条目
+    pub truncated: bool,
+}
+
+/// 浏览仓库某个目录（path 为空表示根目录）
+#[tauri::command]
+pub async fn browse_repo_path(
+    author: String,
+    name: String,
+    path: Option<String>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<RepoDirectory, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    let host = GitHubHost::from_settings(&settings);
+    let path = path.unwrap_or_default().trim_matches('/').to_string();
+
+    // 同时用于确认仓库可访问（私有/不存在时返回明确错误）
+    let metadata = fetch_repo_metadata(&host, &author, &name).await?;
+    let branch = metadata.default_branch.unwrap_or_else(|| "main".to_string());
+
+    let client = crate::github::http_client();
+
+    let url = format!("{}/repos/{}/{}/contents/{}?ref={}", host.api_base(), author, name, path, branch);
+    let resp = send_with_retry(host.authorize(client.get(&url)))
+        .await
+        .map_err(|e| tr(ErrorCode::GitHubRequestFailed, &[&e]))?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(tr(ErrorCode::PathNotFound, &[&path]));
+    }
+    if !resp.status().is_success() {
+        return Err(status_error(&resp));
+    }
+    let value = resp.json::<serde_json::Value>().await.map_err(|e| tr(ErrorCode::ParseFailed, &[&e]))?;
+    let items = value.as_array().ok_or_else(|| tr(ErrorCode::NotADirectory, &[&path]))?;
+
+    let (mut entries, truncated) = if items.len() >= CONTENTS_API_LIMIT {
+        fetch_tree_entries(client, &host, &author, &name, &branch, &path).await?
+    } else {
+        (parse_contents_entries(items), false)
+    };
+    sort_entries(&mut entries);
+
+    Ok(RepoDirectory { branch, path, entries, truncated })
+}
+
+/// 使用 git trees API 获取超大目录的完整条目列表
+async fn fetch_tree_entries(
+    client: &reqwest::Client,
+    host: &GitHubHost,
+    author: &str,
+    name: &str,
+    branch: &str,
+    path: &str,
+) -> Result<(Vec<RepoEntry>, bool), String> {
+    let tree_ish = if path.is_empty() { branch.to_string() } else { format!("{}:{}", branch, path) };
+    let url = format!("{}/repos/{}/{}/git/trees/{}", host.api_base(), author, name, tree_ish);
+    let resp = send_with_retry(host.authorize(client.get(&url)))
+        .await
+        .map_err(|e| tr(ErrorCode::GitHubRequestFailed, &[&e]))?;
+    if !resp.status().is_success() {
+        return Err(status_error(&resp));
+    }
+    let value = resp.json::<serde_json::Value>().await.map_err(|e| tr(ErrorCode::ParseFailed, &[&e]))?;
+    Ok(parse_tree_entries(&value, path))
+}
+
+/// 解析 contents API 返回的目录条目
+fn parse_contents_entries(items: &[serde_json::Value]) -> Vec<RepoEntry> {
+    items.iter().map(|item| RepoEntry {
+        name: item["name"].as_str().unwrap_or("").to_string(),
+        path: item["path"].as_str().unwrap_or("").to_string(),
+        kind: EntryKind::parse(item["type"].as_str().unwrap_or("")),
+        size: item["size"].as_u64().unwrap_or(0),
+    }).collect()
+}
+
+/// 解析 git trees API 的返回值，条目路径相对于所浏览的目录
+fn parse_tree_entries(value: &serde_json::Value, parent: &str) -> (Vec<RepoEntry>, bool) {
+    let entries = value["tree"].as_array().map(|items| {
+        items.iter().map(|item| {
+            let name = item["path"].as_str().unwrap_or("").to_string();
+            let path = if parent.is_empty() { name.clone() } else { format!("{}/{}", parent, name) };
+            RepoEntry {
+                name,
+                path,
+                kind: EntryKind::parse(item["type"].as_str().unwrap_or("")),
+                size: item["size"].as_u64().unwrap_or(0),
+            }
+        }).collect()
+    }).unwrap_or_default();
+    (entries, value["truncated"].as_bool().unwrap_or(false))
+}
+
+/// 目录在前，其余按名称排序
+fn sort_entries(entries: &mut [RepoEntry]) {
+    entries.sort_by(|a, b| {
+        (a.kind != EntryKind::Dir)
+            .cmp(&(b.kind != EntryKind::Dir))
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_tree_entries() {
+        let value = json!({
+            "tree": [
+                { "path": "main.rs", "type": "blob", "size": 120 },
+                { "path": "llm", "type": "tree" },
+                { "path": "vendor", "type": "commit" }
+            ],
+            "truncated": true
+        });
+        let (mut entries, truncated) = parse_tree_entries(&value, "src");
+        sort_entries(&mut entries);
+
+        assert!(truncated);
+        assert_eq!(entries[0].path, "src/llm");
+        assert_eq!(entries[0].kind, EntryKind::Dir);
+        assert_eq!(entries[1].name, "main.rs");
+        assert_eq!(entries[1].size, 120);
+        assert_eq!(entries[2].kind, EntryKind::Submodule);
+    }
+}
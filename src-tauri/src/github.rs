@@ -0,0 +1,504 @@
+//! GitHub 请求辅助
+//!
+//! 处理 GitHub 的二级限流（secondary rate limit）：响应为 403/429 并携带 `Retry-After` 头，
+//! 与主配额耗尽不同，等待指定秒数后即可恢复。
+//!
+//! 同时根据用户配置的主机（github.com 或 GitHub Enterprise）生成 API 与原始文件地址，
+//! 并在配置了个人访问令牌时为请求附加认证头。
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use crate::models::AppSettings;
+use crate::i18n::{tr, ErrorCode};
+
+/// 公共 GitHub 的主机名
+const PUBLIC_HOST: &str = "github.com";
+
+/// 共享 HTTP 客户端的连接超时
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 共享 HTTP 客户端的默认请求超时（单个请求可另行覆盖）
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 共享的 HTTP 客户端，复用连接池与 TLS 会话；深度模式一次总结会发起多个 GitHub 请求
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent("github-capture")
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// 获取共享的 HTTP 客户端（GitHub API、原始文件与趋势榜页面共用）
+///
+/// 模型提供商需要自定义超时与代理，仍各自构建客户端。
+pub fn http_client() -> &'static reqwest::Client {
+    &HTTP_CLIENT
+}
+
+/// 当前使用的 GitHub 主机
+#[derive(Clone)]
+pub struct GitHubHost {
+    host: String,
+    token: Option<String>,
+}
+
+impl std::fmt::Debug for GitHubHost {
+    // 令牌不写入日志
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubHost")
+            .field("host", &self.host)
+            .field("token", &self.token.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+impl GitHubHost {
+    /// 根据应用设置创建，未配置时使用 github.com
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let host = settings
+            .github_host
+            .as_deref()
+            .map(normalize_host)
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| PUBLIC_HOST.to_string());
+        let token = settings
+            .github_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+        Self { host, token }
+    }
+
+    /// 配置了个人访问令牌时附加 `Authorization: Bearer` 头
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    fn is_public(&self) -> bool {
+        self.host == PUBLIC_HOST
+    }
+
+    /// 网页主机名
+    pub fn web_host(&self) -> &str {
+        &self.host
+    }
+
+    /// REST API 基础地址（Enterprise 为 `https://{host}/api/v3`）
+    pub fn api_base(&self) -> String {
+        if self.is_public() {
+            "https://api.github.com".to_string()
+        } else {
+            format!("https://{}/api/v3", self.host)
+        }
+    }
+
+    /// 仓库中某个文件的原始内容地址
+    pub fn raw_file_url(&self, owner: &str, name: &str, branch: &str, path: &str) -> String {
+        if self.is_public() {
+            format!("https://raw.githubusercontent.com/{}/{}/refs/heads/{}/{}", owner, name, branch, path)
+        } else {
+            format!("https://{}/{}/{}/raw/{}/{}", self.host, owner, name, branch, path)
+        }
+    }
+
+    /// 从仓库地址解析 owner/name，主机与配置不符时返回错误
+    pub fn parse_repo_url(&self, url: &str) -> Result<(String, String), String> {
+        let without_scheme = url
+            .trim()
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let (host, path) = without_scheme.split_once('/').unwrap_or((without_scheme, ""));
+
+        let host = normalize_host(host);
+        let host = if self.is_public() { host.trim_start_matches("www.").to_string() } else { host };
+        if host != self.host {
+            return Err(format!("仓库地址 {} 与配置的 GitHub 主机 {} 不匹配", url, self.host));
+        }
+
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        match (segments.next(), segments.next()) {
+            (Some(owner), Some(name)) => {
+                Ok((owner.to_string(), name.trim_end_matches(".git").to_string()))
+            }
+            _ => Err(format!("无法从地址中解析仓库: {}", url)),
+        }
+    }
+}
+
+/// 仓库元数据（`GET /repos/{owner}/{name}` 的部分字段）
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RepoMetadata {
+    pub name: String,
+    pub owner: RepoOwner,
+    pub html_url: String,
+    pub description: Option<String>,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub stargazers_count: u64,
+    #[serde(default)]
+    pub forks_count: u64,
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub has_pages: bool,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+}
+
+/// 仓库所有者
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RepoOwner {
+    pub login: String,
+}
+
+/// 获取仓库元数据
+pub async fn fetch_repo_metadata(host: &GitHubHost, owner: &str, name: &str) -> Result<RepoMetadata, String> {
+    let client = http_client();
+    let url = format!("{}/repos/{}/{}", host.api_base(), owner, name);
+
+    let resp = send_with_retry(host.authorize(client.get(&url)))
+        .await
+        .map_err(|e| tr(ErrorCode::GitHubRequestFailed, &[&e]))?;
+    if resp.status() == StatusCode::NOT_FOUND {
+        return Err(tr(ErrorCode::RepoNotAccessible, &[&format!("{}/{}", owner, name)]));
+    }
+    if !resp.status().is_success() {
+        return Err(status_error(&resp));
+    }
+    let metadata = resp.json::<RepoMetadata>().await.map_err(|e| tr(ErrorCode::ParseFailed, &[&e]))?;
+    remember_default_branch(host, owner, name, metadata.default_branch.clone());
+    Ok(metadata)
+}
+
+/// 默认分支缓存的有效期
+const DEFAULT_BRANCH_TTL: Duration = Duration::from_secs(10 * 60);
+
+type BranchCache = HashMap<String, (Option<String>, Instant)>;
+
+/// 已解析的默认分支（None 表示解析失败），键为 `host/owner/name`
+static DEFAULT_BRANCHES: LazyLock<Mutex<BranchCache>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn default_branch_key(host: &GitHubHost, owner: &str, name: &str) -> String {
+    format!("{}/{}/{}", host.web_host(), owner, name).to_lowercase()
+}
+
+/// 记录仓库的默认分支，同一仓库在有效期内复用，避免每个文件都查询一次元数据
+pub fn remember_default_branch(host: &GitHubHost, owner: &str, name: &str, branch: Option<String>) {
+    if let Ok(mut cache) = DEFAULT_BRANCHES.lock() {
+        cache.retain(|_, (_, at)| at.elapsed() < DEFAULT_BRANCH_TTL);
+        cache.insert(default_branch_key(host, owner, name), (branch, Instant::now()));
+    }
+}
+
+/// 读取缓存的默认分支：外层 None 表示尚未解析，内层 None 表示上次解析失败
+pub fn cached_default_branch(host: &GitHubHost, owner: &str, name: &str) -> Option<Option<String>> {
+    let cache = DEFAULT_BRANCHES.lock().ok()?;
+    cache
+        .get(&default_branch_key(host, owner, name))
+        .filter(|(_, at)| at.elapsed() < DEFAULT_BRANCH_TTL)
+        .map(|(branch, _)| branch.clone())
+}
+
+/// 解析仓库的默认分支（带缓存）；元数据请求失败时返回 None，由调用方回退到 main/master
+pub async fn resolve_default_branch(host: &GitHubHost, owner: &str, name: &str) -> Option<String> {
+    if let Some(branch) = cached_default_branch(host, owner, name) {
+        return branch;
+    }
+    match fetch_repo_metadata(host, owner, name).await {
+        Ok(metadata) => metadata.default_branch,
+        Err(e) => {
+            log::warn!("解析默认分支失败 {}/{}: {}", owner, name, e);
+            remember_default_branch(host, owner, name, None);
+            None
+        }
+    }
+}
+
+/// 单次操作允许发起的 GitHub 请求额度，避免一个仓库耗尽限流配额
+#[derive(Debug, Clone)]
+pub struct CallBudget {
+    remaining: u32,
+}
+
+impl CallBudget {
+    pub fn new(limit: u32) -> Self {
+        Self { remaining: limit }
+    }
+
+    /// 不限额度（用于不需要封顶的调用方）
+    pub fn unlimited() -> Self {
+        Self::new(u32::MAX)
+    }
+
+    /// 占用一次请求额度，额度用尽时返回 false
+    pub fn try_take(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// 检查仓库是否可以读取；GitHub 对私有仓库同样返回 404
+///
+/// 只有明确的 404 才视为不可访问，限流或网络错误时放行，交由后续抓取处理。
+pub async fn ensure_repo_accessible(host: &GitHubHost, owner: &str, name: &str) -> Result<(), String> {
+    let client = http_client();
+    let url = format!("{}/repos/{}/{}", host.api_base(), owner, name);
+
+    match send_with_retry(host.authorize(client.get(&url))).await {
+        Ok(resp) if resp.status() == StatusCode::NOT_FOUND => {
+            Err(tr(ErrorCode::RepoNotAccessible, &[&format!("{}/{}", owner, name)]))
+        }
+        Ok(resp) if resp.status().is_success() => {
+            // 顺便记录默认分支，后续抓取文件时无需再查询
+            if let Ok(metadata) = resp.json::<RepoMetadata>().await {
+                remember_default_branch(host, owner, name, metadata.default_branch);
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 标准化主机名：去掉协议、路径与尾部斜杠并转为小写
+fn normalize_host(host: &str) -> String {
+    host.trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// 遇到二级限流时的最大重试次数
+const MAX_SECONDARY_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// 单次等待的上限，避免 `Retry-After` 过大时长时间阻塞
+const MAX_RETRY_AFTER_SECS: u64 = 60;
+
+/// 发送 GitHub 请求，遇到二级限流时按 `Retry-After` 等待后重试
+pub async fn send_with_retry(request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let mut request = request;
+    let mut attempt = 0;
+
+    loop {
+        let next_request = request.try_clone();
+        let response = request.send().await?;
+        record_rate_limit(&response);
+
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok());
+        let delay = secondary_rate_limit_delay(response.status().as_u16(), retry_after);
+
+        match (delay, next_request) {
+            (Some(delay), Some(next)) if attempt < MAX_SECONDARY_RATE_LIMIT_RETRIES => {
+                attempt += 1;
+                log::warn!(
+                    "GitHub 二级限流，{} 秒后重试（第 {} 次）",
+                    delay.as_secs(),
+                    attempt
+                );
+                tokio::time::sleep(delay).await;
+                request = next;
+            }
+            _ => return Ok(response),
+        }
+    }
+}
+
+/// 非成功响应的错误信息；主配额耗尽时提示恢复时间，而不是只返回状态码
+pub fn status_error(response: &Response) -> String {
+    let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+    match rate_limit_reset(response.status().as_u16(), header("x-ratelimit-remaining"), header("x-ratelimit-reset")) {
+        Some(reset) => {
+            let reset = chrono::DateTime::from_timestamp(reset, 0)
+                .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| reset.to_string());
+            tr(ErrorCode::GitHubRateLimited, &[&reset])
+        }
+        None => tr(ErrorCode::GitHubApiError, &[&response.status()]),
+    }
+}
+
+/// GitHub API 主配额状态（取自最近一次响应的 `X-RateLimit-*` 头）
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct RateLimitState {
+    pub remaining: u32,
+    pub limit: u32,
+    /// 配额重置时间（Unix 时间戳，秒）
+    pub reset_at: i64,
+}
+
+/// 最近一次记录的主配额状态
+static RATE_LIMIT: Mutex<Option<RateLimitState>> = Mutex::new(None);
+
+/// 解析主配额响应头；搜索 API 等独立计数的配额（`X-RateLimit-Resource` 不为 `core`）不记录
+fn parse_rate_limit(
+    resource: Option<&str>,
+    limit: Option<&str>,
+    remaining: Option<&str>,
+    reset: Option<&str>,
+) -> Option<RateLimitState> {
+    if resource.is_some_and(|r| r.trim() != "core") {
+        return None;
+    }
+    Some(RateLimitState {
+        remaining: remaining?.trim().parse().ok()?,
+        limit: limit?.trim().parse().ok()?,
+        reset_at: reset?.trim().parse().ok()?,
+    })
+}
+
+/// 记录响应中的配额状态（原始文件等不带配额头的响应会被忽略）
+fn record_rate_limit(response: &Response) {
+    let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+    let state = parse_rate_limit(
+        header("x-ratelimit-resource"),
+        header("x-ratelimit-limit"),
+        header("x-ratelimit-remaining"),
+        header("x-ratelimit-reset"),
+    );
+    if let (Some(state), Ok(mut latest)) = (state, RATE_LIMIT.lock()) {
+        *latest = Some(state);
+    }
+}
+
+/// 获取最近一次记录的 GitHub API 配额；已过重置时间时视为配额已恢复，尚未发起请求时返回 None
+#[tauri::command]
+pub fn get_github_rate_limit() -> Option<RateLimitState> {
+    let state = (*RATE_LIMIT.lock().ok()?)?;
+    if state.reset_at <= chrono::Utc::now().timestamp() {
+        return Some(RateLimitState { remaining: state.limit, ..state });
+    }
+    Some(state)
+}
+
+/// 判断响应是否为主配额耗尽（403/429 且 `X-RateLimit-Remaining` 为 0），返回重置时间戳
+fn rate_limit_reset(status: u16, remaining: Option<&str>, reset: Option<&str>) -> Option<i64> {
+    if status != 403 && status != 429 {
+        return None;
+    }
+    if remaining?.trim() != "0" {
+        return None;
+    }
+    reset?.trim().parse::<i64>().ok()
+}
+
+/// 判断响应是否为二级限流，返回需要等待的时长
+fn secondary_rate_limit_delay(status: u16, retry_after: Option<&str>) -> Option<Duration> {
+    if status != 403 && status != 429 {
+        return None;
+    }
+    let secs = retry_after?.trim().parse::<u64>().ok()?;
+    Some(Duration::from_secs(secs.min(MAX_RETRY_AFTER_SECS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_branch_cache() {
+        let host = GitHubHost::from_settings(&AppSettings::default());
+        assert_eq!(cached_default_branch(&host, "cache-test", "repo"), None);
+        remember_default_branch(&host, "Cache-Test", "Repo", Some("develop".to_string()));
+        assert_eq!(cached_default_branch(&host, "cache-test", "repo"), Some(Some("develop".to_string())));
+        remember_default_branch(&host, "cache-test", "missing", None);
+        assert_eq!(cached_default_branch(&host, "cache-test", "missing"), Some(None));
+    }
+
+    #[test]
+    fn test_call_budget() {
+        let mut budget = CallBudget::new(2);
+        assert!(budget.try_take());
+        assert!(!budget.is_exhausted());
+        assert!(budget.try_take());
+        assert!(budget.is_exhausted());
+        assert!(!budget.try_take());
+    }
+
+    #[test]
+    fn test_secondary_rate_limit_delay() {
+        assert_eq!(secondary_rate_limit_delay(403, Some("5")), Some(Duration::from_secs(5)));
+        assert_eq!(secondary_rate_limit_delay(429, Some("3600")), Some(Duration::from_secs(MAX_RETRY_AFTER_SECS)));
+        // 没有 Retry-After 的 403 是主配额耗尽或权限问题，不重试
+        assert_eq!(secondary_rate_limit_delay(403, None), None);
+        assert_eq!(secondary_rate_limit_delay(200, Some("5")), None);
+        assert_eq!(secondary_rate_limit_delay(403, Some("soon")), None);
+    }
+
+    #[test]
+    fn test_rate_limit_reset() {
+        assert_eq!(rate_limit_reset(403, Some("0"), Some("1700000000")), Some(1_700_000_000));
+        assert_eq!(rate_limit_reset(429, Some("0"), Some("1700000000")), Some(1_700_000_000));
+        // 仍有剩余额度的 403 是权限问题
+        assert_eq!(rate_limit_reset(403, Some("12"), Some("1700000000")), None);
+        assert_eq!(rate_limit_reset(403, None, None), None);
+        assert_eq!(rate_limit_reset(404, Some("0"), Some("1700000000")), None);
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let state = RateLimitState { remaining: 42, limit: 60, reset_at: 1_700_000_000 };
+        assert_eq!(parse_rate_limit(Some("core"), Some("60"), Some("42"), Some("1700000000")), Some(state));
+        assert_eq!(parse_rate_limit(None, Some("60"), Some("42"), Some("1700000000")), Some(state));
+        // 搜索 API 的配额单独计数
+        assert_eq!(parse_rate_limit(Some("search"), Some("10"), Some("9"), Some("1700000000")), None);
+        assert_eq!(parse_rate_limit(None, None, None, None), None);
+    }
+
+    #[test]
+    fn test_token_redacted() {
+        let settings = AppSettings {
+            github_token: Some(" ghp_secret ".to_string()),
+            ..Default::default()
+        };
+        let host = GitHubHost::from_settings(&settings);
+        assert_eq!(host.token.as_deref(), Some("ghp_secret"));
+        assert!(!format!("{:?}", host).contains("ghp_secret"));
+    }
+
+    #[test]
+    fn test_parse_repo_url() {
+        let public = GitHubHost::from_settings(&AppSettings::default());
+        assert_eq!(
+            public.parse_repo_url("https://github.com/rust-lang/rust").unwrap(),
+            ("rust-lang".to_string(), "rust".to_string())
+        );
+        assert_eq!(
+            public.parse_repo_url("https://www.github.com/tauri-apps/tauri.git/").unwrap(),
+            ("tauri-apps".to_string(), "tauri".to_string())
+        );
+        assert!(public.parse_repo_url("https://github.mycompany.com/team/app").is_err());
+        assert!(public.parse_repo_url("https://github.com/only-owner").is_err());
+
+        let settings = AppSettings {
+            github_host: Some("https://GitHub.MyCompany.com/".to_string()),
+            ..Default::default()
+        };
+        let enterprise = GitHubHost::from_settings(&settings);
+        assert_eq!(
+            enterprise.parse_repo_url("https://github.mycompany.com/team/app").unwrap(),
+            ("team".to_string(), "app".to_string())
+        );
+        assert!(enterprise.parse_repo_url("https://github.com/team/app").is_err());
+        assert_eq!(enterprise.api_base(), "https://github.mycompany.com/api/v3");
+    }
+}
@@ -0,0 +1,121 @@
+//! 错误信息国际化
+//!
+//! 错误按错误码定义，按界面语言设置选择中文或英文文案。
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use serde::{Deserialize, Serialize};
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Zh,
+    En,
+}
+
+impl Language {
+    /// 解析界面语言代码（如 `zh`、`zh-CN`、`en-US`），未设置时保持中文
+    pub fn from_code(code: Option<&str>) -> Self {
+        match code.map(|c| c.trim().to_lowercase()) {
+            Some(c) if c.starts_with("en") => Language::En,
+            _ => Language::Zh,
+        }
+    }
+}
+
+/// 当前界面语言（0 = 中文，1 = 英文）
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// 切换错误信息使用的语言（启动时与更新设置时调用）
+pub fn set_language(language: Language) {
+    let value = match language {
+        Language::Zh => 0,
+        Language::En => 1,
+    };
+    CURRENT_LANGUAGE.store(value, Ordering::Relaxed);
+}
+
+pub fn current_language() -> Language {
+    match CURRENT_LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::En,
+        _ => Language::Zh,
+    }
+}
+
+/// 错误码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorCode {
+    ModelConfigNotFound,
+    MissingCredentials,
+    RepoNotAccessible,
+    GitHubRequestFailed,
+    GitHubApiError,
+    GitHubRateLimited,
+    ParseFailed,
+    PathNotFound,
+    NotADirectory,
+    OfflineMode,
+}
+
+impl ErrorCode {
+    /// 错误文案模板，`{}` 按顺序替换为参数
+    fn template(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (ErrorCode::ModelConfigNotFound, Language::Zh) => "找不到模型配置: {}",
+            (ErrorCode::ModelConfigNotFound, Language::En) => "Model configuration not found: {}",
+            (ErrorCode::MissingCredentials, Language::Zh) => "必须提供 API Key 或模型配置 ID",
+            (ErrorCode::MissingCredentials, Language::En) => "An API key or model configuration ID is required",
+            (ErrorCode::RepoNotAccessible, Language::Zh) => "仓库 {} 不存在或为私有仓库，无法读取",
+            (ErrorCode::RepoNotAccessible, Language::En) => "Repository {} not found or private",
+            (ErrorCode::GitHubRequestFailed, Language::Zh) => "GitHub API 请求失败: {}",
+            (ErrorCode::GitHubRequestFailed, Language::En) => "GitHub API request failed: {}",
+            (ErrorCode::GitHubApiError, Language::Zh) => "GitHub API 错误: {}",
+            (ErrorCode::GitHubApiError, Language::En) => "GitHub API error: {}",
+            (ErrorCode::GitHubRateLimited, Language::Zh) => "GitHub API 请求额度已用完，将于 {} 恢复；可在设置中配置 GitHub Token 提高额度",
+            (ErrorCode::GitHubRateLimited, Language::En) => "GitHub API rate limit exceeded; resets at {}. Configure a GitHub token in settings for a higher limit",
+            (ErrorCode::ParseFailed, Language::Zh) => "解析失败: {}",
+            (ErrorCode::ParseFailed, Language::En) => "Failed to parse response: {}",
+            (ErrorCode::PathNotFound, Language::Zh) => "路径不存在: {}",
+            (ErrorCode::PathNotFound, Language::En) => "Path not found: {}",
+            (ErrorCode::NotADirectory, Language::Zh) => "{} 不是目录",
+            (ErrorCode::NotADirectory, Language::En) => "{} is not a directory",
+            (ErrorCode::OfflineMode, Language::Zh) => "已开启离线模式，该操作需要联网",
+            (ErrorCode::OfflineMode, Language::En) => "Offline mode is enabled; this operation requires network access",
+        }
+    }
+
+    /// 按指定语言生成错误文案
+    pub fn message_in(&self, language: Language, args: &[&dyn std::fmt::Display]) -> String {
+        let mut parts = self.template(language).split("{}");
+        let mut message = parts.next().unwrap_or_default().to_string();
+        for (i, part) in parts.enumerate() {
+            if let Some(arg) = args.get(i) {
+                message.push_str(&arg.to_string());
+            }
+            message.push_str(part);
+        }
+        message
+    }
+}
+
+/// 按当前界面语言生成错误文案
+pub fn tr(code: ErrorCode, args: &[&dyn std::fmt::Display]) -> String {
+    code.message_in(current_language(), args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_in() {
+        let code = ErrorCode::ModelConfigNotFound;
+        assert_eq!(code.message_in(Language::Zh, &[&"abc"]), "找不到模型配置: abc");
+        assert_eq!(code.message_in(Language::En, &[&"abc"]), "Model configuration not found: abc");
+        assert_eq!(
+            ErrorCode::MissingCredentials.message_in(Language::En, &[]),
+            "An API key or model configuration ID is required"
+        );
+        assert_eq!(Language::from_code(Some("en-US")), Language::En);
+        assert_eq!(Language::from_code(None), Language::Zh);
+    }
+}
@@ -0,0 +1,740 @@
+//! AI 洞察缓存
+//!
+//! 洞察保存在数据库的 `insight_cache` 表中，按仓库地址与变体（篇幅、对比稿）区分；
+//! 思考过程与生成信息随内容一并保存。旧版本以 Markdown 文件缓存，读取时自动迁移。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use crate::ai::SummaryLength;
+use crate::config::commands::ConfigManagerState;
+use crate::db::DbState;
+use crate::models::{AppSettings, DEFAULT_SUMMARY_LANGUAGE};
+
+/// 对比稿变体中模型配置 ID 前的标记
+const COMPARISON_MARKER: &str = ".compare-";
+/// 变体中输出语言前的标记
+const LANGUAGE_MARKER: &str = ".lang-";
+
+/// 洞察缓存条目的标识：仓库地址加变体（篇幅后缀、非默认输出语言，对比稿另带模型配置 ID）
+///
+/// 直接以仓库地址为键，大小写不同的仓库（如 `Foo/Bar` 与 `foo/bar`）不会互相覆盖。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsightKey {
+    pub repo_url: String,
+    pub variant: String,
+}
+
+impl InsightKey {
+    /// 仓库某个篇幅与输出语言的主缓存
+    pub fn primary(repo_url: &str, length: SummaryLength, language: &str) -> Self {
+        Self {
+            repo_url: repo_url.to_string(),
+            variant: primary_variant(length, language),
+        }
+    }
+
+    /// 多模型对比时某个模型配置的对比稿
+    pub fn comparison(repo_url: &str, length: SummaryLength, language: &str, config_id: &str) -> Self {
+        Self {
+            repo_url: repo_url.to_string(),
+            variant: format!("{}{}{}", primary_variant(length, language), COMPARISON_MARKER, config_id),
+        }
+    }
+}
+
+/// 主缓存的变体：默认语言不加后缀，以兼容已有缓存
+fn primary_variant(length: SummaryLength, language: &str) -> String {
+    if language == DEFAULT_SUMMARY_LANGUAGE {
+        length.cache_suffix().to_string()
+    } else {
+        format!("{}{}{}", length.cache_suffix(), LANGUAGE_MARKER, language)
+    }
+}
+
+/// 某个输出语言的主缓存变体对应的篇幅，其他语言与对比稿返回 None
+fn variant_length(variant: &str, language: &str) -> Option<SummaryLength> {
+    SummaryLength::ALL.into_iter().find(|length| primary_variant(*length, language) == variant)
+}
+
+/// 洞察的生成信息，以 JSON 保存在 `insight_cache.metadata` 列中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightMetadata {
+    /// 仓库全名 `author/name`
+    pub repo: String,
+    pub language: String,
+    pub config_id: String,
+    pub model: String,
+    /// 模型提供商名称（早期的生成信息中没有该字段）
+    #[serde(default)]
+    pub provider: String,
+    pub deep_mode: bool,
+    pub high_quality: bool,
+    pub length: SummaryLength,
+    /// 生成时间（Unix 秒）
+    pub generated_at: i64,
+    /// 缓存内容的字符数
+    #[serde(default)]
+    pub chars: usize,
+}
+
+impl InsightMetadata {
+    /// 生成模式描述，与页脚中的模式一致
+    pub fn mode_label(&self) -> String {
+        crate::ai::SummaryOptions {
+            deep_mode: self.deep_mode,
+            high_quality: self.high_quality,
+            length: self.length,
+        }
+        .mode_label()
+    }
+}
+
+/// 写入一条缓存，`generated_at` 为生成时间（Unix 秒）
+async fn insert(
+    pool: &DbState,
+    key: &InsightKey,
+    content: &str,
+    reasoning: Option<&str>,
+    metadata: Option<&InsightMetadata>,
+    generated_at: i64,
+) -> Result<(), String> {
+    let metadata = metadata.and_then(|m| serde_json::to_string(m).ok());
+    sqlx::query(
+        "INSERT INTO insight_cache (repo_url, variant, content, reasoning, metadata, updated_at, accessed_at)
+         VALUES (?, ?, ?, ?, ?, datetime(?, 'unixepoch'), CURRENT_TIMESTAMP)
+         ON CONFLICT(repo_url, variant) DO UPDATE SET
+            content = excluded.content, reasoning = excluded.reasoning, metadata = excluded.metadata,
+            updated_at = excluded.updated_at, accessed_at = excluded.accessed_at",
+    )
+    .bind(&key.repo_url)
+    .bind(&key.variant)
+    .bind(content)
+    .bind(reasoning)
+    .bind(metadata)
+    .bind(generated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 写入洞察缓存（覆盖同一条目），不缓存过短或空的内容，返回是否成功写入
+pub async fn store(
+    pool: &DbState,
+    key: &InsightKey,
+    content: &str,
+    reasoning: Option<&str>,
+    metadata: Option<&InsightMetadata>,
+) -> bool {
+    if content.trim().len() < 10 {
+        return false;
+    }
+    match insert(pool, key, content, reasoning, metadata, chrono::Utc::now().timestamp()).await {
+        Ok(()) => true,
+        Err(e) => {
+            // 写入失败时仅记录日志，总结本身已经发送给前端
+            log::warn!("保存洞察缓存失败 {}: {}", key.repo_url, e);
+            false
+        }
+    }
+}
+
+/// 替换已有条目的内容（如续写），生成时间与思考过程保持不变
+pub async fn update_content(pool: &DbState, key: &InsightKey, content: &str, metadata: Option<&InsightMetadata>) -> bool {
+    let metadata = metadata.and_then(|m| serde_json::to_string(m).ok());
+    sqlx::query(
+        "UPDATE insight_cache SET content = ?, metadata = COALESCE(?, metadata), accessed_at = CURRENT_TIMESTAMP
+         WHERE repo_url = ? AND variant = ?",
+    )
+    .bind(content)
+    .bind(metadata)
+    .bind(&key.repo_url)
+    .bind(&key.variant)
+    .execute(pool)
+    .await
+    .is_ok_and(|result| result.rows_affected() > 0)
+}
+
+/// 生成于 `generated_at` 的洞察在 `now` 时是否已超过有效期（`ttl_days` 为空表示永不过期）
+pub fn is_stale(generated_at: i64, now: i64, ttl_days: Option<u64>) -> bool {
+    ttl_days.is_some_and(|days| now - generated_at > i64::try_from(days.saturating_mul(86_400)).unwrap_or(i64::MAX))
+}
+
+/// 读取缓存内容并记录访问时间（清理时按最近访问时间淘汰），过期的条目视为未命中
+pub async fn load(pool: &DbState, key: &InsightKey, ttl_days: Option<u64>) -> Option<String> {
+    let (content, generated_at) = sqlx::query_as::<_, (String, i64)>(
+        "SELECT content, CAST(strftime('%s', updated_at) AS INTEGER) FROM insight_cache WHERE repo_url = ? AND variant = ?",
+    )
+    .bind(&key.repo_url)
+    .bind(&key.variant)
+    .fetch_optional(pool)
+    .await
+    .ok()??;
+    if is_stale(generated_at, chrono::Utc::now().timestamp(), ttl_days) {
+        return None;
+    }
+
+    let _ = sqlx::query("UPDATE insight_cache SET accessed_at = CURRENT_TIMESTAMP WHERE repo_url = ? AND variant = ?")
+        .bind(&key.repo_url)
+        .bind(&key.variant)
+        .execute(pool)
+        .await;
+    Some(content)
+}
+
+/// 是否已有未过期的该条目（不记录访问）
+pub async fn exists(pool: &DbState, key: &InsightKey, ttl_days: Option<u64>) -> bool {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT CAST(strftime('%s', updated_at) AS INTEGER) FROM insight_cache WHERE repo_url = ? AND variant = ?",
+    )
+    .bind(&key.repo_url)
+    .bind(&key.variant)
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+    .is_some_and(|generated_at| !is_stale(generated_at, chrono::Utc::now().timestamp(), ttl_days))
+}
+
+/// 读取洞察的生成信息（旧缓存迁移而来、没有生成信息时返回 None）
+pub async fn load_metadata(pool: &DbState, key: &InsightKey) -> Option<InsightMetadata> {
+    let json = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT metadata FROM insight_cache WHERE repo_url = ? AND variant = ?",
+    )
+    .bind(&key.repo_url)
+    .bind(&key.variant)
+    .fetch_optional(pool)
+    .await
+    .ok()???;
+    serde_json::from_str(&json).ok()
+}
+
+/// 将一个条目连同思考过程与生成信息复制为另一个条目，源条目不存在时返回 false
+pub async fn copy(pool: &DbState, source: &InsightKey, target: &InsightKey) -> Result<bool, String> {
+    let result = sqlx::query(
+        "INSERT INTO insight_cache (repo_url, variant, content, reasoning, metadata, updated_at, accessed_at)
+         SELECT ?, ?, content, reasoning, metadata, updated_at, CURRENT_TIMESTAMP
+         FROM insight_cache WHERE repo_url = ? AND variant = ?
+         ON CONFLICT(repo_url, variant) DO UPDATE SET
+            content = excluded.content, reasoning = excluded.reasoning, metadata = excluded.metadata,
+            updated_at = excluded.updated_at, accessed_at = excluded.accessed_at",
+    )
+    .bind(&target.repo_url)
+    .bind(&target.variant)
+    .bind(&source.repo_url)
+    .bind(&source.variant)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// 批量查询得到的主缓存条目
+#[derive(Debug, Clone)]
+pub struct CachedInsight {
+    pub repo_url: String,
+    pub length: SummaryLength,
+    /// 生成时间（Unix 秒）
+    pub generated_at: i64,
+}
+
+/// 单次查询绑定的仓库地址数量上限，避免超出 SQLite 的参数个数限制
+const LOOKUP_CHUNK: usize = 500;
+
+/// 用 `IN (...)` 查询一批仓库某个输出语言的所有主缓存（不含对比稿）
+pub async fn cached_insights(pool: &DbState, urls: &[String], language: &str) -> Vec<CachedInsight> {
+    let mut cached = Vec::new();
+    for chunk in urls.chunks(LOOKUP_CHUNK) {
+        let sql = format!(
+            "SELECT repo_url, variant, CAST(strftime('%s', updated_at) AS INTEGER) FROM insight_cache
+             WHERE repo_url IN ({})",
+            vec!["?"; chunk.len()].join(", ")
+        );
+        let mut query = sqlx::query_as::<_, (String, String, i64)>(&sql);
+        for url in chunk {
+            query = query.bind(url);
+        }
+        let rows = query.fetch_all(pool).await.unwrap_or_default();
+        cached.extend(rows.into_iter().filter_map(|(repo_url, variant, generated_at)| {
+            Some(CachedInsight { repo_url, length: variant_length(&variant, language)?, generated_at })
+        }));
+    }
+    cached
+}
+
+/// 旧版本的洞察缓存目录（洞察曾以 Markdown 文件保存，现仅用于迁移）
+fn legacy_cache_dir(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+    let dir = app_handle.path().app_data_dir().ok()?.join("ai_insights");
+    dir.is_dir().then_some(dir)
+}
+
+/// 旧缓存目录中尚未迁移的洞察文件名：只在首次使用时扫描一次目录，之后读取洞察不再访问磁盘
+///
+/// 对比稿等无法按仓库定位的旧文件不进入索引，留在目录中也不会被反复检查。
+static LEGACY_FILES: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// 列出旧缓存目录中可迁移的洞察文件（不含思考过程、生成信息与对比稿）
+fn scan_legacy_files(dir: &Path) -> HashSet<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return HashSet::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".md") && !name.ends_with(".reasoning.md") && !name.contains(COMPARISON_MARKER))
+        .collect()
+}
+
+/// 从索引中取出该仓库的旧缓存文件，返回是否存在；每个文件只会尝试迁移一次
+fn claim_legacy_files(app_handle: &tauri::AppHandle, author: &str, name: &str) -> bool {
+    let Ok(mut index) = LEGACY_FILES.lock() else {
+        return false;
+    };
+    let files = index.get_or_insert_with(|| {
+        legacy_cache_dir(app_handle).map(|dir| scan_legacy_files(&dir)).unwrap_or_default()
+    });
+    let mut found = false;
+    for length in SummaryLength::ALL {
+        found |= files.remove(&crate::ai::cache_file_name(author, name, length));
+    }
+    found
+}
+
+/// 读取旧缓存旁边的 `<缓存名>.meta.json` 生成信息
+fn legacy_metadata(cache_path: &Path) -> Option<InsightMetadata> {
+    let json = fs::read_to_string(cache_path.with_extension("meta.json")).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// 将旧缓存目录中某个仓库各篇幅的洞察导入数据库并删除旧文件，返回导入的条目数
+///
+/// 旧文件名经过小写化处理，只能按作者与仓库名定位；数据库中已有的条目以数据库为准。
+async fn import_legacy_from(pool: &DbState, dir: &Path, repo_url: &str, author: &str, name: &str) -> usize {
+    let mut imported = 0;
+    for length in SummaryLength::ALL {
+        let path = dir.join(crate::ai::cache_file_name(author, name, length));
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        // 旧版本只生成默认语言的洞察
+        let key = InsightKey::primary(repo_url, length, DEFAULT_SUMMARY_LANGUAGE);
+        if !exists(pool, &key, None).await {
+            let reasoning = fs::read_to_string(path.with_extension("reasoning.md")).ok();
+            let metadata = legacy_metadata(&path);
+            let generated_at = metadata.as_ref().map(|m| m.generated_at).unwrap_or_else(|| {
+                fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map_or_else(|| chrono::Utc::now().timestamp(), |d| d.as_secs() as i64)
+            });
+            if let Err(e) = insert(pool, &key, &content, reasoning.as_deref(), metadata.as_ref(), generated_at).await {
+                log::warn!("迁移旧洞察文件失败 {:?}: {}", path, e);
+                continue;
+            }
+            imported += 1;
+        }
+        for legacy in [path.clone(), path.with_extension("reasoning.md"), path.with_extension("meta.json")] {
+            let _ = fs::remove_file(legacy);
+        }
+    }
+    // 全部迁移完成后目录为空，顺带删除
+    let _ = fs::remove_dir(dir);
+    imported
+}
+
+/// 读取仓库洞察前调用：该仓库仍有旧文件缓存时迁移到数据库
+pub async fn import_legacy(pool: &DbState, app_handle: &tauri::AppHandle, repo_url: &str, author: &str, name: &str) -> usize {
+    if !claim_legacy_files(app_handle, author, name) {
+        return 0;
+    }
+    match legacy_cache_dir(app_handle) {
+        Some(dir) => import_legacy_from(pool, &dir, repo_url, author, name).await,
+        None => 0,
+    }
+}
+
+/// 洞察列表的筛选条件，未填写的条件不做限制
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct InsightFilter {
+    /// 模型名或模型配置 ID（模型名按子串匹配，不区分大小写）
+    pub model: Option<String>,
+    pub deep_mode: Option<bool>,
+    pub high_quality: Option<bool>,
+    pub length: Option<SummaryLength>,
+    /// 仓库的编程语言
+    pub language: Option<String>,
+    /// 只包含至少生成了这么多天的洞察
+    pub min_age_days: Option<u64>,
+    /// 只包含最近这么多天内生成的洞察
+    pub max_age_days: Option<u64>,
+}
+
+impl InsightFilter {
+    fn matches(&self, metadata: &InsightMetadata, now: i64) -> bool {
+        let age_days = (now - metadata.generated_at).max(0) as u64 / (24 * 60 * 60);
+        self.model.as_ref().is_none_or(|model| {
+            metadata.config_id == *model || metadata.model.to_lowercase().contains(&model.to_lowercase())
+        }) && self.deep_mode.is_none_or(|deep| metadata.deep_mode == deep)
+            && self.high_quality.is_none_or(|hq| metadata.high_quality == hq)
+            && self.length.is_none_or(|length| metadata.length == length)
+            && self.language.as_ref().is_none_or(|language| metadata.language.eq_ignore_ascii_case(language))
+            && self.min_age_days.is_none_or(|min| age_days >= min)
+            && self.max_age_days.is_none_or(|max| age_days <= max)
+    }
+}
+
+/// 按模型、模式排序，同组内最新的在前
+fn sort_insights(insights: &mut [InsightMetadata]) {
+    insights.sort_by(|a, b| {
+        (&a.model, a.deep_mode, a.high_quality)
+            .cmp(&(&b.model, b.deep_mode, b.high_quality))
+            .then(b.generated_at.cmp(&a.generated_at))
+    });
+}
+
+/// 缓存清理策略
+#[derive(Debug, Clone, Default)]
+pub struct EvictionPolicy {
+    pub max_entries: Option<usize>,
+    pub max_bytes: Option<u64>,
+    pub max_age: Option<Duration>,
+}
+
+impl EvictionPolicy {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            max_entries: settings.insight_cache_max_entries,
+            max_bytes: settings.insight_cache_max_bytes,
+            max_age: settings.insight_cache_max_age_days.map(|days| Duration::from_secs(days.saturating_mul(86_400))),
+        }
+    }
+
+    fn is_unbounded(&self) -> bool {
+        self.max_entries.is_none() && self.max_bytes.is_none() && self.max_age.is_none()
+    }
+}
+
+/// 单条缓存的元信息
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    key: InsightKey,
+    size: u64,
+    last_accessed: SystemTime,
+    /// 受保护的条目（已收藏仓库）不会被清理
+    protected: bool,
+}
+
+/// 清理结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvictionReport {
+    pub removed: usize,
+    pub freed_bytes: u64,
+    pub remaining: usize,
+}
+
+/// 按策略选出需要清理的条目：先清理过期条目，再按最近访问时间从旧到新清理直到满足数量与容量限制
+fn select_evictions(mut entries: Vec<CacheEntry>, policy: &EvictionPolicy, now: SystemTime) -> Vec<CacheEntry> {
+    entries.sort_by_key(|e| e.last_accessed);
+
+    let mut evicted = Vec::new();
+    let mut kept = Vec::new();
+    for entry in entries {
+        let expired = policy.max_age.is_some_and(|max_age| {
+            now.duration_since(entry.last_accessed).unwrap_or_default() > max_age
+        });
+        if expired && !entry.protected {
+            evicted.push(entry);
+        } else {
+            kept.push(entry);
+        }
+    }
+
+    let mut count = kept.len();
+    let mut bytes: u64 = kept.iter().map(|e| e.size).sum();
+    let over_limit = |count: usize, bytes: u64| {
+        policy.max_entries.is_some_and(|max| count > max) || policy.max_bytes.is_some_and(|max| bytes > max)
+    };
+    for entry in kept {
+        if !over_limit(count, bytes) {
+            break;
+        }
+        if entry.protected {
+            continue;
+        }
+        count -= 1;
+        bytes -= entry.size;
+        evicted.push(entry);
+    }
+    evicted
+}
+
+/// 读取所有缓存条目；`protect_favorites` 为真时已收藏仓库的条目受保护
+async fn load_entries(pool: &DbState, protect_favorites: bool) -> Result<Vec<CacheEntry>, String> {
+    let rows = sqlx::query_as::<_, (String, String, i64, i64, bool)>(
+        "SELECT repo_url, variant, length(CAST(content AS BLOB)), CAST(strftime('%s', accessed_at) AS INTEGER),
+                repo_url IN (SELECT url FROM repos)
+         FROM insight_cache",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(repo_url, variant, size, accessed_at, favorite)| CacheEntry {
+            key: InsightKey { repo_url, variant },
+            size: size.max(0) as u64,
+            last_accessed: SystemTime::UNIX_EPOCH + Duration::from_secs(accessed_at.max(0) as u64),
+            protected: protect_favorites && favorite,
+        })
+        .collect())
+}
+
+/// 删除一条缓存
+async fn remove(pool: &DbState, key: &InsightKey) -> Result<(), String> {
+    sqlx::query("DELETE FROM insight_cache WHERE repo_url = ? AND variant = ?")
+        .bind(&key.repo_url)
+        .bind(&key.variant)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 执行一次缓存清理
+async fn prune(settings: &AppSettings, pool: &DbState) -> Result<EvictionReport, String> {
+    let entries = load_entries(pool, !settings.evict_favorite_insights).await?;
+    let total = entries.len();
+    let policy = EvictionPolicy::from_settings(settings);
+    if policy.is_unbounded() {
+        return Ok(EvictionReport { remaining: total, ..Default::default() });
+    }
+
+    let mut report = EvictionReport::default();
+    for entry in select_evictions(entries, &policy, SystemTime::now()) {
+        if let Err(e) = remove(pool, &entry.key).await {
+            log::warn!("清理洞察缓存失败 {:?}: {}", entry.key, e);
+            continue;
+        }
+        report.removed += 1;
+        report.freed_bytes += entry.size;
+    }
+    report.remaining = total - report.removed;
+    Ok(report)
+}
+
+/// 启动时将旧的文件缓存迁移到数据库，并补录收藏仓库的洞察来源，失败时只记录日志
+///
+/// 已收藏仓库的地址是确定的，启动时直接迁移；其余旧缓存在首次读取时迁移。
+pub async fn backfill_on_startup(app_handle: &tauri::AppHandle) {
+    let Some(pool) = app_handle.try_state::<DbState>() else {
+        return;
+    };
+    if legacy_cache_dir(app_handle).is_some() {
+        let favorites = sqlx::query_as::<_, (String, String, String)>("SELECT url, author, name FROM repos")
+            .fetch_all(&*pool)
+            .await
+            .unwrap_or_default();
+        let mut migrated = 0;
+        for (url, author, name) in favorites {
+            migrated += import_legacy(&pool, app_handle, &url, &author, &name).await;
+        }
+        if migrated > 0 {
+            log::info!("已将 {} 个旧洞察文件迁移到数据库", migrated);
+        }
+    }
+    match crate::db::backfill_insights(&pool).await {
+        Ok(migrated) if migrated > 0 => log::info!("已将 {} 条洞察回填到数据库", migrated),
+        Ok(_) => {}
+        Err(e) => log::warn!("回填洞察失败: {}", e),
+    }
+}
+
+/// 启动时的后台清理，失败时只记录日志
+pub async fn prune_on_startup(app_handle: &tauri::AppHandle) {
+    let Some(config_manager) = app_handle.try_state::<ConfigManagerState>() else {
+        return;
+    };
+    let Some(pool) = app_handle.try_state::<DbState>() else {
+        return;
+    };
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    match prune(&settings, &pool).await {
+        Ok(report) if report.removed > 0 => {
+            log::info!("已清理 {} 条洞察缓存（{} 字节）", report.removed, report.freed_bytes);
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("清理洞察缓存失败: {}", e),
+    }
+}
+
+/// 按当前设置手动清理洞察缓存
+#[tauri::command]
+pub async fn prune_insight_cache(
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    db: tauri::State<'_, DbState>,
+) -> Result<EvictionReport, String> {
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    prune(&settings, db.inner()).await
+}
+
+/// 列出已缓存的洞察及其生成信息（不含对比稿；缺少生成信息的旧缓存不会列出）
+#[tauri::command]
+pub async fn list_insights(
+    filter: Option<InsightFilter>,
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<InsightMetadata>, String> {
+    let rows = sqlx::query_scalar::<_, String>(
+        "SELECT metadata FROM insight_cache WHERE metadata IS NOT NULL AND instr(variant, ?) = 0",
+    )
+    .bind(COMPARISON_MARKER)
+    .fetch_all(db.inner())
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let filter = filter.unwrap_or_default();
+    let now = chrono::Utc::now().timestamp();
+    let mut insights: Vec<InsightMetadata> = rows
+        .iter()
+        .filter_map(|json| serde_json::from_str::<InsightMetadata>(json).ok())
+        .filter(|metadata| filter.matches(metadata, now))
+        .collect();
+    sort_insights(&mut insights);
+    Ok(insights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, size: u64, age_days: u64, protected: bool, now: SystemTime) -> CacheEntry {
+        CacheEntry {
+            key: InsightKey::primary(name, SummaryLength::Medium, DEFAULT_SUMMARY_LANGUAGE),
+            size,
+            last_accessed: now - Duration::from_secs(age_days * 24 * 60 * 60),
+            protected,
+        }
+    }
+
+    fn names(entries: &[CacheEntry]) -> Vec<String> {
+        entries.iter().map(|e| e.key.repo_url.clone()).collect()
+    }
+
+    fn metadata(model: &str, deep_mode: bool, age_days: i64, now: i64) -> InsightMetadata {
+        InsightMetadata {
+            repo: format!("owner/{}", model),
+            language: "Rust".to_string(),
+            config_id: format!("cfg-{}", model),
+            model: model.to_string(),
+            provider: "OpenAI".to_string(),
+            deep_mode,
+            high_quality: false,
+            length: SummaryLength::Medium,
+            generated_at: now - age_days * 24 * 60 * 60,
+            chars: 100,
+        }
+    }
+
+    #[test]
+    fn test_insight_filter() {
+        let now = 1_700_000_000;
+        let old = metadata("gpt-3.5-turbo", false, 90, now);
+        let new = metadata("gpt-4o", true, 1, now);
+
+        let by_model = InsightFilter { model: Some("3.5".to_string()), ..Default::default() };
+        assert!(by_model.matches(&old, now) && !by_model.matches(&new, now));
+
+        let by_config = InsightFilter { model: Some("cfg-gpt-4o".to_string()), ..Default::default() };
+        assert!(by_config.matches(&new, now) && !by_config.matches(&old, now));
+
+        let by_age = InsightFilter { min_age_days: Some(30), language: Some("rust".to_string()), ..Default::default() };
+        assert!(by_age.matches(&old, now) && !by_age.matches(&new, now));
+
+        let by_mode = InsightFilter { deep_mode: Some(true), max_age_days: Some(7), ..Default::default() };
+        assert!(by_mode.matches(&new, now) && !by_mode.matches(&old, now));
+
+        let mut insights = vec![new.clone(), old.clone(), metadata("gpt-3.5-turbo", false, 10, now)];
+        sort_insights(&mut insights);
+        let order: Vec<i64> = insights.iter().map(|m| m.generated_at).collect();
+        assert_eq!(order, vec![now - 10 * 24 * 60 * 60, old.generated_at, new.generated_at]);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = 1_700_000_000;
+        let day = 24 * 60 * 60;
+        assert!(!is_stale(now - 365 * day, now, None));
+        assert!(!is_stale(now - 6 * day, now, Some(7)));
+        assert!(is_stale(now - 8 * day, now, Some(7)));
+        assert!(!is_stale(0, now, Some(u64::MAX)));
+    }
+
+    #[test]
+    fn test_insight_key_variants() {
+        let url = "https://github.com/Foo/Bar";
+        let zh = DEFAULT_SUMMARY_LANGUAGE;
+        assert_eq!(variant_length(&InsightKey::primary(url, SummaryLength::Medium, zh).variant, zh), Some(SummaryLength::Medium));
+        assert_eq!(variant_length(&InsightKey::primary(url, SummaryLength::Long, zh).variant, zh), Some(SummaryLength::Long));
+        assert_eq!(variant_length(&InsightKey::comparison(url, SummaryLength::Medium, zh, "cfg-1").variant, zh), None);
+        assert_ne!(InsightKey::primary(url, SummaryLength::Medium, zh), InsightKey::primary("https://github.com/foo/bar", SummaryLength::Medium, zh));
+
+        // 默认语言沿用旧变体，其他语言分别缓存
+        assert_eq!(InsightKey::primary(url, SummaryLength::Medium, zh).variant, "");
+        let en = InsightKey::primary(url, SummaryLength::Short, "en");
+        assert_eq!(en.variant, "_short.lang-en");
+        assert_eq!(variant_length(&en.variant, "en"), Some(SummaryLength::Short));
+        assert_eq!(variant_length(&en.variant, zh), None);
+    }
+
+    #[test]
+    fn test_select_evictions() {
+        let now = SystemTime::now();
+        let entries = vec![
+            entry("new.md", 10, 1, false, now),
+            entry("old.md", 10, 30, false, now),
+            entry("fav.md", 10, 60, true, now),
+            entry("mid.md", 10, 10, false, now),
+        ];
+
+        let by_count = EvictionPolicy { max_entries: Some(2), ..Default::default() };
+        assert_eq!(names(&select_evictions(entries.clone(), &by_count, now)), vec!["old.md", "mid.md"]);
+
+        let by_bytes = EvictionPolicy { max_bytes: Some(30), ..Default::default() };
+        assert_eq!(names(&select_evictions(entries.clone(), &by_bytes, now)), vec!["old.md"]);
+
+        let by_age = EvictionPolicy { max_age: Some(Duration::from_secs(20 * 24 * 60 * 60)), ..Default::default() };
+        assert_eq!(names(&select_evictions(entries.clone(), &by_age, now)), vec!["old.md"]);
+
+        // 过大的天数不会溢出，等同于不按时间清理
+        let settings = AppSettings { insight_cache_max_age_days: Some(u64::MAX), ..Default::default() };
+        let huge = EvictionPolicy::from_settings(&settings);
+        assert!(select_evictions(entries, &huge, now).is_empty());
+    }
+
+    #[test]
+    fn test_scan_legacy_files() {
+        let dir = std::env::temp_dir().join(format!("legacy-insights-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["astral-sh_uv.md", "astral-sh_uv_short.md", "astral-sh_uv.reasoning.md", "astral-sh_uv.meta.json", "astral-sh_uv.compare-cfg.md"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let mut files: Vec<String> = scan_legacy_files(&dir).into_iter().collect();
+        files.sort();
+        assert_eq!(files, vec!["astral-sh_uv.md", "astral-sh_uv_short.md"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(scan_legacy_files(&dir).is_empty());
+    }
+}
@@ -5,6 +5,20 @@ mod search;
 mod models;
 mod llm;
 mod config;
+mod github;
+mod audit;
+mod links;
+mod insight_cache;
+mod explorer;
+mod i18n;
+mod batch;
+mod deps;
+mod offline;
+mod diagnostics;
+mod error;
+mod tokens;
+mod metadata;
+mod logging;
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -17,8 +31,47 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// 启动阶段记录的错误（例如应用数据目录不可写）
+struct StartupErrors(Vec<String>);
+
+/// 获取启动阶段的错误，前端启动后调用并提示用户
+#[tauri::command]
+fn get_startup_errors(errors: tauri::State<'_, StartupErrors>) -> Vec<String> {
+    errors.0.clone()
+}
+
+/// 创建应用数据目录、连接数据库并执行迁移，失败时返回包含路径的错误说明
+fn init_database(app: &tauri::App) -> Result<sqlx::SqlitePool, String> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?;
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("无法创建应用数据目录 {:?}，请检查该目录的读写权限: {}", app_data_dir, e))?;
+    let db_path = app_data_dir.join(db::DB_FILE_NAME);
+    
+    // 打印路径用于调试确认（在某些环境下很有用）
+    println!("Database path: {:?}", db_path);
+    
+    use sqlx::sqlite::SqliteConnectOptions;
+    let options = SqliteConnectOptions::new()
+        .filename(&db_path)
+        .create_if_missing(true);
+    let pool = tauri::async_runtime::block_on(async move {
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+    }).map_err(|e| format!("无法打开数据库 {:?}，收藏与标语等功能不可用: {}", db_path, e))?;
+
+    // 确保执行迁移
+    tauri::async_runtime::block_on(db::run_migrations(&pool))
+        .map_err(|e| format!("数据库迁移失败: {}", e))?;
+
+    Ok(pool)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_sql::Builder::default()
@@ -26,67 +79,135 @@ pub fn run() {
             .build())
         .plugin(tauri_plugin_store::Builder::default().build())
         .setup(|app| {
+            // 启动过程中的非致命错误，交给前端展示而不是直接崩溃
+            let mut startup_errors = Vec::new();
+
             // 初始化配置管理器
             let handle = app.handle().clone();
-            let manager = tauri::async_runtime::block_on(async move {
-                ConfigManager::new(handle).await
-            })?;
+            let manager = match tauri::async_runtime::block_on(ConfigManager::new(handle.clone())) {
+                Ok(manager) => manager,
+                Err(e) => {
+                    startup_errors.push(format!("加载配置失败: {}", e));
+                    ConfigManager::without_migration(handle)
+                }
+            };
             
+            // 错误信息跟随界面语言，离线模式从启动起生效
+            let settings = tauri::async_runtime::block_on(manager.get_app_settings()).unwrap_or_default();
+            i18n::set_language(i18n::Language::from_code(settings.ui_language.as_deref()));
+            offline::set_offline_mode(settings.offline_mode);
+            trending::load_topic_rules(settings.topic_rules.clone());
+            let recent_errors = diagnostics::RecentErrors::default();
+            if let Ok(config) = tauri::async_runtime::block_on(manager.load_config()) {
+                recent_errors.set_secrets(audit::config_secrets(&config));
+            }
+
             let manager_state: ConfigManagerState = Arc::new(Mutex::new(manager));
             app.manage(manager_state);
 
             // 初始化数据库连接池
-            let app_data_dir = app.path().app_data_dir().expect("Failed to get app data dir");
-            std::fs::create_dir_all(&app_data_dir).expect("Failed to create app data dir");
-            let db_path = app_data_dir.join("github_capture.db");
-            
-            // 打印路径用于调试确认（在某些环境下很有用）
-            println!("Database path: {:?}", db_path);
-            
-            use sqlx::sqlite::SqliteConnectOptions;
-            let pool = tauri::async_runtime::block_on(async move {
-                let options = SqliteConnectOptions::new()
-                    .filename(&db_path)
-                    .create_if_missing(true);
-                
-                sqlx::sqlite::SqlitePoolOptions::new()
-                    .max_connections(5)
-                    .connect_with(options)
-                    .await
-            }).expect("Failed to connect to database");
-            
-            app.manage(pool.clone());
+            match init_database(app) {
+                Ok(pool) => {
+                    app.manage(pool);
+                    app.manage(db::DbMaintenanceLock::default());
+                }
+                Err(e) => startup_errors.push(e),
+            }
+
+            for error in &startup_errors {
+                log::error!("{}", error);
+            }
+            app.manage(StartupErrors(startup_errors));
+            app.manage(recent_errors);
+            app.manage(ai::SummaryCancellations::default());
 
-            // 确保执行迁移
-            let pool_clone = pool.clone();
-            tauri::async_runtime::block_on(async move {
-                db::run_migrations(&pool_clone).await
-            }).expect("Failed to run migrations");
+            // 启动时在后台迁移旧的洞察文件缓存，并按设置清理
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                insight_cache::backfill_on_startup(&handle).await;
+                insight_cache::prune_on_startup(&handle).await;
+            });
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
+            get_startup_errors,
+            diagnostics::get_recent_errors,
+            diagnostics::clear_recent_errors,
+            diagnostics::record_command_error,
             trending::get_trending,
+            trending::get_trending_topics,
+            trending::get_trending_developers,
+            trending::merge_repo_feeds,
+            trending::set_ranking_weights,
+            trending::get_topic_rules,
+            trending::set_topic_rules,
             ai::summarize_repo,
+            ai::cancel_summary,
+            ai::prewarm_trending_insights,
+            ai::summarize_repos_batch,
+            ai::summarize_with_providers,
+            ai::adopt_comparison_summary,
+            ai::generate_digest,
+            ai::continue_summary,
+            ai::can_do_deep_context,
             ai::test_model_connection,
+            ai::test_streaming,
             ai::list_models,
             ai::get_cached_insight,
             ai::check_insights_batch,
+            ai::check_insights_map,
+            insight_cache::prune_insight_cache,
+            insight_cache::list_insights,
+            ai::generate_tagline,
+            ai::generate_favorite_taglines,
+            links::get_repo_links,
+            explorer::browse_repo_path,
+            deps::get_repo_dependencies,
+            metadata::extract_repo_metadata,
+            audit::get_audit_entries,
+            audit::clear_audit_log,
             search::ai_rewrite_query,
+            github::get_github_rate_limit,
             search::search_github,
+            search::ai_search,
             // 配置管理命令
             config::commands::get_model_configs,
             config::commands::get_active_model_config,
             config::commands::set_active_model_config,
             config::commands::save_model_config,
             config::commands::update_model_config,
+            config::commands::duplicate_model_config,
             config::commands::delete_model_config,
             config::commands::clear_model_cache,
+            config::commands::clear_model_cache_for,
+            config::commands::get_recommended_models,
+            config::commands::validate_key_format,
+            config::commands::get_app_settings,
+            config::commands::update_app_settings,
             // 数据库收藏命令
             db::toggle_favorite,
             db::get_favorites,
+            db::get_favorites_by_tag,
+            db::search_favorites,
+            db::export_favorites,
+            db::import_favorites,
+            db::add_tag,
+            db::remove_tag,
+            db::get_tags,
             db::is_favorite,
+            db::set_favorite_note,
+            db::get_favorite_facets,
+            db::vacuum_database,
+            db::hide_repo,
+            db::unhide_repo,
+            db::get_hidden_repos,
+            db::import_repos_from_urls,
+            db::get_insight_provenance,
+            db::record_search,
+            db::get_search_history,
+            db::clear_search_history,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,148 @@
+//! 仓库相关链接提取
+//!
+//! 汇总主页、文档、演示和社区链接，方便用户快速跳转到合适的资源。
+
+use serde::Serialize;
+use crate::ai::{fetch_readme_with_limit, fetch_root_entries};
+use crate::config::commands::ConfigManagerState;
+use crate::github::{fetch_repo_metadata, GitHubHost};
+
+/// 链接分类
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum LinkCategory {
+    Homepage,
+    Docs,
+    Demo,
+    Community,
+}
+
+/// 仓库相关链接
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoLink {
+    pub category: LinkCategory,
+    pub url: String,
+    pub label: Option<String>,
+}
+
+/// 获取仓库的主页、文档、演示与社区链接
+#[tauri::command]
+pub async fn get_repo_links(
+    author: String,
+    name: String,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<Vec<RepoLink>, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    let host = GitHubHost::from_settings(&settings);
+
+    let mut links = Vec::new();
+
+    // 1. 仓库元数据中的主页与 GitHub Pages
+    if let Ok(metadata) = fetch_repo_metadata(&host, &author, &name).await {
+        if let Some(homepage) = metadata.homepage.filter(|h| !h.trim().is_empty()) {
+            links.push(RepoLink { category: LinkCategory::Homepage, url: homepage, label: None });
+        }
+        if metadata.has_pages {
+            links.push(RepoLink {
+                category: LinkCategory::Docs,
+                url: format!("https://{}.github.io/{}/", author.to_lowercase(), name),
+                label: Some("GitHub Pages".to_string()),
+            });
+        }
+    }
+
+    // 2. 根目录下的 docs 目录
+    if let Some(entries) = fetch_root_entries(&host, &author, &name).await {
+        if let Some(docs) = entries.iter().find(|e| e.is_dir && e.name.eq_ignore_ascii_case("docs")) {
+            links.push(RepoLink {
+                category: LinkCategory::Docs,
+                url: format!("https://{}/{}/{}/tree/HEAD/{}", host.web_host(), author, name, docs.name),
+                label: Some(docs.name.clone()),
+            });
+        }
+    }
+
+    // 3. README 中的显著链接
+    if let Some(readme) = fetch_readme_with_limit(&host, &author, &name, None).await {
+        for (label, url) in extract_markdown_links(&readme) {
+            if let Some(category) = categorize_link(&label, &url) {
+                links.push(RepoLink { category, url, label: Some(label).filter(|l| !l.is_empty()) });
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    links.retain(|link| seen.insert(link.url.trim_end_matches('/').to_string()));
+    Ok(links)
+}
+
+/// 提取 Markdown 中的 `[label](url)` 链接（忽略图片本身的地址）
+fn extract_markdown_links(text: &str) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find("](") {
+        let before = &rest[..pos];
+        let after = &rest[pos + 2..];
+        let end = after.find(|c: char| c == ')' || c.is_whitespace()).unwrap_or(after.len());
+        let url = after[..end].to_string();
+
+        let label_start = before.rfind('[').map(|i| i + 1).unwrap_or(before.len());
+        let is_image = label_start >= 2 && before[..label_start - 1].ends_with('!');
+        if !is_image && url.starts_with("http") {
+            links.push((before[label_start..].trim().to_string(), url));
+        }
+        rest = &after[end..];
+    }
+    links
+}
+
+/// 根据地址与文字判断链接分类，无法识别时返回 None
+fn categorize_link(label: &str, url: &str) -> Option<LinkCategory> {
+    let url_lower = url.to_lowercase();
+    let label_lower = label.to_lowercase();
+    let matches = |keywords: &[&str]| {
+        keywords.iter().any(|k| url_lower.contains(k) || label_lower.contains(k))
+    };
+
+    if url_lower.contains("shields.io") || url_lower.contains("badge") {
+        return None;
+    }
+    if matches(&["discord.gg", "discord.com", "slack.com", "gitter.im", "t.me/", "telegram", "reddit.com", "forum", "community"]) {
+        return Some(LinkCategory::Community);
+    }
+    if matches(&["demo", "playground", "try it", "live preview"]) {
+        return Some(LinkCategory::Demo);
+    }
+    if matches(&["docs", "documentation", "readthedocs", "gitbook", "/wiki", "文档"]) {
+        return Some(LinkCategory::Docs);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_markdown_links() {
+        let readme = "See [the docs](https://example.com/docs) and \
+            [![Discord](https://img.shields.io/discord/1)](https://discord.gg/abc).";
+        let links = extract_markdown_links(readme);
+        assert_eq!(links, vec![
+            ("the docs".to_string(), "https://example.com/docs".to_string()),
+            ("".to_string(), "https://discord.gg/abc".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_categorize_link() {
+        assert_eq!(categorize_link("", "https://discord.gg/abc"), Some(LinkCategory::Community));
+        assert_eq!(categorize_link("Live Demo", "https://app.example.com"), Some(LinkCategory::Demo));
+        assert_eq!(categorize_link("", "https://project.readthedocs.io"), Some(LinkCategory::Docs));
+        assert_eq!(categorize_link("", "https://img.shields.io/badge/docs-blue"), None);
+        assert_eq!(categorize_link("License", "https://opensource.org/MIT"), None);
+    }
+}
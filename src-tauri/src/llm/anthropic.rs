@@ -1,11 +1,26 @@
 //! Anthropic (Claude) 提供商实现
 
+use serde_json::json;
+use reqwest::Client;
+use reqwest_eventsource::{Event, EventSource};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use crate::models::{ModelConfig, ModelInfo, ChatMessage, ModelProvider};
-use super::{LLMProvider, LLMError, LLMResponse};
+use super::{LLMProvider, LLMError, LLMResponse, StreamChunk, Usage};
+
+/// Messages API 版本
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Messages API 要求必须提供 max_tokens，未配置时使用该值
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+/// 扩展思考预算的下限（接口要求至少 1024）
+const MIN_THINKING_BUDGET: u32 = 1024;
 
 /// Anthropic 提供商
 pub struct AnthropicProvider {
     config: ModelConfig,
+    client: Client,
+    /// 流式响应的取消令牌（默认永不触发）
+    cancel: CancellationToken,
 }
 
 impl AnthropicProvider {
@@ -13,7 +28,202 @@ impl AnthropicProvider {
     pub fn new(config: &ModelConfig) -> Self {
         Self {
             config: config.clone(),
+            client: super::build_http_client(config),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// 构建 API 端点 URL（兼容以 `/v1` 结尾的基础地址）
+    fn build_endpoint_url(&self, path: &str) -> String {
+        let base_url = self.config.api_base_url.trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/v1").unwrap_or(base_url);
+        format!("{}/v1{}", base_url, path)
+    }
+
+    /// 构建 Messages API 请求体：system 消息提取到顶层 `system` 字段，其余保留 user/assistant
+    fn build_payload(&self, messages: Vec<ChatMessage>, model: &str, stream: bool) -> serde_json::Value {
+        let (system, conversation): (Vec<ChatMessage>, Vec<ChatMessage>) =
+            messages.into_iter().partition(|msg| msg.role == "system");
+
+        let anthropic_messages: Vec<serde_json::Value> = conversation
+            .into_iter()
+            .map(|msg| {
+                let role = if msg.role == "assistant" { "assistant" } else { "user" };
+                json!({
+                    "role": role,
+                    "content": msg.content
+                })
+            })
+            .collect();
+
+        let mut payload = json!({
+            "model": model,
+            "messages": anthropic_messages,
+            "max_tokens": self.config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "stream": stream,
+        });
+        if let Some(budget) = self.config.thinking_budget.filter(|n| *n > 0) {
+            // 开启扩展思考时 max_tokens 必须大于预算，且不允许自定义温度
+            let budget = budget.max(MIN_THINKING_BUDGET);
+            let max_tokens = self.config.max_tokens.filter(|n| *n > budget).unwrap_or(budget + DEFAULT_MAX_TOKENS);
+            payload["max_tokens"] = json!(max_tokens);
+            payload["thinking"] = json!({ "type": "enabled", "budget_tokens": budget });
+        } else if let Some(temperature) = self.config.temperature {
+            // Anthropic 的温度范围为 0~1
+            payload["temperature"] = json!(temperature.min(1.0));
+        }
+        if !system.is_empty() {
+            let system: Vec<String> = system.into_iter().map(|msg| msg.content).collect();
+            payload["system"] = json!(system.join("\n\n"));
+        }
+        payload
+    }
+
+    /// 处理非流式响应
+    async fn handle_completion_response(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<LLMResponse, LLMError> {
+        let json: serde_json::Value = response.json().await?;
+
+        if let Some(error) = json.get("error") {
+            let error_msg = error.get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Unknown Anthropic error");
+            return Err(LLMError::RequestFailed(error_msg.to_string()));
         }
+
+        // 拼接所有文本块
+        let content: String = json["content"]
+            .as_array()
+            .ok_or_else(|| LLMError::ParseError("Missing content in response".to_string()))?
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect();
+
+        let model = json["model"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string();
+
+        let usage = json.get("usage").map(|usage| {
+            let prompt_tokens = usage["input_tokens"].as_u64().unwrap_or(0) as u32;
+            let completion_tokens = usage["output_tokens"].as_u64().unwrap_or(0) as u32;
+            Usage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+            }
+        });
+
+        Ok(LLMResponse::Completion {
+            content,
+            model,
+            usage,
+        })
+    }
+
+    /// 将单个 SSE 事件转换为流式块；`None` 表示可忽略的事件（如 ping、message_start）
+    fn parse_stream_event(value: &serde_json::Value) -> Option<StreamChunk> {
+        match value["type"].as_str()? {
+            // 开启 extended thinking 时，思考内容以 thinking_delta 单独下发
+            "content_block_delta" if value["delta"]["type"] == "thinking_delta" => value["delta"]["thinking"]
+                .as_str()
+                .filter(|thinking| !thinking.is_empty())
+                .map(|thinking| StreamChunk::Reasoning(thinking.to_string())),
+            "content_block_delta" => value["delta"]["text"]
+                .as_str()
+                .filter(|text| !text.is_empty())
+                .map(|text| StreamChunk::Text(text.to_string())),
+            "message_stop" => Some(StreamChunk::Done),
+            "error" => {
+                let message = value["error"]["message"]
+                    .as_str()
+                    .unwrap_or("Unknown Anthropic error");
+                Some(StreamChunk::Error(message.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// 通过 `GET /v1/models` 获取可用模型
+    async fn fetch_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
+        let endpoint = self.build_endpoint_url("/models?limit=100");
+
+        let response = self.client
+            .get(&endpoint)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .timeout(super::request_timeout(&self.config))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(LLMError::from_status_code(status.as_u16(), &error_text));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        parse_models_response(&json)
+    }
+
+    /// 处理流式响应
+    async fn handle_stream_response(
+        &self,
+        mut source: EventSource,
+    ) -> Result<LLMResponse, LLMError> {
+        let (tx, rx) = mpsc::channel(super::stream_buffer_size(&self.config));
+        let idle_timeout = super::request_timeout(&self.config);
+        let cancel = self.cancel.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let event = match super::next_stream_event(&mut source, idle_timeout, &cancel).await {
+                    Ok(Some(event)) => event,
+                    Ok(None) => break,
+                    Err(message) => {
+                        let _ = tx.send(StreamChunk::Error(message)).await;
+                        break;
+                    }
+                };
+                match event {
+                    Ok(Event::Message(message)) => {
+                        match serde_json::from_str::<serde_json::Value>(&message.data) {
+                            Ok(value) => match Self::parse_stream_event(&value) {
+                                Some(chunk @ (StreamChunk::Text(_) | StreamChunk::Reasoning(_))) => {
+                                    let _ = tx.send(chunk).await;
+                                }
+                                Some(chunk) => {
+                                    // message_stop 或 error 事件均结束本次流
+                                    let _ = tx.send(chunk).await;
+                                    break;
+                                }
+                                None => {}
+                            },
+                            Err(e) => {
+                                let chunk = StreamChunk::Error(e.to_string());
+                                let _ = tx.send(chunk).await;
+                                break;
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        let chunk = StreamChunk::Error(e.to_string());
+                        let _ = tx.send(chunk).await;
+                        break;
+                    }
+                }
+            }
+            source.close();
+
+            // 确保发送完成信号
+            let _ = tx.send(StreamChunk::Done).await;
+        });
+
+        Ok(LLMResponse::Stream { stream: rx })
     }
 }
 
@@ -21,53 +231,214 @@ impl AnthropicProvider {
 impl LLMProvider for AnthropicProvider {
     async fn chat_completion(
         &self,
-        _messages: Vec<ChatMessage>,
-        _model: &str,
-        _stream: bool,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        stream: bool,
     ) -> Result<LLMResponse, LLMError> {
-        // TODO: 实现 Anthropic API 调用
-        Err(LLMError::ConfigurationError(
-            "Anthropic provider not yet implemented".to_string(),
-        ))
+        let endpoint = self.build_endpoint_url("/messages");
+        let payload = self.build_payload(messages, model, stream);
+
+        let request = self.client
+            .post(&endpoint)
+            .header("x-api-key", &self.config.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("Content-Type", "application/json")
+            .json(&payload);
+
+        if stream {
+            let source = EventSource::new(request)
+                .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+            self.handle_stream_response(source).await
+        } else {
+            let response = request
+                .timeout(super::request_timeout(&self.config))
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(LLMError::from_status_code(status.as_u16(), &error_text));
+            }
+
+            self.handle_completion_response(response).await
+        }
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
-        // TODO: 实现 Anthropic 模型列表获取
-        Ok(vec![
-            ModelInfo {
-                id: "claude-3-opus-20240229".to_string(),
-                name: "Claude 3 Opus".to_string(),
-                provider: ModelProvider::Anthropic,
-                context_length: Some(200000),
-                max_tokens: Some(4096),
-                supports_streaming: true,
-                supports_function_calling: true,
-            },
-            ModelInfo {
-                id: "claude-3-sonnet-20240229".to_string(),
-                name: "Claude 3 Sonnet".to_string(),
-                provider: ModelProvider::Anthropic,
-                context_length: Some(200000),
-                max_tokens: Some(4096),
-                supports_streaming: true,
-                supports_function_calling: true,
-            },
-            ModelInfo {
-                id: "claude-3-haiku-20240307".to_string(),
-                name: "Claude 3 Haiku".to_string(),
+        // 尝试从 API 获取模型列表，失败则返回预设列表
+        match self.fetch_models().await {
+            Ok(models) if !models.is_empty() => Ok(models),
+            Ok(_) => Ok(fallback_models()),
+            Err(e) => {
+                log::warn!("获取 Anthropic 模型列表失败，使用预设列表: {}", e);
+                Ok(fallback_models())
+            }
+        }
+    }
+
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel = token;
+    }
+}
+
+/// 当前 Claude 模型的上下文窗口（接口未返回时使用）
+const DEFAULT_CONTEXT_LENGTH: u32 = 200_000;
+
+/// 解析模型列表接口返回的 `data[]`
+fn parse_models_response(json: &serde_json::Value) -> Result<Vec<ModelInfo>, LLMError> {
+    let models = json["data"]
+        .as_array()
+        .ok_or_else(|| LLMError::ParseError("Invalid models response".to_string()))?
+        .iter()
+        .filter_map(|model| {
+            let id = model["id"].as_str()?.to_string();
+            let name = model["display_name"].as_str().unwrap_or(&id).to_string();
+            Some(ModelInfo {
+                id,
+                name,
                 provider: ModelProvider::Anthropic,
-                context_length: Some(200000),
-                max_tokens: Some(4096),
+                context_length: Some(
+                    model["max_input_tokens"].as_u64().map(|n| n as u32).unwrap_or(DEFAULT_CONTEXT_LENGTH),
+                ),
+                max_tokens: model["max_tokens"].as_u64().map(|n| n as u32),
                 supports_streaming: true,
                 supports_function_calling: true,
-            },
-        ])
+            })
+        })
+        .collect();
+    Ok(models)
+}
+
+/// 预设的模型列表（接口不可用时使用）
+fn fallback_models() -> Vec<ModelInfo> {
+    [
+        ("claude-3-5-sonnet-latest", "Claude 3.5 Sonnet", 8192),
+        ("claude-3-5-haiku-latest", "Claude 3.5 Haiku", 8192),
+        ("claude-3-opus-20240229", "Claude 3 Opus", 4096),
+        ("claude-3-sonnet-20240229", "Claude 3 Sonnet", 4096),
+        ("claude-3-haiku-20240307", "Claude 3 Haiku", 4096),
+    ]
+    .into_iter()
+    .map(|(id, name, max_tokens)| ModelInfo {
+        id: id.to_string(),
+        name: name.to_string(),
+        provider: ModelProvider::Anthropic,
+        context_length: Some(DEFAULT_CONTEXT_LENGTH),
+        max_tokens: Some(max_tokens),
+        supports_streaming: true,
+        supports_function_calling: true,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(base_url: &str) -> AnthropicProvider {
+        let config = ModelConfig::new(
+            "Test".to_string(),
+            ModelProvider::Anthropic,
+            base_url.to_string(),
+            "test-key".to_string(),
+            "claude-3-haiku-20240307".to_string(),
+        );
+        AnthropicProvider::new(&config)
     }
 
-    async fn test_connection(&self) -> Result<(), LLMError> {
-        // TODO: 实现 Anthropic 连接测试
-        Err(LLMError::ConfigurationError(
-            "Anthropic connection test not yet implemented".to_string(),
-        ))
+    #[test]
+    fn test_build_endpoint_url() {
+        assert_eq!(provider("https://api.anthropic.com").build_endpoint_url("/messages"), "https://api.anthropic.com/v1/messages");
+        assert_eq!(provider("https://api.anthropic.com/v1/").build_endpoint_url("/messages"), "https://api.anthropic.com/v1/messages");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_build_payload_extracts_system() {
+        let messages = vec![
+            ChatMessage::system("你是架构师"),
+            ChatMessage::user("总结这个项目"),
+            ChatMessage::assistant("好的"),
+        ];
+        let payload = provider("https://api.anthropic.com").build_payload(messages, "claude-3-haiku-20240307", false);
+
+        assert_eq!(payload["system"], "你是架构师");
+        assert_eq!(payload["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert_eq!(payload["messages"], json!([
+            {"role": "user", "content": "总结这个项目"},
+            {"role": "assistant", "content": "好的"},
+        ]));
+
+        let payload = provider("https://api.anthropic.com").build_payload(vec![ChatMessage::user("hi")], "m", true);
+        assert!(payload.get("system").is_none());
+        assert!(payload.get("thinking").is_none());
+        assert_eq!(payload["stream"], true);
+    }
+
+    #[test]
+    fn test_build_payload_with_thinking() {
+        let mut config = provider("https://api.anthropic.com").config;
+        config.temperature = Some(0.2);
+        config.max_tokens = Some(1);
+        config.thinking_budget = Some(2000);
+        let payload = AnthropicProvider::new(&config).build_payload(vec![ChatMessage::user("hi")], "m", true);
+
+        assert_eq!(payload["thinking"], json!({"type": "enabled", "budget_tokens": 2000}));
+        assert_eq!(payload["max_tokens"], 2000 + DEFAULT_MAX_TOKENS);
+        assert!(payload.get("temperature").is_none());
+
+        config.thinking_budget = Some(10);
+        config.max_tokens = Some(8000);
+        let payload = AnthropicProvider::new(&config).build_payload(vec![ChatMessage::user("hi")], "m", false);
+        assert_eq!(payload["thinking"]["budget_tokens"], MIN_THINKING_BUDGET);
+        assert_eq!(payload["max_tokens"], 8000);
+    }
+
+    #[test]
+    fn test_build_payload_for_probe() {
+        let mut config = provider("https://api.anthropic.com").config;
+        config.thinking_budget = Some(2000);
+        let probe = crate::llm::LLMFactory::probe_config(&config);
+        let payload = AnthropicProvider::new(&probe).build_payload(vec![ChatMessage::user("hi")], "m", false);
+
+        assert!(payload.get("thinking").is_none());
+        assert_eq!(payload["max_tokens"], 1);
+    }
+
+    #[test]
+    fn test_parse_stream_event() {
+        let delta = json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "你好"}});
+        assert!(matches!(AnthropicProvider::parse_stream_event(&delta), Some(StreamChunk::Text(t)) if t == "你好"));
+
+        let thinking = json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "先比较两个方案"}});
+        assert!(matches!(AnthropicProvider::parse_stream_event(&thinking), Some(StreamChunk::Reasoning(t)) if t == "先比较两个方案"));
+
+        let stop = json!({"type": "message_stop"});
+        assert!(matches!(AnthropicProvider::parse_stream_event(&stop), Some(StreamChunk::Done)));
+
+        let error = json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}});
+        assert!(matches!(AnthropicProvider::parse_stream_event(&error), Some(StreamChunk::Error(m)) if m == "Overloaded"));
+
+        assert!(AnthropicProvider::parse_stream_event(&json!({"type": "ping"})).is_none());
+        assert!(AnthropicProvider::parse_stream_event(&json!({"type": "message_start", "message": {}})).is_none());
+    }
+
+    #[test]
+    fn test_parse_models_response() {
+        let json = serde_json::json!({
+            "data": [
+                { "type": "model", "id": "claude-3-5-sonnet-20241022", "display_name": "Claude 3.5 Sonnet (New)" },
+                { "type": "model", "id": "claude-3-5-haiku-20241022" },
+                { "type": "model" }
+            ],
+            "has_more": false
+        });
+        let models = parse_models_response(&json).unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "Claude 3.5 Sonnet (New)");
+        assert_eq!(models[1].name, "claude-3-5-haiku-20241022");
+        assert_eq!(models[1].context_length, Some(DEFAULT_CONTEXT_LENGTH));
+        assert_eq!(models[1].provider, ModelProvider::Anthropic);
+        assert!(parse_models_response(&serde_json::json!({ "error": {} })).is_err());
+    }
+}
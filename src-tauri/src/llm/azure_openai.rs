@@ -1,35 +1,102 @@
 //! Azure OpenAI 提供商实现
+//!
+//! 请求/响应格式与 OpenAI 一致，但按部署（deployment）路由，并使用 `api-key` 请求头认证。
+//! 配置中的 `default_model` 即部署名称。
 
+use reqwest::Client;
+use tokio_util::sync::CancellationToken;
 use crate::models::{ModelConfig, ModelInfo, ChatMessage, ModelProvider};
 use super::{LLMProvider, LLMError, LLMResponse};
+use super::openai::OpenAIProvider;
+
+/// 未配置 `api_version` 时使用的 Azure OpenAI REST API 版本
+const DEFAULT_API_VERSION: &str = "2024-02-15-preview";
+
+/// 支持 `stream_options`（流式返回使用量）的最早 API 版本日期
+const STREAM_OPTIONS_MIN_VERSION: &str = "2024-09-01";
+
+/// 该 API 版本是否接受 `stream_options` 参数（旧版本会因未知参数拒绝请求）
+fn supports_stream_options(api_version: &str) -> bool {
+    api_version.get(..STREAM_OPTIONS_MIN_VERSION.len())
+        .is_some_and(|date| date >= STREAM_OPTIONS_MIN_VERSION)
+}
 
 /// Azure OpenAI 提供商
 pub struct AzureOpenAIProvider {
+    /// 内部使用 OpenAI 提供商构建请求体与解析响应
+    inner: OpenAIProvider,
     config: ModelConfig,
+    client: Client,
 }
 
 impl AzureOpenAIProvider {
     /// 创建新的 Azure OpenAI 提供商实例
     pub fn new(config: &ModelConfig) -> Self {
         Self {
+            inner: OpenAIProvider::new(config),
             config: config.clone(),
+            client: super::build_http_client(config),
         }
     }
+
+    /// 请求使用的 API 版本
+    fn api_version(&self) -> &str {
+        self.config.api_version.as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(DEFAULT_API_VERSION)
+    }
+
+    /// 构建部署的 Chat Completions 端点 URL
+    fn build_endpoint_url(&self) -> String {
+        let base_url = self.config.api_base_url.trim_end_matches('/');
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            base_url,
+            self.config.default_model.trim(),
+            self.api_version()
+        )
+    }
+
+    /// 使用给定的 OpenAI 兼容实现向部署发送请求
+    async fn send_to_deployment(
+        &self,
+        inner: &OpenAIProvider,
+        messages: Vec<ChatMessage>,
+        stream: bool,
+    ) -> Result<LLMResponse, LLMError> {
+        if self.config.default_model.trim().is_empty() {
+            return Err(LLMError::ConfigurationError(
+                "Azure OpenAI deployment name (default_model) is empty".to_string(),
+            ));
+        }
+
+        let mut payload = inner.build_chat_payload(messages, &self.config.default_model, stream);
+        if !supports_stream_options(self.api_version()) {
+            if let Some(object) = payload.as_object_mut() {
+                object.remove("stream_options");
+            }
+        }
+        let request = self.client
+            .post(self.build_endpoint_url())
+            .header("api-key", &self.config.api_key)
+            .header("Content-Type", "application/json")
+            .json(&payload);
+
+        inner.send_chat_request(request, stream).await
+    }
 }
 
 #[async_trait::async_trait]
 impl LLMProvider for AzureOpenAIProvider {
     async fn chat_completion(
         &self,
-        _messages: Vec<ChatMessage>,
+        messages: Vec<ChatMessage>,
         _model: &str,
-        _stream: bool,
+        stream: bool,
     ) -> Result<LLMResponse, LLMError> {
-        // TODO: 实现 Azure OpenAI API 调用
-        // Azure OpenAI 与 OpenAI API 类似，但端点格式不同
-        Err(LLMError::ConfigurationError(
-            "Azure OpenAI provider not yet implemented".to_string(),
-        ))
+        // Azure 按部署路由，模型由部署决定，忽略传入的模型名
+        self.send_to_deployment(&self.inner, messages, stream).await
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
@@ -66,10 +133,35 @@ impl LLMProvider for AzureOpenAIProvider {
         ])
     }
 
-    async fn test_connection(&self) -> Result<(), LLMError> {
-        // TODO: 实现 Azure OpenAI 连接测试
-        Err(LLMError::ConfigurationError(
-            "Azure OpenAI connection test not yet implemented".to_string(),
-        ))
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.inner.set_cancellation_token(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_endpoint_url() {
+        let config = ModelConfig::new(
+            "Azure".to_string(),
+            ModelProvider::AzureOpenAI,
+            "https://example.openai.azure.com/".to_string(),
+            "test-key".to_string(),
+            "gpt4o-prod".to_string(),
+        );
+        let provider = AzureOpenAIProvider::new(&config);
+        assert_eq!(
+            provider.build_endpoint_url(),
+            "https://example.openai.azure.com/openai/deployments/gpt4o-prod/chat/completions?api-version=2024-02-15-preview"
+        );
+        assert!(!supports_stream_options(provider.api_version()));
+
+        let mut config = config;
+        config.api_version = Some("2024-10-21".to_string());
+        let provider = AzureOpenAIProvider::new(&config);
+        assert!(provider.build_endpoint_url().ends_with("?api-version=2024-10-21"));
+        assert!(supports_stream_options(provider.api_version()));
     }
 }
\ No newline at end of file
@@ -2,7 +2,8 @@
 //!
 //! 支持任意 OpenAI 兼容 API（如 Ollama, vLLM, LiteLLM, Together AI 等）。
 
-use crate::models::{ModelConfig, ModelInfo, ChatMessage};
+use tokio_util::sync::CancellationToken;
+use crate::models::{ModelConfig, ModelInfo, ChatMessage, ToolDef};
 use super::{LLMProvider, LLMError, LLMResponse};
 use super::openai::OpenAIProvider;
 
@@ -35,6 +36,15 @@ impl LLMProvider for CustomProvider {
         self.inner.chat_completion(messages, model, stream).await
     }
 
+    async fn chat_completion_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        tools: Vec<ToolDef>,
+    ) -> Result<LLMResponse, LLMError> {
+        self.inner.chat_completion_with_tools(messages, model, tools).await
+    }
+
     async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
         // 尝试从 API 获取模型列表
         match self.inner.list_models().await {
@@ -62,7 +72,7 @@ impl LLMProvider for CustomProvider {
         }
     }
 
-    async fn test_connection(&self) -> Result<(), LLMError> {
-        self.inner.test_connection().await
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.inner.set_cancellation_token(token);
     }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
 //! DeepSeek 提供商实现
 //!
 //! DeepSeek API 完全兼容 OpenAI 格式，复用 OpenAI 的请求/响应处理逻辑。
+//! `deepseek-reasoner` 在流式响应中额外返回 `reasoning_content`，会作为推理过程单独转发。
 
-use crate::models::{ModelConfig, ModelInfo, ChatMessage, ModelProvider};
+use tokio_util::sync::CancellationToken;
+use crate::models::{ModelConfig, ModelInfo, ChatMessage, ModelProvider, ToolDef};
 use super::{LLMProvider, LLMError, LLMResponse};
 use super::openai::OpenAIProvider;
 
@@ -17,7 +19,7 @@ impl DeepSeekProvider {
     /// 创建新的 DeepSeek 提供商实例
     pub fn new(config: &ModelConfig) -> Self {
         Self {
-            inner: OpenAIProvider::new(config),
+            inner: OpenAIProvider::new(config).with_reasoning_content(),
             config: config.clone(),
         }
     }
@@ -35,6 +37,15 @@ impl LLMProvider for DeepSeekProvider {
         self.inner.chat_completion(messages, model, stream).await
     }
 
+    async fn chat_completion_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        tools: Vec<ToolDef>,
+    ) -> Result<LLMResponse, LLMError> {
+        self.inner.chat_completion_with_tools(messages, model, tools).await
+    }
+
     async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
         // 尝试从 API 获取模型列表，失败则返回预设列表
         match self.inner.list_models().await {
@@ -71,7 +82,7 @@ impl LLMProvider for DeepSeekProvider {
         }
     }
 
-    async fn test_connection(&self) -> Result<(), LLMError> {
-        self.inner.test_connection().await
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.inner.set_cancellation_token(token);
     }
 }
\ No newline at end of file
@@ -63,11 +63,4 @@ impl LLMProvider for GoogleProvider {
             },
         ])
     }
-
-    async fn test_connection(&self) -> Result<(), LLMError> {
-        // TODO: 实现 Google 连接测试
-        Err(LLMError::ConfigurationError(
-            "Google connection test not yet implemented".to_string(),
-        ))
-    }
 }
\ No newline at end of file
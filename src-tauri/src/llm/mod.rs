@@ -2,9 +2,13 @@
 //!
 //! 提供统一的 LLM 接口，支持多种模型厂商。
 
+use std::future::Future;
+use std::time::{Duration, Instant};
+use futures_util::StreamExt;
+use tokio_util::sync::CancellationToken;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use crate::models::{ModelConfig, ModelInfo, ChatMessage};
+use crate::models::{ModelConfig, ModelInfo, ChatMessage, ToolCall, ToolDef};
 
 // 导出各个厂商实现
 pub mod openai;
@@ -25,11 +29,79 @@ pub trait LLMProvider: Send + Sync {
         stream: bool,
     ) -> Result<LLMResponse, LLMError>;
 
+    /// 执行非流式聊天补全并直接返回完整文本
+    async fn complete_text(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+    ) -> Result<String, LLMError> {
+        match self.chat_completion(messages, model, false).await? {
+            LLMResponse::Completion { content, .. } => Ok(content),
+            LLMResponse::Stream { .. } => {
+                Err(LLMError::ParseError("预期非流式响应，但收到流式响应".to_string()))
+            }
+            LLMResponse::ToolCalls { .. } => {
+                Err(LLMError::ParseError("预期文本响应，但收到工具调用".to_string()))
+            }
+        }
+    }
+
+    /// 携带工具定义执行非流式聊天补全，模型选择调用工具时返回 [`LLMResponse::ToolCalls`]
+    ///
+    /// 默认实现只在工具为空时退化为普通补全，其余情况返回配置错误。
+    async fn chat_completion_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        tools: Vec<ToolDef>,
+    ) -> Result<LLMResponse, LLMError> {
+        if tools.is_empty() {
+            return self.chat_completion(messages, model, false).await;
+        }
+        Err(LLMError::ConfigurationError("当前提供商不支持工具调用".to_string()))
+    }
+
     /// 列出可用的模型
     async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError>;
 
-    /// 测试连接和认证
-    async fn test_connection(&self) -> Result<(), LLMError>;
+    /// 发送一次极短的对话请求，验证模型能实际回复并测量延迟
+    ///
+    /// 默认实现沿用配置中的 `max_tokens`，应通过 [`LLMFactory::create_probe_provider`] 创建实例以限制为 1 个 token。
+    async fn ping(&self, model: &str) -> Result<ConnectionReport, LLMError> {
+        let started = Instant::now();
+        let messages = vec![ChatMessage::user("Reply with the single word: pong")];
+        let response = self.chat_completion(messages, model, false).await?;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        match response {
+            LLMResponse::Completion { content, model, .. } => Ok(ConnectionReport {
+                ok: !content.trim().is_empty(),
+                latency_ms,
+                model_echo: Some(model).filter(|m| !m.is_empty()),
+            }),
+            LLMResponse::Stream { .. } => {
+                Err(LLMError::ParseError("预期非流式响应，但收到流式响应".to_string()))
+            }
+            LLMResponse::ToolCalls { .. } => {
+                Err(LLMError::ParseError("预期文本响应，但收到工具调用".to_string()))
+            }
+        }
+    }
+
+    /// 设置取消令牌：令牌触发后流式响应停止读取并关闭连接，随后发送 `Done`
+    ///
+    /// 默认忽略，不支持取消的提供商会照常输出到结束。
+    fn set_cancellation_token(&mut self, _token: CancellationToken) {}
+}
+
+/// 对话连接测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionReport {
+    /// 模型是否返回了非空回复
+    pub ok: bool,
+    /// 请求往返耗时（毫秒）
+    pub latency_ms: u64,
+    /// 响应中报告的模型名称
+    pub model_echo: Option<String>,
 }
 
 /// LLM 响应类型
@@ -41,31 +113,157 @@ pub enum LLMResponse {
         model: String,
         usage: Option<Usage>,
     },
+    /// 模型要求调用工具（仅由 `chat_completion_with_tools` 返回）
+    ToolCalls {
+        calls: Vec<ToolCall>,
+        /// 与工具调用一同返回的文本（通常为空）
+        content: Option<String>,
+        model: String,
+        usage: Option<Usage>,
+    },
     /// 流式响应通道
     Stream {
         stream: tokio::sync::mpsc::Receiver<StreamChunk>,
     },
 }
 
+/// 流式响应通道的默认容量
+///
+/// 通道满时 SSE 读取会等待前端消费（反压），不会丢弃文本；容量越大越能吸收前端的短暂卡顿，
+/// 但卡顿期间堆积的内存也越多。文本块通常只有几个字符，256 个块的缓冲内存可以忽略。
+/// 空的 keep-alive 增量在进入通道前就会被过滤，不占用容量。
+pub const DEFAULT_STREAM_BUFFER: usize = 256;
+
+/// 获取模型配置的流式通道容量（未配置时使用默认值）
+pub fn stream_buffer_size(config: &ModelConfig) -> usize {
+    config.stream_buffer_size.filter(|n| *n > 0).unwrap_or(DEFAULT_STREAM_BUFFER)
+}
+
+/// 默认请求超时秒数
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// 获取模型配置的请求超时（未配置时使用默认值）
+pub fn request_timeout(config: &ModelConfig) -> Duration {
+    Duration::from_secs(config.timeout_secs.filter(|n| *n > 0).unwrap_or(DEFAULT_TIMEOUT_SECS))
+}
+
+/// 构建提供商使用的 HTTP 客户端
+///
+/// 客户端只限制建立连接的时间：整体超时会截断耗时较长的流式响应，
+/// 因此非流式请求单独设置请求超时，流式响应改用数据间隔的空闲超时（见 [`next_stream_event`]）。
+/// 配置了代理时所有请求经该代理发出，否则由 reqwest 读取 `HTTPS_PROXY`/`ALL_PROXY` 等环境变量。
+pub fn build_http_client(config: &ModelConfig) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(request_timeout(config));
+
+    if let Some(proxy_url) = config.proxy_url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        match reqwest::Proxy::all(proxy_url) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => log::warn!("代理地址 {} 无效，已忽略该代理: {}", proxy_url, e),
+        }
+    }
+
+    builder
+        .build()
+        .unwrap_or_else(|e| {
+            log::warn!("构建 HTTP 客户端失败，使用默认配置: {}", e);
+            reqwest::Client::new()
+        })
+}
+
+/// 等待下一个 SSE 事件；超过 `idle_timeout` 仍无数据时返回错误信息
+///
+/// 流结束或 `cancel` 被触发时返回 `Ok(None)`，取消时会先关闭连接，不再消耗输出 token。
+pub async fn next_stream_event(
+    source: &mut reqwest_eventsource::EventSource,
+    idle_timeout: Duration,
+    cancel: &CancellationToken,
+) -> Result<Option<Result<reqwest_eventsource::Event, reqwest_eventsource::Error>>, String> {
+    let next = tokio::select! {
+        _ = cancel.cancelled() => None,
+        next = tokio::time::timeout(idle_timeout, source.next()) => Some(next),
+    };
+    match next {
+        Some(next) => next.map_err(|_| format!("流式响应超过 {} 秒未收到数据", idle_timeout.as_secs())),
+        None => {
+            source.close();
+            Ok(None)
+        }
+    }
+}
+
+/// 暂时性错误的默认重试次数（不含首次请求）
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+/// 首次重试前的基础等待时间，之后逐次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 获取模型配置的重试次数（未配置时使用默认值）
+pub fn max_retries(config: &ModelConfig) -> u32 {
+    config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES)
+}
+
+/// 第 `attempt` 次重试前的等待时间：指数退避并叠加最多 25% 的随机抖动
+fn retry_delay(attempt: u32) -> Duration {
+    let base = RETRY_BASE_DELAY * 2u32.pow(attempt);
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    base + base.mul_f64(f64::from(seed % 1000) / 4000.0)
+}
+
+/// 执行请求，遇到暂时性错误时按指数退避重试最多 `max_retries` 次
+pub async fn retry_with_backoff<T, F, Fut>(max_retries: u32, mut operation: F) -> Result<T, LLMError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, LLMError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Err(e) if e.is_retryable() && attempt < max_retries => {
+                let delay = retry_delay(attempt);
+                log::warn!("LLM 请求失败，{}ms 后重试（第 {} 次）: {}", delay.as_millis(), attempt + 1, e);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// 流式响应块
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StreamChunk {
     /// 文本块
     Text(String),
+    /// 推理过程（如 DeepSeek Reasoner 在正文之前输出的思维链），不计入正文
+    Reasoning(String),
     /// 错误
     Error(String),
+    /// 使用量统计（提供商在流结束前报告时发送）
+    Usage(Usage),
     /// 完成
     Done,
 }
 
 /// 使用量统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Usage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
 }
 
+impl Usage {
+    /// 累加同一任务中另一次请求的使用量
+    pub fn add(&mut self, other: &Usage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
 /// 统一的 LLM 错误类型
 #[derive(Debug, Error)]
 pub enum LLMError {
@@ -99,6 +297,11 @@ impl LLMError {
             _ => LLMError::Unknown(message.to_string()),
         }
     }
+
+    /// 是否为可能自行恢复的暂时性错误（网络错误、超时、服务端错误与 429 限流）
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, LLMError::NetworkError(_) | LLMError::InsufficientQuota)
+    }
 }
 
 /// LLM 提供商工厂
@@ -129,6 +332,19 @@ impl LLMFactory {
         }
     }
 
+    /// 创建用于连接测试的提供商实例，回复限制为 1 个 token
+    pub fn create_probe_provider(config: &ModelConfig) -> Result<Box<dyn LLMProvider>, LLMError> {
+        Self::create_provider(&Self::probe_config(config))
+    }
+
+    /// 连接测试使用的配置：关闭扩展思考，否则思考预算会撑大 1 个 token 的回复上限
+    pub(crate) fn probe_config(config: &ModelConfig) -> ModelConfig {
+        let mut probe_config = config.clone();
+        probe_config.max_tokens = Some(1);
+        probe_config.thinking_budget = None;
+        probe_config
+    }
+
     /// 获取所有支持的提供商类型
     pub fn supported_providers() -> Vec<crate::models::ModelProvider> {
         vec![
@@ -167,4 +383,53 @@ impl From<serde_json::Error> for LLMError {
 
 /// 为异步 trait 启用 async_trait 宏
 #[allow(unused_imports)]
-use async_trait::async_trait;
\ No newline at end of file
+use async_trait::async_trait;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_retry_policy() {
+        assert!(LLMError::from_status_code(429, "").is_retryable());
+        assert!(LLMError::from_status_code(503, "").is_retryable());
+        assert!(!LLMError::from_status_code(401, "").is_retryable());
+        assert!(!LLMError::from_status_code(404, "").is_retryable());
+
+        for attempt in 0..3 {
+            let base = RETRY_BASE_DELAY * 2u32.pow(attempt);
+            let delay = retry_delay(attempt);
+            assert!(delay >= base && delay <= base + base / 4);
+        }
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let mut config = ModelConfig::default_openai("test-key".to_string());
+        assert_eq!(request_timeout(&config), Duration::from_secs(DEFAULT_TIMEOUT_SECS));
+        config.timeout_secs = Some(300);
+        assert_eq!(request_timeout(&config), Duration::from_secs(300));
+        config.timeout_secs = Some(0);
+        assert_eq!(request_timeout(&config), Duration::from_secs(DEFAULT_TIMEOUT_SECS));
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with_backoff(1, || {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if call == 0 { Err(LLMError::InsufficientQuota) } else { Ok(call) }
+            }
+        }).await;
+        assert_eq!(result.unwrap(), 1);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_with_backoff(2, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(LLMError::AuthenticationFailed("bad key".to_string())) }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -4,14 +4,18 @@ use serde_json::json;
 use reqwest::Client;
 use reqwest_eventsource::{Event, EventSource};
 use tokio::sync::mpsc;
-use futures_util::StreamExt;
-use crate::models::{ModelConfig, ModelInfo, ChatMessage, ModelProvider};
+use tokio_util::sync::CancellationToken;
+use crate::models::{ModelConfig, ModelInfo, ChatMessage, ModelProvider, ToolCall, ToolDef};
 use super::{LLMProvider, LLMError, LLMResponse, StreamChunk, Usage};
 
 /// OpenAI 提供商
 pub struct OpenAIProvider {
     config: ModelConfig,
     client: Client,
+    /// 流式响应的取消令牌（默认永不触发）
+    cancel: CancellationToken,
+    /// 是否转发 `delta.reasoning_content`（OpenAI 本身不返回该字段）
+    forward_reasoning: bool,
 }
 
 impl OpenAIProvider {
@@ -19,16 +23,185 @@ impl OpenAIProvider {
     pub fn new(config: &ModelConfig) -> Self {
         Self {
             config: config.clone(),
-            client: Client::new(),
+            client: super::build_http_client(config),
+            cancel: CancellationToken::new(),
+            forward_reasoning: false,
         }
     }
 
+    /// 将流式响应中的 `reasoning_content` 作为 [`StreamChunk::Reasoning`] 转发
+    pub(super) fn with_reasoning_content(mut self) -> Self {
+        self.forward_reasoning = true;
+        self
+    }
+
     /// 构建 API 端点 URL
     fn build_endpoint_url(&self, path: &str) -> String {
         let base_url = self.config.api_base_url.trim_end_matches('/');
         format!("{}{}", base_url, path)
     }
 
+    /// 添加认证头；本地部署（如 Ollama、vLLM）未配置 API Key 时不发送
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.config.api_key.trim().is_empty() {
+            request
+        } else {
+            request.header("Authorization", format!("Bearer {}", self.config.api_key))
+        }
+    }
+
+    /// 按配置移除或重命名后端不支持的请求参数
+    fn apply_payload_overrides(&self, payload: &mut serde_json::Value) {
+        let Some(object) = payload.as_object_mut() else { return };
+
+        for param in &self.config.unsupported_params {
+            object.remove(param);
+        }
+        for (from, to) in &self.config.param_renames {
+            if let Some(value) = object.remove(from) {
+                object.insert(to.clone(), value);
+            }
+        }
+    }
+
+    /// 构建 Chat Completions 请求体（Azure 等兼容实现复用）
+    pub(super) fn build_chat_payload(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        stream: bool,
+    ) -> serde_json::Value {
+        // 转换消息格式
+        let openai_messages: Vec<serde_json::Value> = messages
+            .into_iter()
+            .map(|msg| {
+                json!({
+                    "role": msg.role,
+                    "content": msg.content
+                })
+            })
+            .collect();
+
+        let mut payload = json!({
+            "model": model,
+            "messages": openai_messages,
+            "stream": stream,
+        });
+        if let Some(max_tokens) = self.config.max_tokens {
+            payload["max_tokens"] = json!(max_tokens);
+        }
+        if let Some(temperature) = self.config.temperature {
+            payload["temperature"] = json!(temperature);
+        }
+        if stream {
+            // 让最后一个数据块携带使用量；不支持该参数的后端可通过 unsupported_params 移除
+            payload["stream_options"] = json!({ "include_usage": true });
+        }
+        self.apply_payload_overrides(&mut payload);
+        payload
+    }
+
+    /// 转换为 Chat Completions 的 `tools` 字段
+    fn tools_payload(tools: &[ToolDef]) -> serde_json::Value {
+        tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// 解析 `choices[0].message.tool_calls`，`arguments` 是 JSON 字符串，无法解析时保留原文
+    fn parse_tool_calls(message: &serde_json::Value) -> Vec<ToolCall> {
+        let Some(calls) = message["tool_calls"].as_array() else { return Vec::new() };
+
+        calls
+            .iter()
+            .filter_map(|call| {
+                let function = &call["function"];
+                let name = function["name"].as_str()?.to_string();
+                let arguments = match &function["arguments"] {
+                    serde_json::Value::String(raw) => serde_json::from_str(raw)
+                        .unwrap_or_else(|_| serde_json::Value::String(raw.clone())),
+                    other => other.clone(),
+                };
+                Some(ToolCall {
+                    id: call["id"].as_str().unwrap_or_default().to_string(),
+                    name,
+                    arguments,
+                })
+            })
+            .collect()
+    }
+
+    /// 发送已构建好端点与认证头的请求，并按是否流式处理响应
+    pub(super) async fn send_chat_request(
+        &self,
+        request: reqwest::RequestBuilder,
+        stream: bool,
+    ) -> Result<LLMResponse, LLMError> {
+        if stream {
+            let source = EventSource::new(request)
+                .map_err(|e| LLMError::NetworkError(e.to_string()))?;
+            self.handle_stream_response(source).await
+        } else {
+            let request = request.timeout(super::request_timeout(&self.config));
+            let response = super::retry_with_backoff(super::max_retries(&self.config), || {
+                let request = request.try_clone();
+                async move {
+                    let response = request
+                        .ok_or_else(|| LLMError::Unknown("无法重试该请求".to_string()))?
+                        .send()
+                        .await?;
+
+                    if !response.status().is_success() {
+                        let status = response.status();
+                        let error_text = response.text().await.unwrap_or_default();
+                        return Err(LLMError::from_status_code(status.as_u16(), &error_text));
+                    }
+                    Ok(response)
+                }
+            }).await?;
+
+            self.handle_completion_response(response).await
+        }
+    }
+
+    /// 解析 `usage` 字段
+    fn parse_usage(usage: &serde_json::Value) -> Usage {
+        Usage {
+            prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0) as u32,
+            completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
+            total_tokens: usage["total_tokens"].as_u64().unwrap_or(0) as u32,
+        }
+    }
+
+    /// 解析流式响应的一个数据块
+    fn parse_stream_data(value: &serde_json::Value, forward_reasoning: bool) -> Vec<StreamChunk> {
+        let mut chunks = Vec::new();
+        let delta = &value["choices"][0]["delta"];
+        if forward_reasoning {
+            if let Some(reasoning) = delta["reasoning_content"].as_str().filter(|r| !r.is_empty()) {
+                chunks.push(StreamChunk::Reasoning(reasoning.to_string()));
+            }
+        }
+        if let Some(content) = delta["content"].as_str().filter(|c| !c.is_empty()) {
+            chunks.push(StreamChunk::Text(content.to_string()));
+        }
+        // 开启 include_usage 后，最后一个数据块的 choices 为空并携带使用量
+        if let Some(usage) = value.get("usage").filter(|u| u.is_object()) {
+            chunks.push(StreamChunk::Usage(Self::parse_usage(usage)));
+        }
+        chunks
+    }
+
     /// 处理非流式响应
     async fn handle_completion_response(
         &self,
@@ -43,23 +216,28 @@ impl OpenAIProvider {
             return Err(LLMError::RequestFailed(error_msg.to_string()));
         }
 
-        let content = json["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or_else(|| LLMError::ParseError("Missing content in response".to_string()))?
-            .to_string();
-
+        let message = &json["choices"][0]["message"];
         let model = json["model"]
             .as_str()
             .unwrap_or("unknown")
             .to_string();
 
-        let usage = json.get("usage").map(|usage| {
-            Usage {
-                prompt_tokens: usage["prompt_tokens"].as_u64().unwrap_or(0) as u32,
-                completion_tokens: usage["completion_tokens"].as_u64().unwrap_or(0) as u32,
-                total_tokens: usage["total_tokens"].as_u64().unwrap_or(0) as u32,
-            }
-        });
+        let usage = json.get("usage").map(Self::parse_usage);
+
+        let calls = Self::parse_tool_calls(message);
+        if !calls.is_empty() {
+            return Ok(LLMResponse::ToolCalls {
+                calls,
+                content: message["content"].as_str().map(str::to_string),
+                model,
+                usage,
+            });
+        }
+
+        let content = message["content"]
+            .as_str()
+            .ok_or_else(|| LLMError::ParseError("Missing content in response".to_string()))?
+            .to_string();
 
         Ok(LLMResponse::Completion {
             content,
@@ -73,10 +251,21 @@ impl OpenAIProvider {
         &self,
         mut source: EventSource,
     ) -> Result<LLMResponse, LLMError> {
-        let (tx, rx) = mpsc::channel(100);
+        let (tx, rx) = mpsc::channel(super::stream_buffer_size(&self.config));
+        let idle_timeout = super::request_timeout(&self.config);
+        let cancel = self.cancel.clone();
+        let forward_reasoning = self.forward_reasoning;
 
         tokio::spawn(async move {
-            while let Some(event) = source.next().await {
+            loop {
+                let event = match super::next_stream_event(&mut source, idle_timeout, &cancel).await {
+                    Ok(Some(event)) => event,
+                    Ok(None) => break,
+                    Err(message) => {
+                        let _ = tx.send(StreamChunk::Error(message)).await;
+                        break;
+                    }
+                };
                 match event {
                     Ok(Event::Message(message)) => {
                         if message.data == "[DONE]" {
@@ -86,10 +275,8 @@ impl OpenAIProvider {
 
                         match serde_json::from_str::<serde_json::Value>(&message.data) {
                             Ok(value) => {
-                                if let Some(content) = value["choices"][0]["delta"]["content"].as_str() {
-                                    if !content.is_empty() {
-                                        let _ = tx.send(StreamChunk::Text(content.to_string())).await;
-                                    }
+                                for chunk in Self::parse_stream_data(&value, forward_reasoning) {
+                                    let _ = tx.send(chunk).await;
                                 }
                             }
                             Err(e) => {
@@ -125,55 +312,38 @@ impl LLMProvider for OpenAIProvider {
         stream: bool,
     ) -> Result<LLMResponse, LLMError> {
         let endpoint = self.build_endpoint_url("/chat/completions");
+        let payload = self.build_chat_payload(messages, model, stream);
 
-        // 转换消息格式
-        let openai_messages: Vec<serde_json::Value> = messages
-            .into_iter()
-            .map(|msg| {
-                json!({
-                    "role": msg.role,
-                    "content": msg.content
-                })
-            })
-            .collect();
-
-        let payload = json!({
-            "model": model,
-            "messages": openai_messages,
-            "stream": stream,
-        });
-
-        let request = self.client
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
+        let request = self.authorize(self.client.post(&endpoint))
             .header("Content-Type", "application/json")
             .json(&payload);
 
-        if stream {
-            let source = EventSource::new(request)
-                .map_err(|e| LLMError::NetworkError(e.to_string()))?;
-            self.handle_stream_response(source).await
-        } else {
-            let response = request
-                .send()
-                .await?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_text = response.text().await.unwrap_or_default();
-                return Err(LLMError::from_status_code(status.as_u16(), &error_text));
-            }
+        self.send_chat_request(request, stream).await
+    }
 
-            self.handle_completion_response(response).await
+    async fn chat_completion_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        model: &str,
+        tools: Vec<ToolDef>,
+    ) -> Result<LLMResponse, LLMError> {
+        let endpoint = self.build_endpoint_url("/chat/completions");
+        let mut payload = self.build_chat_payload(messages, model, false);
+        if !tools.is_empty() {
+            payload["tools"] = Self::tools_payload(&tools);
         }
+
+        let request = self.authorize(self.client.post(&endpoint))
+            .header("Content-Type", "application/json")
+            .json(&payload);
+
+        self.send_chat_request(request, false).await
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>, LLMError> {
         let endpoint = self.build_endpoint_url("/models");
 
-        let response = self.client
-            .get(&endpoint)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
+        let response = self.authorize(self.client.get(&endpoint))
             .send()
             .await?;
 
@@ -215,14 +385,8 @@ impl LLMProvider for OpenAIProvider {
         Ok(models)
     }
 
-    async fn test_connection(&self) -> Result<(), LLMError> {
-        // 尝试列出模型来测试连接
-        let result = self.list_models().await;
-        match result {
-            Ok(_) => Ok(()),
-            Err(LLMError::AuthenticationFailed(msg)) => Err(LLMError::AuthenticationFailed(msg)),
-            Err(e) => Err(LLMError::ConfigurationError(format!("Connection test failed: {}", e))),
-        }
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancel = token;
     }
 }
 
@@ -230,6 +394,108 @@ impl LLMProvider for OpenAIProvider {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_apply_payload_overrides() {
+        let mut config = ModelConfig::new(
+            "Test".to_string(),
+            ModelProvider::OpenAI,
+            "https://api.openai.com/v1".to_string(),
+            "test-key".to_string(),
+            "o1-mini".to_string(),
+        );
+        config.unsupported_params = vec!["temperature".to_string()];
+        config.param_renames.insert("max_tokens".to_string(), "max_completion_tokens".to_string());
+        let provider = OpenAIProvider::new(&config);
+
+        let mut payload = json!({
+            "model": "o1-mini",
+            "temperature": 0.2,
+            "max_tokens": 100,
+        });
+        provider.apply_payload_overrides(&mut payload);
+
+        assert_eq!(payload, json!({
+            "model": "o1-mini",
+            "max_completion_tokens": 100,
+        }));
+    }
+
+    #[test]
+    fn test_build_chat_payload_sampling_params() {
+        let mut config = ModelConfig::new(
+            "Test".to_string(),
+            ModelProvider::OpenAI,
+            "https://api.openai.com/v1".to_string(),
+            "test-key".to_string(),
+            "gpt-4".to_string(),
+        );
+        let payload = OpenAIProvider::new(&config).build_chat_payload(vec![ChatMessage::user("hi")], "gpt-4", false);
+        assert!(payload.get("temperature").is_none());
+        assert!(payload.get("max_tokens").is_none());
+        assert!(payload.get("stream_options").is_none());
+
+        let payload = OpenAIProvider::new(&config).build_chat_payload(vec![ChatMessage::user("hi")], "gpt-4", true);
+        assert_eq!(payload["stream_options"]["include_usage"], true);
+
+        config.temperature = Some(0.5);
+        config.max_tokens = Some(256);
+        let payload = OpenAIProvider::new(&config).build_chat_payload(vec![ChatMessage::user("hi")], "gpt-4", false);
+        assert_eq!(payload["temperature"], 0.5);
+        assert_eq!(payload["max_tokens"], 256);
+    }
+
+    #[test]
+    fn test_parse_stream_data_reasoning() {
+        let value = json!({ "choices": [{ "delta": { "reasoning_content": "先看 README", "content": "" } }] });
+        let chunks = OpenAIProvider::parse_stream_data(&value, true);
+        assert!(matches!(chunks.as_slice(), [StreamChunk::Reasoning(r)] if r == "先看 README"));
+        assert!(OpenAIProvider::parse_stream_data(&value, false).is_empty());
+
+        let value = json!({ "choices": [{ "delta": { "reasoning_content": null, "content": "答案" } }] });
+        let chunks = OpenAIProvider::parse_stream_data(&value, true);
+        assert!(matches!(chunks.as_slice(), [StreamChunk::Text(t)] if t == "答案"));
+
+        let value = json!({ "choices": [], "usage": { "prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5 } });
+        let chunks = OpenAIProvider::parse_stream_data(&value, true);
+        assert!(matches!(chunks.as_slice(), [StreamChunk::Usage(u)] if u.total_tokens == 5));
+    }
+
+    #[test]
+    fn test_tool_calls() {
+        let tools = vec![ToolDef {
+            name: "extract_repo".to_string(),
+            description: "提取仓库元数据".to_string(),
+            parameters: json!({ "type": "object", "properties": { "language": { "type": "string" } } }),
+        }];
+        let payload = OpenAIProvider::tools_payload(&tools);
+        assert_eq!(payload[0]["type"], "function");
+        assert_eq!(payload[0]["function"]["name"], "extract_repo");
+        assert_eq!(payload[0]["function"]["parameters"]["type"], "object");
+
+        let message = json!({
+            "content": null,
+            "tool_calls": [
+                {
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "extract_repo", "arguments": "{\"language\":\"Rust\"}" }
+                },
+                {
+                    "id": "call_2",
+                    "type": "function",
+                    "function": { "name": "extract_repo", "arguments": "{invalid" }
+                }
+            ]
+        });
+        let calls = OpenAIProvider::parse_tool_calls(&message);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(calls[0].arguments, json!({ "language": "Rust" }));
+        assert_eq!(calls[1].arguments, json!("{invalid"));
+
+        assert!(OpenAIProvider::parse_tool_calls(&json!({ "content": "hi" })).is_empty());
+    }
+
     #[test]
     fn test_build_endpoint_url() {
         let config = ModelConfig::new(
@@ -0,0 +1,54 @@
+//! 日志输出
+//!
+//! 将 `log` 宏的输出写到标准错误，级别由 `RUST_LOG` 环境变量控制（默认 info）。
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct StderrLogger;
+
+static LOGGER: StderrLogger = StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "[{} {} {}] {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// 解析日志级别，无法识别时使用 info
+fn parse_level(spec: Option<&str>) -> LevelFilter {
+    spec.and_then(|s| s.trim().parse().ok()).unwrap_or(LevelFilter::Info)
+}
+
+/// 安装日志输出（应用启动时调用一次，重复调用会被忽略）
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(parse_level(std::env::var("RUST_LOG").ok().as_deref()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level(None), LevelFilter::Info);
+        assert_eq!(parse_level(Some("debug")), LevelFilter::Debug);
+        assert_eq!(parse_level(Some(" WARN ")), LevelFilter::Warn);
+        assert_eq!(parse_level(Some("verbose")), LevelFilter::Info);
+    }
+}
@@ -0,0 +1,111 @@
+//! 仓库元数据提取
+//!
+//! 通过工具调用让模型按 JSON Schema 返回结构化的仓库信息，避免从自由文本中解析。
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::ai::RepoInfo;
+use crate::config::commands::ConfigManagerState;
+use crate::error::{CommandError, ErrorKind};
+use crate::github::GitHubHost;
+use crate::llm::{LLMFactory, LLMResponse};
+use crate::models::{ChatMessage, ToolCall, ToolDef};
+
+/// 提取元数据使用的工具名
+const METADATA_TOOL: &str = "record_repo_metadata";
+
+/// 模型提取的仓库元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepoMetadata {
+    /// 项目类别（如 "CLI 工具"、"Web 框架"）
+    pub category: String,
+    /// 使用的主要框架或库
+    #[serde(default)]
+    pub frameworks: Vec<String>,
+    /// 典型使用场景
+    #[serde(default)]
+    pub use_cases: Vec<String>,
+    /// 成熟度：experimental、active 或 stable
+    pub maturity: String,
+}
+
+/// 提取元数据的工具定义
+fn metadata_tool() -> ToolDef {
+    ToolDef {
+        name: METADATA_TOOL.to_string(),
+        description: "记录从仓库信息中提取的结构化元数据".to_string(),
+        parameters: json!({
+            "type": "object",
+            "properties": {
+                "category": { "type": "string", "description": "项目类别，如 CLI 工具、Web 框架、LLM 应用" },
+                "frameworks": { "type": "array", "items": { "type": "string" }, "description": "使用的主要框架或库" },
+                "use_cases": { "type": "array", "items": { "type": "string" }, "description": "典型使用场景" },
+                "maturity": { "type": "string", "enum": ["experimental", "active", "stable"] }
+            },
+            "required": ["category", "maturity"]
+        }),
+    }
+}
+
+/// 从模型返回的工具调用中解析元数据
+fn parse_metadata(calls: &[ToolCall]) -> Result<RepoMetadata, String> {
+    let call = calls
+        .iter()
+        .find(|call| call.name == METADATA_TOOL)
+        .ok_or_else(|| format!("模型未调用 {}", METADATA_TOOL))?;
+    serde_json::from_value(call.arguments.clone()).map_err(|e| format!("元数据格式无效: {}", e))
+}
+
+/// 使用模型的工具调用能力提取仓库元数据（当前仅 OpenAI 兼容的提供商支持）
+#[tauri::command]
+pub async fn extract_repo_metadata(
+    repo: RepoInfo,
+    model_config_id: String,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<RepoMetadata, CommandError> {
+    crate::offline::ensure_online().map_err(CommandError::network)?;
+    let manager = config_manager.lock().await;
+    let config = crate::ai::find_model_config(&manager, &model_config_id).await?;
+    let settings = manager.get_app_settings().await.unwrap_or_default();
+    let provider = LLMFactory::create_provider(&config)?;
+    let provider = crate::audit::wrap_provider(provider, &config, &manager).await;
+    drop(manager);
+
+    let readme = crate::ai::fetch_readme(&GitHubHost::from_settings(&settings), &repo.author, &repo.name)
+        .await
+        .unwrap_or_default();
+    let prompt = format!(
+        "请阅读以下 GitHub 项目信息，并调用 {} 记录它的元数据：\n项目：{}/{}\n描述：{}\n语言：{}\nREADME 片段：\n{}",
+        METADATA_TOOL, repo.author, repo.name, repo.description, repo.language, readme
+    );
+    let response = provider
+        .chat_completion_with_tools(vec![ChatMessage::user(&prompt)], &config.default_model, vec![metadata_tool()])
+        .await?;
+
+    match response {
+        LLMResponse::ToolCalls { calls, .. } => parse_metadata(&calls).map_err(|e| CommandError::new(ErrorKind::Parse, e)),
+        _ => Err(CommandError::new(ErrorKind::Parse, format!("模型未调用 {}", METADATA_TOOL))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metadata() {
+        let calls = vec![ToolCall {
+            id: "call_1".to_string(),
+            name: METADATA_TOOL.to_string(),
+            arguments: json!({ "category": "CLI 工具", "frameworks": ["clap"], "maturity": "stable" }),
+        }];
+        let metadata = parse_metadata(&calls).unwrap();
+        assert_eq!(metadata.category, "CLI 工具");
+        assert_eq!(metadata.frameworks, vec!["clap"]);
+        assert!(metadata.use_cases.is_empty());
+
+        assert!(parse_metadata(&[]).is_err());
+        let invalid = vec![ToolCall { arguments: json!("not json"), ..calls[0].clone() }];
+        assert!(parse_metadata(&invalid).is_err());
+    }
+}
@@ -33,6 +33,44 @@ impl ModelProvider {
         }
     }
 
+    /// 根据 API Key 的前缀猜测其所属提供商（仅为启发式判断）
+    fn guess_from_api_key(api_key: &str) -> Option<ModelProvider> {
+        let key = api_key.trim();
+        if key.starts_with("sk-ant-") {
+            Some(ModelProvider::Anthropic)
+        } else if key.starts_with("AIza") {
+            Some(ModelProvider::Google)
+        } else if key.starts_with("sk-") {
+            Some(ModelProvider::OpenAI)
+        } else {
+            None
+        }
+    }
+
+    /// 检查 API Key 格式是否与提供商匹配，不匹配时返回警告（不拦截保存）
+    pub fn key_format_warning(&self, api_key: &str) -> Option<String> {
+        let guessed = Self::guess_from_api_key(api_key);
+        let mismatch = match self {
+            // DeepSeek 与 OpenAI 同样使用 sk- 前缀
+            ModelProvider::OpenAI | ModelProvider::DeepSeek => guessed != Some(ModelProvider::OpenAI),
+            ModelProvider::Anthropic | ModelProvider::Google => guessed.as_ref() != Some(self),
+            // Azure 与自定义服务的密钥格式不固定，只在明显属于其他提供商时提醒
+            ModelProvider::AzureOpenAI => guessed.is_some(),
+            ModelProvider::Custom(_) => false,
+        };
+        if !mismatch {
+            return None;
+        }
+        Some(match guessed {
+            Some(other) => format!(
+                "该 API Key 看起来属于 {}，与所选的 {} 不匹配，请确认是否粘贴错误",
+                other.display_name(),
+                self.display_name()
+            ),
+            None => format!("该 API Key 不符合 {} 的常见格式，请确认是否粘贴完整", self.display_name()),
+        })
+    }
+
     /// 获取默认的API基础URL
     pub fn default_api_base_url(&self) -> String {
         match self {
@@ -57,6 +95,64 @@ impl ModelProvider {
         }
     }
 
+    /// 模型列表未提供上下文长度时使用的默认值（token）
+    pub fn fallback_context_length(&self, model: &str) -> u32 {
+        let model = model.to_lowercase();
+        match self {
+            ModelProvider::Google if model.contains("1.5") || model.contains("2.") => 1_000_000,
+            ModelProvider::Google => 32_768,
+            ModelProvider::Anthropic => 200_000,
+            ModelProvider::DeepSeek => 64_000,
+            ModelProvider::OpenAI | ModelProvider::AzureOpenAI => {
+                if model.contains("gpt-4.1") {
+                    1_000_000
+                } else if model.starts_with('o') || model.contains("gpt-4o") || model.contains("turbo") {
+                    128_000
+                } else if model.contains("gpt-4") {
+                    8_192
+                } else {
+                    16_385
+                }
+            }
+            ModelProvider::Custom(_) => 32_768,
+        }
+    }
+
+    /// 获取推荐的常用模型（实时模型列表拉取失败或过长时供用户挑选）
+    pub fn recommended_models(&self) -> Vec<RecommendedModel> {
+        let models: &[(&str, &str, &str)] = match self {
+            ModelProvider::OpenAI | ModelProvider::AzureOpenAI => &[
+                ("gpt-4o-mini", "GPT-4o mini", "便宜快速，适合日常总结"),
+                ("gpt-4o", "GPT-4o", "综合质量高"),
+                ("gpt-4.1", "GPT-4.1", "长上下文，适合深度模式"),
+                ("o3-mini", "o3-mini", "推理模型，响应较慢"),
+            ],
+            ModelProvider::Anthropic => &[
+                ("claude-3-haiku-20240307", "Claude 3 Haiku", "便宜快速"),
+                ("claude-3-5-sonnet-20241022", "Claude 3.5 Sonnet", "综合质量高"),
+                ("claude-3-opus-20240229", "Claude 3 Opus", "质量最好，价格较高"),
+            ],
+            ModelProvider::Google => &[
+                ("gemini-1.5-flash", "Gemini 1.5 Flash", "便宜快速"),
+                ("gemini-1.5-pro", "Gemini 1.5 Pro", "长上下文，质量高"),
+                ("gemini-pro", "Gemini Pro", "兼容旧版 API"),
+            ],
+            ModelProvider::DeepSeek => &[
+                ("deepseek-chat", "DeepSeek Chat", "便宜快速，中文表现好"),
+                ("deepseek-reasoner", "DeepSeek Reasoner", "推理模型，会输出思考过程"),
+            ],
+            ModelProvider::Custom(_) => &[],
+        };
+        models
+            .iter()
+            .map(|(id, name, note)| RecommendedModel {
+                id: id.to_string(),
+                name: name.to_string(),
+                note: note.to_string(),
+            })
+            .collect()
+    }
+
     /// 检查该提供商是否需要API密钥
     pub fn requires_api_key(&self) -> bool {
         match self {
@@ -65,7 +161,8 @@ impl ModelProvider {
             ModelProvider::Google => true,
             ModelProvider::DeepSeek => true,
             ModelProvider::AzureOpenAI => true,
-            ModelProvider::Custom(_) => true,
+            // 本地部署的 OpenAI 兼容服务（Ollama、vLLM 等）通常不需要密钥
+            ModelProvider::Custom(_) => false,
         }
     }
 
@@ -79,6 +176,15 @@ impl ModelProvider {
     }
 }
 
+/// 推荐模型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendedModel {
+    pub id: String,
+    pub name: String,
+    /// 简短说明（如“便宜快速”“质量最好”）
+    pub note: String,
+}
+
 /// 模型配置结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
@@ -90,6 +196,36 @@ pub struct ModelConfig {
     pub api_key: String, // API密钥（加密存储）
     pub default_model: String, // 默认模型名称
     pub enabled: bool,
+    /// 后端不支持、需要从请求体中移除的参数（如 `temperature`）
+    #[serde(default)]
+    pub unsupported_params: Vec<String>,
+    /// 需要重命名的请求参数（如 `max_tokens` -> `max_completion_tokens`）
+    #[serde(default)]
+    pub param_renames: HashMap<String, String>,
+    /// 单次回复的最大 token 数（为空则使用服务端默认值）
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// 采样温度（为空则使用服务端默认值）
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// 暂时性错误（网络错误、限流、服务端错误）的重试次数（为空则使用 `llm::DEFAULT_MAX_RETRIES`）
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// 请求超时秒数；流式响应中作为两次数据之间的最长等待（为空则使用 `llm::DEFAULT_TIMEOUT_SECS`）
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// HTTP(S) 代理地址（如 `http://127.0.0.1:7890`，暂不支持 SOCKS）；为空时使用 `HTTPS_PROXY`/`ALL_PROXY` 等环境变量
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// 流式响应通道容量（为空则使用默认值，见 `llm::DEFAULT_STREAM_BUFFER`）
+    #[serde(default)]
+    pub stream_buffer_size: Option<usize>,
+    /// API 版本（目前仅 Azure OpenAI 使用，为空则使用提供商的默认版本）
+    #[serde(default)]
+    pub api_version: Option<String>,
+    /// 扩展思考的 token 预算（目前仅 Anthropic 使用，为空则不开启）
+    #[serde(default)]
+    pub thinking_budget: Option<u32>,
     #[serde(default = "default_now")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde(default = "default_now")]
@@ -117,6 +253,16 @@ impl ModelConfig {
             api_key,
             default_model,
             enabled: true,
+            unsupported_params: Vec::new(),
+            param_renames: HashMap::new(),
+            max_tokens: None,
+            temperature: None,
+            max_retries: None,
+            timeout_secs: None,
+            proxy_url: None,
+            stream_buffer_size: None,
+            api_version: None,
+            thinking_budget: None,
             created_at: now,
             updated_at: now,
         }
@@ -133,6 +279,19 @@ impl ModelConfig {
         )
     }
 
+    /// 复制配置：生成新的 ID，名称追加“(副本)”，副本默认启用
+    pub fn duplicate(&self) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: default_id(),
+            name: format!("{} (副本)", self.name),
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+            ..self.clone()
+        }
+    }
+
     /// 更新配置
     pub fn update(&mut self, updates: ModelConfigUpdate) {
         if let Some(name) = updates.name {
@@ -153,8 +312,69 @@ impl ModelConfig {
         if let Some(enabled) = updates.enabled {
             self.enabled = enabled;
         }
+        if let Some(unsupported_params) = updates.unsupported_params {
+            self.unsupported_params = unsupported_params;
+        }
+        if let Some(param_renames) = updates.param_renames {
+            self.param_renames = param_renames;
+        }
+        if let Some(max_tokens) = updates.max_tokens {
+            self.max_tokens = Some(max_tokens).filter(|n| *n > 0);
+        }
+        if let Some(temperature) = updates.temperature {
+            // 负值表示清除，恢复服务端默认值
+            self.temperature = Some(temperature).filter(|t| *t >= 0.0);
+        }
+        if let Some(max_retries) = updates.max_retries {
+            self.max_retries = Some(max_retries);
+        }
+        if let Some(timeout_secs) = updates.timeout_secs {
+            self.timeout_secs = Some(timeout_secs).filter(|n| *n > 0);
+        }
+        if let Some(proxy_url) = updates.proxy_url {
+            let proxy_url = proxy_url.trim().to_string();
+            self.proxy_url = Some(proxy_url).filter(|url| !url.is_empty());
+        }
+        if let Some(stream_buffer_size) = updates.stream_buffer_size {
+            self.stream_buffer_size = Some(stream_buffer_size).filter(|n| *n > 0);
+        }
+        if let Some(api_version) = updates.api_version {
+            let api_version = api_version.trim().to_string();
+            self.api_version = Some(api_version).filter(|v| !v.is_empty());
+        }
+        if let Some(thinking_budget) = updates.thinking_budget {
+            // 0 表示关闭扩展思考
+            self.thinking_budget = Some(thinking_budget).filter(|n| *n > 0);
+        }
         self.updated_at = chrono::Utc::now();
     }
+
+    /// 检查配置是否可用（必填项与 URL 格式），返回具体的错误说明
+    pub fn validate(&self) -> Result<(), String> {
+        let provider = self.provider.display_name();
+        if self.provider.requires_api_key() && self.api_key.trim().is_empty() {
+            return Err(format!("{} 需要填写 API Key", provider));
+        }
+        let base_url = self.api_base_url.trim();
+        if base_url.is_empty() {
+            if self.provider.requires_custom_base_url() {
+                return Err(format!("{} 需要填写 API 基础 URL", provider));
+            }
+        } else {
+            match reqwest::Url::parse(base_url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                _ => return Err(format!("API 基础 URL 格式无效（需以 http:// 或 https:// 开头）: {}", base_url)),
+            }
+        }
+        if let Some(proxy_url) = &self.proxy_url {
+            match reqwest::Url::parse(proxy_url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                Ok(url) => return Err(format!("不支持的代理协议 {}（仅支持 http:// 或 https://）", url.scheme())),
+                Err(_) => return Err(format!("代理地址格式无效: {}", proxy_url)),
+            }
+        }
+        Ok(())
+    }
 }
 
 /// 模型配置更新结构（用于部分更新）
@@ -166,6 +386,16 @@ pub struct ModelConfigUpdate {
     pub api_key: Option<String>,
     pub default_model: Option<String>,
     pub enabled: Option<bool>,
+    pub unsupported_params: Option<Vec<String>>,
+    pub param_renames: Option<HashMap<String, String>>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub max_retries: Option<u32>,
+    pub timeout_secs: Option<u64>,
+    pub proxy_url: Option<String>,
+    pub stream_buffer_size: Option<usize>,
+    pub api_version: Option<String>,
+    pub thinking_budget: Option<u32>,
 }
 
 /// 模型信息（从API拉取）
@@ -180,13 +410,403 @@ pub struct ModelInfo {
     pub supports_function_calling: bool,
 }
 
+/// 单个提供商的模型列表缓存，各自独立过期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "ModelCacheEntryRepr")]
+pub struct ModelCacheEntry {
+    pub models: Vec<ModelInfo>,
+    /// 过期时间（旧格式缓存没有该字段，视为已过期）
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 兼容旧格式：旧版本直接按提供商保存模型列表，过期时间是全局的
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ModelCacheEntryRepr {
+    Entry {
+        models: Vec<ModelInfo>,
+        #[serde(default)]
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    Legacy(Vec<ModelInfo>),
+}
+
+impl From<ModelCacheEntryRepr> for ModelCacheEntry {
+    fn from(repr: ModelCacheEntryRepr) -> Self {
+        match repr {
+            ModelCacheEntryRepr::Entry { models, expires_at } => Self { models, expires_at },
+            ModelCacheEntryRepr::Legacy(models) => Self { models, expires_at: None },
+        }
+    }
+}
+
+impl ModelCacheEntry {
+    /// 创建在 `hours` 小时后过期的缓存
+    pub fn new(models: Vec<ModelInfo>, hours: i64) -> Self {
+        Self { models, expires_at: Some(chrono::Utc::now() + chrono::Duration::hours(hours)) }
+    }
+
+    /// 检查缓存是否过期
+    pub fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(expiry) => chrono::Utc::now() >= expiry,
+            None => true,
+        }
+    }
+}
+
 /// 应用配置
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
     pub active_model_config_id: Option<String>, // 当前激活的模型配置ID
     pub model_configs: Vec<ModelConfig>, // 所有模型配置
-    pub model_cache: HashMap<String, Vec<ModelInfo>>, // 模型列表缓存（按提供商）
-    pub cache_expires_at: Option<chrono::DateTime<chrono::Utc>>, // 缓存过期时间
+    pub model_cache: HashMap<String, ModelCacheEntry>, // 模型列表缓存（按提供商，各自过期）
+    #[serde(default)]
+    pub settings: AppSettings, // 全局应用设置
+}
+
+/// 深度上下文默认尝试抓取的根目录文件
+pub const DEFAULT_CONTEXT_FILES: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "go.mod",
+    "requirements.txt",
+    "pyproject.toml",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Gemfile",
+    "composer.json",
+    "pubspec.yaml",
+    "deno.json",
+    "Dockerfile",
+    "docker-compose.yml",
+];
+
+/// 全局应用设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// 用户追加的深度上下文文件（在默认列表之后尝试）
+    #[serde(default)]
+    pub extra_context_files: Vec<String>,
+    /// 替换内置列表的深度上下文文件（为空则使用 [`DEFAULT_CONTEXT_FILES`]）
+    #[serde(default)]
+    pub context_files_override: Option<Vec<String>>,
+    /// 是否将发送给模型的提示词与响应写入本地审计日志
+    #[serde(default)]
+    pub audit_llm_requests: bool,
+    /// GitHub 主机（GitHub Enterprise 填写如 `github.mycompany.com`，为空则使用 github.com）
+    #[serde(default)]
+    pub github_host: Option<String>,
+    /// GitHub 个人访问令牌，配置后以 `Authorization: Bearer` 发送，提高 API 限流额度
+    #[serde(default)]
+    pub github_token: Option<String>,
+    /// 缓存总结前是否剔除推理模型输出的 `<think>` 思考过程
+    #[serde(default = "default_true")]
+    pub strip_reasoning: bool,
+    /// 洞察缓存最多保留的条数（为空则不限制）
+    #[serde(default)]
+    pub insight_cache_max_entries: Option<usize>,
+    /// 洞察缓存最多占用的字节数（为空则不限制）
+    #[serde(default)]
+    pub insight_cache_max_bytes: Option<u64>,
+    /// 超过该天数未访问的洞察将被清理（为空则不限制）
+    #[serde(default)]
+    pub insight_cache_max_age_days: Option<u64>,
+    /// 洞察生成超过该天数后视为过期，读取时重新生成（为空则永不过期）
+    #[serde(default)]
+    pub insight_ttl_days: Option<u64>,
+    /// 清理缓存时是否允许删除已收藏仓库的洞察
+    #[serde(default)]
+    pub evict_favorite_insights: bool,
+    /// 是否将生成的洞察缓存到本地磁盘（关闭后既不读取也不写入缓存）
+    #[serde(default = "default_true")]
+    pub cache_insights: bool,
+    /// 单次总结最多发起的 GitHub 请求数，超出后跳过剩余的上下文抓取
+    #[serde(default = "default_summary_github_calls")]
+    pub max_github_calls_per_summary: u32,
+    /// 界面语言（如 `zh`、`en`），决定后端错误信息的语言
+    #[serde(default)]
+    pub ui_language: Option<String>,
+    /// 是否在总结末尾附加模型、模式与日期等来源信息
+    #[serde(default)]
+    pub summary_footer: bool,
+    /// 页脚模板，支持 `{model}`、`{mode}`、`{date}` 占位符（为空则使用默认模板）
+    #[serde(default)]
+    pub summary_footer_template: Option<String>,
+    /// 自定义总结提示词模板（为空则使用内置提示词）
+    ///
+    /// 支持 `{author}`、`{name}`、`{description}`、`{language}`、`{readme}`、`{tree}`、
+    /// `{context}`（深度模式下识别的技术栈与配置文件）和 `{instructions}`（篇幅要求）占位符，
+    /// 未抓取到的内容替换为空字符串。
+    #[serde(default)]
+    pub summary_prompt_template: Option<String>,
+    /// 总结的输出语言（如 `zh`、`en`、`ja`），不同语言的洞察分别缓存
+    #[serde(default = "default_summary_language")]
+    pub summary_language: String,
+    /// 批量生成总结时的最大并发数
+    #[serde(default = "default_max_concurrent_summaries")]
+    pub max_concurrent_summaries: usize,
+    /// 离线模式：拦截所有网络请求，只读取本地缓存与数据库
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// 趋势榜新鲜度评分的权重
+    #[serde(default)]
+    pub ranking_weights: RankingWeights,
+    /// 生成总结后自动收藏该仓库
+    #[serde(default)]
+    pub auto_favorite_on_summarize: bool,
+    /// 按名称与描述归类主题的关键词规则（按顺序匹配，先命中者优先）
+    #[serde(default = "default_topic_rules")]
+    pub topic_rules: Vec<TopicRule>,
+}
+
+/// 主题分类规则：名称或描述包含任一关键词（不区分大小写）即归入该分类
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicRule {
+    pub category: String,
+    pub keywords: Vec<String>,
+}
+
+impl TopicRule {
+    fn new(category: &str, keywords: &[&str]) -> Self {
+        Self {
+            category: category.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+}
+
+/// 内置的主题分类规则（英文关键词 + 常见中文描述用词）
+pub fn default_topic_rules() -> Vec<TopicRule> {
+    vec![
+        TopicRule::new("AI / LLM", &[
+            "ai", "llm", "gpt", "model", "inference", "agent", "rag", "learning", "llama",
+            "人工智能", "大模型", "语言模型", "智能体", "机器学习", "深度学习", "模型", "推理", "知识库",
+        ]),
+        TopicRule::new("Web / App", &[
+            "web", "react", "vue", "frontend", "backend", "nextjs", "api", "framework",
+            "前端", "后端", "网站", "网页", "框架", "接口", "小程序",
+        ]),
+        TopicRule::new("Tools / CLI", &[
+            "cli", "tool", "utility", "helper", "automation", "workflow",
+            "工具", "命令行", "自动化", "工作流", "脚本", "效率",
+        ]),
+        TopicRule::new("Systems / OS", &[
+            "system", "kernel", "driver", "hardware", "linux", "os", "memory", "cpu",
+            "操作系统", "内核", "驱动", "硬件", "嵌入式", "内存",
+        ]),
+        TopicRule::new("Mobile", &[
+            "ios", "android", "mobile", "flutter", "swift", "kotlin",
+            "移动端", "安卓", "手机", "鸿蒙",
+        ]),
+    ]
+}
+
+/// 趋势榜新鲜度评分的权重
+///
+/// 默认权重：今日新增星标 1.0、总星标 0.3（两者均取 ln(1+x)）、有描述 0.5、有语言 0.2
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingWeights {
+    pub stars_today: f64,
+    pub total_stars: f64,
+    pub description: f64,
+    pub language: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            stars_today: 1.0,
+            total_stars: 0.3,
+            description: 0.5,
+            language: 0.2,
+        }
+    }
+}
+
+/// 默认的总结输出语言（与内置提示词一致）
+pub const DEFAULT_SUMMARY_LANGUAGE: &str = "zh";
+
+/// 默认的总结页脚模板
+pub const DEFAULT_FOOTER_TEMPLATE: &str = "—Generated by {model} ({mode}) on {date}";
+
+fn default_true() -> bool { true }
+fn default_summary_github_calls() -> u32 { 10 }
+fn default_max_concurrent_summaries() -> usize { 3 }
+fn default_summary_language() -> String { DEFAULT_SUMMARY_LANGUAGE.to_string() }
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            extra_context_files: Vec::new(),
+            context_files_override: None,
+            audit_llm_requests: false,
+            github_host: None,
+            github_token: None,
+            strip_reasoning: true,
+            insight_cache_max_entries: None,
+            insight_cache_max_bytes: None,
+            insight_cache_max_age_days: None,
+            insight_ttl_days: None,
+            evict_favorite_insights: false,
+            cache_insights: true,
+            max_github_calls_per_summary: default_summary_github_calls(),
+            ui_language: None,
+            summary_footer: false,
+            summary_footer_template: None,
+            summary_prompt_template: None,
+            summary_language: default_summary_language(),
+            max_concurrent_summaries: default_max_concurrent_summaries(),
+            offline_mode: false,
+            ranking_weights: RankingWeights::default(),
+            auto_favorite_on_summarize: false,
+            topic_rules: default_topic_rules(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// 获取深度上下文候选文件列表（默认列表或用户替换的列表 + 用户追加，去重）
+    pub fn context_files(&self) -> Vec<String> {
+        let base: Vec<&str> = match &self.context_files_override {
+            Some(files) => files.iter().map(String::as_str).collect(),
+            None => DEFAULT_CONTEXT_FILES.to_vec(),
+        };
+        let mut files: Vec<String> = Vec::new();
+        for file in base.into_iter().chain(self.extra_context_files.iter().map(String::as_str)) {
+            let file = file.trim();
+            if !file.is_empty() && !files.iter().any(|f| f == file) {
+                files.push(file.to_string());
+            }
+        }
+        files
+    }
+
+    /// 当前生效的页脚模板
+    pub fn footer_template(&self) -> &str {
+        self.summary_footer_template.as_deref().unwrap_or(DEFAULT_FOOTER_TEMPLATE)
+    }
+
+    /// 更新设置
+    pub fn update(&mut self, updates: AppSettingsUpdate) {
+        if let Some(extra_context_files) = updates.extra_context_files {
+            self.extra_context_files = extra_context_files;
+        }
+        // 空列表表示恢复内置列表
+        if let Some(files) = updates.context_files_override {
+            self.context_files_override = Some(files).filter(|f| f.iter().any(|file| !file.trim().is_empty()));
+        }
+        if let Some(audit_llm_requests) = updates.audit_llm_requests {
+            self.audit_llm_requests = audit_llm_requests;
+        }
+        if let Some(github_host) = updates.github_host {
+            self.github_host = Some(github_host).filter(|h| !h.trim().is_empty());
+        }
+        // 空字符串表示清除令牌
+        if let Some(github_token) = updates.github_token {
+            self.github_token = Some(github_token.trim().to_string()).filter(|t| !t.is_empty());
+        }
+        if let Some(strip_reasoning) = updates.strip_reasoning {
+            self.strip_reasoning = strip_reasoning;
+        }
+        // 0 表示取消限制
+        if let Some(max_entries) = updates.insight_cache_max_entries {
+            self.insight_cache_max_entries = Some(max_entries).filter(|n| *n > 0);
+        }
+        if let Some(max_bytes) = updates.insight_cache_max_bytes {
+            self.insight_cache_max_bytes = Some(max_bytes).filter(|n| *n > 0);
+        }
+        if let Some(max_age_days) = updates.insight_cache_max_age_days {
+            self.insight_cache_max_age_days = Some(max_age_days).filter(|n| *n > 0);
+        }
+        if let Some(ttl_days) = updates.insight_ttl_days {
+            self.insight_ttl_days = Some(ttl_days).filter(|n| *n > 0);
+        }
+        if let Some(evict_favorite_insights) = updates.evict_favorite_insights {
+            self.evict_favorite_insights = evict_favorite_insights;
+        }
+        if let Some(cache_insights) = updates.cache_insights {
+            self.cache_insights = cache_insights;
+        }
+        if let Some(max_calls) = updates.max_github_calls_per_summary {
+            // 至少保留一次请求用于可访问性检查
+            self.max_github_calls_per_summary = max_calls.max(1);
+        }
+        if let Some(ui_language) = updates.ui_language {
+            self.ui_language = Some(ui_language).filter(|l| !l.trim().is_empty());
+        }
+        if let Some(summary_footer) = updates.summary_footer {
+            self.summary_footer = summary_footer;
+        }
+        if let Some(template) = updates.summary_footer_template {
+            self.summary_footer_template = Some(template).filter(|t| !t.trim().is_empty());
+        }
+        if let Some(template) = updates.summary_prompt_template {
+            self.summary_prompt_template = Some(template).filter(|t| !t.trim().is_empty());
+        }
+        // 空字符串表示恢复默认语言
+        if let Some(language) = updates.summary_language {
+            let language = language.trim().to_lowercase();
+            self.summary_language = if language.is_empty() { default_summary_language() } else { language };
+        }
+        if let Some(max_concurrent) = updates.max_concurrent_summaries {
+            self.max_concurrent_summaries = max_concurrent.max(1);
+        }
+        if let Some(offline_mode) = updates.offline_mode {
+            self.offline_mode = offline_mode;
+        }
+        if let Some(ranking_weights) = updates.ranking_weights {
+            self.ranking_weights = ranking_weights;
+        }
+        if let Some(auto_favorite) = updates.auto_favorite_on_summarize {
+            self.auto_favorite_on_summarize = auto_favorite;
+        }
+        // 丢弃没有分类名或关键词的规则，空列表表示恢复内置规则
+        if let Some(rules) = updates.topic_rules {
+            let rules: Vec<TopicRule> = rules
+                .into_iter()
+                .filter_map(|rule| {
+                    let category = rule.category.trim().to_string();
+                    let keywords: Vec<String> = rule.keywords.iter()
+                        .map(|k| k.trim().to_lowercase())
+                        .filter(|k| !k.is_empty())
+                        .collect();
+                    (!category.is_empty() && !keywords.is_empty()).then_some(TopicRule { category, keywords })
+                })
+                .collect();
+            self.topic_rules = if rules.is_empty() { default_topic_rules() } else { rules };
+        }
+    }
+}
+
+/// 应用设置更新结构（用于部分更新）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppSettingsUpdate {
+    pub extra_context_files: Option<Vec<String>>,
+    pub context_files_override: Option<Vec<String>>,
+    pub audit_llm_requests: Option<bool>,
+    pub github_host: Option<String>,
+    pub github_token: Option<String>,
+    pub strip_reasoning: Option<bool>,
+    pub insight_cache_max_entries: Option<usize>,
+    pub insight_cache_max_bytes: Option<u64>,
+    pub insight_cache_max_age_days: Option<u64>,
+    pub insight_ttl_days: Option<u64>,
+    pub evict_favorite_insights: Option<bool>,
+    pub cache_insights: Option<bool>,
+    pub max_github_calls_per_summary: Option<u32>,
+    pub ui_language: Option<String>,
+    pub summary_footer: Option<bool>,
+    pub summary_footer_template: Option<String>,
+    pub summary_prompt_template: Option<String>,
+    pub summary_language: Option<String>,
+    pub max_concurrent_summaries: Option<usize>,
+    pub offline_mode: Option<bool>,
+    pub ranking_weights: Option<RankingWeights>,
+    pub auto_favorite_on_summarize: Option<bool>,
+    pub topic_rules: Option<Vec<TopicRule>>,
 }
 
 impl AppConfig {
@@ -264,19 +884,6 @@ impl AppConfig {
             .collect()
     }
 
-    /// 更新缓存过期时间
-    pub fn update_cache_expiry(&mut self, hours: i64) {
-        self.cache_expires_at = Some(chrono::Utc::now() + chrono::Duration::hours(hours));
-    }
-
-    /// 检查缓存是否过期
-    pub fn is_cache_expired(&self) -> bool {
-        match self.cache_expires_at {
-            Some(expiry) => chrono::Utc::now() >= expiry,
-            None => true,
-        }
-    }
-
     /// 标记配置已更新
     fn updated(&mut self) {
         // 可以在这里添加持久化逻辑
@@ -309,4 +916,120 @@ impl ChatMessage {
     pub fn assistant(content: &str) -> Self {
         Self::new("assistant", content)
     }
-}
\ No newline at end of file
+}
+
+/// 提供给模型调用的工具（函数）定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDef {
+    pub name: String,
+    pub description: String,
+    /// 参数的 JSON Schema
+    pub parameters: serde_json::Value,
+}
+
+/// 模型返回的结构化工具调用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    /// 调用参数；模型输出不是合法 JSON 时保留原始字符串
+    pub arguments: serde_json::Value,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_format_warning() {
+        assert!(ModelProvider::OpenAI.key_format_warning("sk-proj-abc123").is_none());
+        assert!(ModelProvider::DeepSeek.key_format_warning("sk-abc123").is_none());
+        assert!(ModelProvider::Anthropic.key_format_warning("sk-ant-api03-abc").is_none());
+        assert!(ModelProvider::Google.key_format_warning("AIzaSyAbc").is_none());
+        assert!(ModelProvider::Custom("local".to_string()).key_format_warning("anything").is_none());
+        assert!(ModelProvider::AzureOpenAI.key_format_warning("0123456789abcdef").is_none());
+
+        let warning = ModelProvider::Anthropic.key_format_warning("sk-proj-abc123").unwrap();
+        assert!(warning.contains("OpenAI"));
+        assert!(ModelProvider::OpenAI.key_format_warning("sk-ant-api03-abc").is_some());
+        assert!(ModelProvider::Google.key_format_warning("not-a-key").is_some());
+    }
+
+    #[test]
+    fn test_context_files() {
+        let mut settings = AppSettings {
+            extra_context_files: vec![" melos.yaml ".to_string(), "Cargo.toml".to_string()],
+            ..Default::default()
+        };
+        let files = settings.context_files();
+        assert_eq!(files.len(), DEFAULT_CONTEXT_FILES.len() + 1);
+        assert!(files.contains(&"pubspec.yaml".to_string()));
+        assert_eq!(files.last().map(String::as_str), Some("melos.yaml"));
+
+        settings.update(AppSettingsUpdate {
+            context_files_override: Some(vec!["pubspec.yaml".to_string(), "".to_string()]),
+            ..Default::default()
+        });
+        assert_eq!(settings.context_files(), vec!["pubspec.yaml", "melos.yaml", "Cargo.toml"]);
+
+        settings.update(AppSettingsUpdate {
+            context_files_override: Some(Vec::new()),
+            ..Default::default()
+        });
+        assert!(settings.context_files_override.is_none());
+    }
+
+    #[test]
+    fn test_validate_model_config() {
+        let config = |provider: ModelProvider, base_url: &str, key: &str| {
+            ModelConfig::new("test".to_string(), provider, base_url.to_string(), key.to_string(), "model".to_string())
+        };
+        assert!(config(ModelProvider::OpenAI, "https://api.openai.com/v1", "sk-abc").validate().is_ok());
+        assert!(config(ModelProvider::OpenAI, "", "sk-abc").validate().is_ok());
+        assert!(config(ModelProvider::OpenAI, "https://api.openai.com/v1", " ").validate().unwrap_err().contains("API Key"));
+        assert!(config(ModelProvider::AzureOpenAI, "", "key").validate().unwrap_err().contains("基础 URL"));
+        assert!(config(ModelProvider::Custom("local".to_string()), "localhost:11434", "key").validate().is_err());
+        assert!(config(ModelProvider::Custom("local".to_string()), "http://localhost:11434/v1", "key").validate().is_ok());
+        assert!(config(ModelProvider::Custom("local".to_string()), "http://localhost:11434/v1", "").validate().is_ok());
+
+        let mut with_proxy = config(ModelProvider::OpenAI, "", "sk-abc");
+        with_proxy.proxy_url = Some("not a url".to_string());
+        assert!(with_proxy.validate().unwrap_err().contains("代理"));
+        with_proxy.proxy_url = Some("socks5://127.0.0.1:1080".to_string());
+        assert!(with_proxy.validate().unwrap_err().contains("socks5"));
+        with_proxy.proxy_url = Some("http://127.0.0.1:7890".to_string());
+        assert!(with_proxy.validate().is_ok());
+    }
+
+    #[test]
+    fn test_model_cache_entry_expiry() {
+        let model = serde_json::json!({
+            "id": "gpt-4o", "name": "gpt-4o", "provider": "OpenAI",
+            "context_length": null, "max_tokens": null,
+            "supports_streaming": true, "supports_function_calling": true
+        });
+
+        // 旧格式：直接保存模型列表，没有单独的过期时间
+        let legacy: HashMap<String, ModelCacheEntry> =
+            serde_json::from_value(serde_json::json!({ "openai": [model.clone()] })).unwrap();
+        assert_eq!(legacy["openai"].models.len(), 1);
+        assert!(legacy["openai"].is_expired());
+
+        let fresh = ModelCacheEntry::new(vec![], 24);
+        assert!(!fresh.is_expired());
+        let round_trip: ModelCacheEntry = serde_json::from_value(serde_json::to_value(&fresh).unwrap()).unwrap();
+        assert_eq!(round_trip.expires_at, fresh.expires_at);
+        assert!(ModelCacheEntry::new(vec![], -1).is_expired());
+    }
+
+    #[test]
+    fn test_duplicate_model_config() {
+        let mut original = ModelConfig::default_openai("sk-abc".to_string());
+        original.enabled = false;
+        let copy = original.duplicate();
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.name, "OpenAI (默认) (副本)");
+        assert_eq!(copy.api_key, original.api_key);
+        assert_eq!(copy.api_base_url, original.api_base_url);
+        assert!(copy.enabled);
+    }
+}
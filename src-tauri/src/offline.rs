@@ -0,0 +1,40 @@
+//! 离线模式
+//!
+//! 开启后所有出站请求（趋势榜、搜索、大模型、GitHub）都会在命令入口被拦截，
+//! 只读取本地缓存与数据库，需要联网的操作返回明确的错误。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::i18n::{tr, ErrorCode};
+
+static OFFLINE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 切换离线模式（启动时与更新设置时调用）
+pub fn set_offline_mode(enabled: bool) {
+    OFFLINE_MODE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE_MODE.load(Ordering::Relaxed)
+}
+
+/// 需要联网的操作在发出请求前调用，离线模式下直接返回错误
+pub fn ensure_online() -> Result<(), String> {
+    if is_offline() {
+        Err(tr(ErrorCode::OfflineMode, &[]))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_online() {
+        set_offline_mode(true);
+        assert!(ensure_online().is_err());
+        set_offline_mode(false);
+        assert!(ensure_online().is_ok());
+    }
+}
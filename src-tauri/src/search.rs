@@ -1,13 +1,108 @@
-use serde::Deserialize;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
-use crate::trending::TrendingRepo;
-use crate::models::ChatMessage;
-use crate::llm::LLMFactory;
+use crate::trending::{TrendingRepo, TrendingSource};
+use crate::models::{ChatMessage, ModelConfig};
+use crate::llm::{LLMFactory, LLMProvider};
 use crate::config::commands::ConfigManagerState;
+use crate::error::{CommandError, ErrorKind};
+use crate::github::GitHubHost;
+use crate::i18n::{tr, ErrorCode};
 
 #[derive(Debug, Deserialize)]
 struct GithubSearchResponse {
+    /// 匹配的仓库总数（搜索 API 最多只能翻到前 1000 条）
+    #[serde(default)]
+    total_count: u64,
     items: Vec<GithubRepoItem>,
+    /// GitHub 内部超时时只返回部分结果
+    #[serde(default)]
+    incomplete_results: bool,
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResults {
+    pub items: Vec<TrendingRepo>,
+    /// GitHub 返回的结果可能不完整（重试一次后仍不完整），前端应提示用户缩小查询范围
+    pub incomplete_results: bool,
+    /// 匹配的仓库总数，配合 `page`/`per_page` 渲染分页
+    pub total_count: u64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// 搜索 API 单页最多返回的条数
+const MAX_PER_PAGE: u32 = 100;
+/// 默认每页条数
+const DEFAULT_PER_PAGE: u32 = 20;
+
+/// 搜索 API 支持的排序字段
+const SORT_FIELDS: &[&str] = &["stars", "forks", "updated", "help-wanted-issues"];
+/// 搜索 API 支持的排序方向
+const SORT_ORDERS: &[&str] = &["desc", "asc"];
+
+/// 搜索分页与排序参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub page: u32,
+    pub per_page: u32,
+    pub sort: &'static str,
+    pub order: &'static str,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self { page: 1, per_page: DEFAULT_PER_PAGE, sort: SORT_FIELDS[0], order: SORT_ORDERS[0] }
+    }
+}
+
+impl SearchOptions {
+    /// 按命令参数构造，页码至少为 1，每页条数限制在 1~100
+    pub fn new(page: Option<u32>, per_page: Option<u32>) -> Self {
+        Self {
+            page: page.unwrap_or(1).max(1),
+            per_page: per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+            ..Self::default()
+        }
+    }
+
+    /// 按 `search_github` 的可选参数构造，排序值不在白名单内时返回错误
+    pub fn from_params(params: &SearchParams) -> Result<Self, String> {
+        Self::new(params.page, params.per_page).with_sort(params.sort.as_deref(), params.order.as_deref())
+    }
+
+    /// 设置排序字段与方向，未提供时保持按 star 数降序，不在白名单内的值返回错误
+    pub fn with_sort(self, sort: Option<&str>, order: Option<&str>) -> Result<Self, String> {
+        Ok(Self {
+            sort: match sort {
+                Some(sort) => allowed_value(sort, SORT_FIELDS, "排序字段")?,
+                None => self.sort,
+            },
+            order: match order {
+                Some(order) => allowed_value(order, SORT_ORDERS, "排序方向")?,
+                None => self.order,
+            },
+            ..self
+        })
+    }
+}
+
+/// `search_github` 的分页与排序参数，均可省略
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchParams {
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+}
+
+fn allowed_value(value: &str, allowed: &[&'static str], label: &str) -> Result<&'static str, String> {
+    let value = value.trim().to_lowercase();
+    allowed.iter().copied().find(|v| *v == value).ok_or_else(|| {
+        format!("无效的{}: {}，可选值: {}", label, value, allowed.join(", "))
+    })
 }
 
 #[derive(Debug, Deserialize)]
@@ -21,6 +116,8 @@ struct GithubRepoItem {
     topics: Option<Vec<String>>,
     pushed_at: Option<String>,
     license: Option<GithubLicense>,
+    #[serde(default)]
+    created_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,8 +135,29 @@ pub async fn ai_rewrite_query(
     query: String,
     api_key: Option<String>,
     model_config_id: Option<String>,
+    bypass_cache: Option<bool>,
     config_manager: tauri::State<'_, ConfigManagerState>,
-) -> Result<String, String> {
+    db: tauri::State<'_, crate::db::DbState>,
+) -> Result<String, CommandError> {
+    crate::offline::ensure_online().map_err(CommandError::network)?;
+    let cache = RewriteCache { db: db.inner(), bypass: bypass_cache.unwrap_or(false) };
+
+    // 确定使用哪种模式
+    let result = if let Some(config_id) = model_config_id {
+        // 新模式：使用配置管理器
+        rewrite_with_config(config_id, &query, &config_manager, cache).await
+    } else if let Some(api_key) = api_key {
+        // 旧模式：使用直接提供的 API Key
+        rewrite_with_api_key(api_key, &query, &config_manager, cache).await
+    } else {
+        return Err(CommandError::config(tr(ErrorCode::MissingCredentials, &[])));
+    };
+
+    result
+}
+
+/// 构造查询改写的提示词
+fn build_rewrite_messages(query: &str) -> Vec<ChatMessage> {
     let prompt = format!(
         "You are a GitHub search query optimizer. Convert the following natural language intent \
         into a precise GitHub search query string using qualifiers like language:, topic:, stars:, pushed:, etc.\n\
@@ -58,150 +176,418 @@ pub async fn ai_rewrite_query(
         query
     );
 
-    let messages = vec![ChatMessage::user(&prompt)];
+    vec![ChatMessage::user(&prompt)]
+}
 
-    // 确定使用哪种模式
-    let result = if let Some(config_id) = model_config_id {
-        // 新模式：使用配置管理器
-        rewrite_with_config(config_id, messages, &config_manager).await
-    } else if let Some(api_key) = api_key {
-        // 旧模式：使用直接提供的 API Key
-        rewrite_with_api_key(api_key, messages).await
-    } else {
-        return Err("必须提供 API Key 或模型配置 ID".to_string());
-    };
+/// 查询改写的默认采样温度（未在模型配置中设置时），保证改写结果稳定
+const QUERY_REWRITE_TEMPERATURE: f32 = 0.0;
 
-    result
+/// 查询改写的超时时间，超时后前端回退到原始查询，避免慢模型卡住搜索框
+const QUERY_REWRITE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 查询改写的持久化缓存（`query_rewrites` 表），`bypass` 时跳过读取但仍写入新结果
+#[derive(Clone, Copy)]
+struct RewriteCache<'a> {
+    db: &'a crate::db::DbState,
+    bypass: bool,
+}
+
+/// 规范化用户输入作为缓存键：合并空白并忽略大小写
+fn normalize_rewrite_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// 缓存按服务地址与模型区分：不同提供商的同名模型改写结果不共用（临时 API Key 配置也能命中）
+fn rewrite_cache_model(config: &ModelConfig) -> String {
+    format!("{}|{}", config.api_base_url.trim().trim_end_matches('/'), config.default_model)
+}
+
+/// 调用模型改写查询：同一意图与模型直接返回缓存的结果，超时返回错误
+async fn complete_rewrite(
+    provider: &dyn LLMProvider,
+    config: &ModelConfig,
+    query: &str,
+    cache: RewriteCache<'_>,
+) -> Result<String, CommandError> {
+    let key = normalize_rewrite_query(query);
+    let model = rewrite_cache_model(config);
+    if !cache.bypass {
+        match crate::db::get_query_rewrite(cache.db, &key, &model).await {
+            Ok(Some(cached)) => return Ok(cached),
+            Ok(None) => {}
+            Err(e) => log::warn!("读取查询改写缓存失败: {}", e),
+        }
+    }
+
+    // 执行聊天补全（非流式）
+    let messages = build_rewrite_messages(query);
+    let content = tokio::time::timeout(QUERY_REWRITE_TIMEOUT, provider.complete_text(messages, &config.default_model))
+        .await
+        .map_err(|_| CommandError::network("AI 改写超时，使用原始查询"))??;
+    let rewritten = content.trim().to_string();
+
+    if !rewritten.is_empty() {
+        if let Err(e) = crate::db::save_query_rewrite(cache.db, &key, &model, &rewritten).await {
+            log::warn!("保存查询改写缓存失败: {}", e);
+        }
+    }
+    Ok(rewritten)
 }
 
 /// 使用配置管理器中的模型配置进行查询改写
 async fn rewrite_with_config(
     config_id: String,
-    messages: Vec<ChatMessage>,
+    query: &str,
     config_manager: &tauri::State<'_, ConfigManagerState>,
-) -> Result<String, String> {
+    cache: RewriteCache<'_>,
+) -> Result<String, CommandError> {
     let manager_lock = config_manager.lock().await;
 
     // 获取模型配置
     let configs = manager_lock.get_all_model_configs().await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| CommandError::config(e.to_string()))?;
 
-    let config = configs.iter()
+    let mut config = configs.into_iter()
         .find(|c| c.id == config_id)
-        .ok_or_else(|| format!("找不到模型配置: {}", config_id))?;
+        .ok_or_else(|| CommandError::not_found(tr(ErrorCode::ModelConfigNotFound, &[&config_id])))?;
+    config.temperature.get_or_insert(QUERY_REWRITE_TEMPERATURE);
+    let config = &config;
 
     // 创建 LLM 提供商
-    let provider = LLMFactory::create_provider(config)
-        .map_err(|e| e.to_string())?;
+    let provider = LLMFactory::create_provider(config)?;
+    let provider = crate::audit::wrap_provider(provider, config, &manager_lock).await;
+    drop(manager_lock);
 
-    // 执行聊天补全（非流式）
-    let response = provider.chat_completion(messages, &config.default_model, false)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    match response {
-        crate::llm::LLMResponse::Completion { content, .. } => {
-            Ok(content.trim().to_string())
-        }
-        crate::llm::LLMResponse::Stream { .. } => {
-            Err("预期非流式响应，但收到流式响应".to_string())
-        }
-    }
+    complete_rewrite(provider.as_ref(), config, query, cache).await
 }
 
 /// 使用直接提供的 API Key 进行查询改写（向后兼容）
 async fn rewrite_with_api_key(
     api_key: String,
-    messages: Vec<ChatMessage>,
-) -> Result<String, String> {
+    query: &str,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+    cache: RewriteCache<'_>,
+) -> Result<String, CommandError> {
     if api_key.is_empty() {
-        return Err("API Key 未配置，请在设置中填写".to_string());
+        return Err(CommandError::new(ErrorKind::Auth, "API Key 未配置，请在设置中填写"));
     }
 
     // 创建临时的 OpenAI 配置
-    use crate::models::{ModelConfig, ModelProvider};
+    use crate::models::ModelProvider;
 
-    let config = ModelConfig::new(
+    let mut config = ModelConfig::new(
         "临时 OpenAI 配置".to_string(),
         ModelProvider::OpenAI,
         ModelProvider::OpenAI.default_api_base_url(),
         api_key,
         ModelProvider::OpenAI.default_model_name(),
     );
+    config.temperature = Some(QUERY_REWRITE_TEMPERATURE);
 
     // 创建 LLM 提供商
-    let provider = LLMFactory::create_provider(&config)
-        .map_err(|e| e.to_string())?;
+    let provider = LLMFactory::create_provider(&config)?;
+    let provider = crate::audit::wrap_provider(provider, &config, &*config_manager.lock().await).await;
 
-    // 执行聊天补全（非流式）
-    let response = provider.chat_completion(messages, &config.default_model, false)
+    complete_rewrite(provider.as_ref(), &config, query, cache).await
+}
+
+/// 直接搜索 GitHub 仓库（不经过 AI 改写）
+///
+/// `options.page`/`per_page` 默认为第 1 页、每页 20 条；`sort` 可选 stars、forks、updated、help-wanted-issues，
+/// `order` 可选 asc、desc，默认按 star 数降序
+#[tauri::command]
+pub async fn search_github(
+    query: String,
+    exclude_hidden: Option<bool>,
+    options: Option<SearchParams>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    db: tauri::State<'_, crate::db::DbState>,
+) -> Result<SearchResults, String> {
+    let options = SearchOptions::from_params(&options.unwrap_or_default())?;
+    let results = run_search(&query, options, exclude_hidden.unwrap_or(false), &config_manager, &db).await?;
+    if let Err(e) = crate::db::save_search(db.inner(), &query).await {
+        log::warn!("记录搜索历史失败: {}", e);
+    }
+    Ok(results)
+}
+
+/// 按当前设置的 GitHub 主机执行搜索，并按需过滤已隐藏的仓库
+async fn run_search(
+    query: &str,
+    options: SearchOptions,
+    exclude_hidden: bool,
+    config_manager: &tauri::State<'_, ConfigManagerState>,
+    db: &tauri::State<'_, crate::db::DbState>,
+) -> Result<SearchResults, String> {
+    crate::offline::ensure_online()?;
+    let settings = config_manager.lock().await
+        .get_app_settings()
         .await
-        .map_err(|e| e.to_string())?;
+        .unwrap_or_default();
+    let mut results = search_github_repositories(&GitHubHost::from_settings(&settings), query, options).await?;
+    if exclude_hidden {
+        results.items = crate::db::filter_hidden(db.inner(), results.items).await?;
+    }
+    Ok(results)
+}
 
-    match response {
-        crate::llm::LLMResponse::Completion { content, .. } => {
-            Ok(content.trim().to_string())
+/// AI 搜索流水线的阶段事件
+#[derive(Clone, Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum SearchPipelineEvent {
+    RewriteStarted,
+    RewriteDone(String),
+    SearchStarted,
+    Results(SearchResults),
+    Error(String),
+}
+
+/// AI 改写 + GitHub 搜索的组合命令，通过事件通道报告每个阶段的进度
+#[tauri::command]
+pub async fn ai_search(
+    query: String,
+    model_config_id: String,
+    exclude_hidden: Option<bool>,
+    on_event: Channel<SearchPipelineEvent>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+    db: tauri::State<'_, crate::db::DbState>,
+) -> Result<(), String> {
+    crate::offline::ensure_online()?;
+    let _ = on_event.send(SearchPipelineEvent::RewriteStarted);
+    let rewritten = match rewrite_with_config(model_config_id, &query, &config_manager, RewriteCache { db: db.inner(), bypass: false })
+        .await
+        .map_err(String::from)
+    {
+        Ok(rewritten) => rewritten,
+        Err(e) => {
+            let _ = on_event.send(SearchPipelineEvent::Error(e.clone()));
+            return Err(e);
         }
-        crate::llm::LLMResponse::Stream { .. } => {
-            Err("预期非流式响应，但收到流式响应".to_string())
+    };
+    let _ = on_event.send(SearchPipelineEvent::RewriteDone(rewritten.clone()));
+
+    let _ = on_event.send(SearchPipelineEvent::SearchStarted);
+    match run_search(&rewritten, SearchOptions::default(), exclude_hidden.unwrap_or(false), &config_manager, &db).await {
+        Ok(results) => {
+            let _ = on_event.send(SearchPipelineEvent::Results(results));
+            if let Err(e) = crate::db::save_search(db.inner(), &query).await {
+                log::warn!("记录搜索历史失败: {}", e);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            let _ = on_event.send(SearchPipelineEvent::Error(e.clone()));
+            Err(e)
         }
     }
 }
 
-/// 直接搜索 GitHub 仓库（不经过 AI 改写）
-#[tauri::command]
-pub async fn search_github(query: String) -> Result<Vec<TrendingRepo>, String> {
-    search_github_repositories(&query).await
+/// 搜索 GitHub 仓库；结果不完整时自动重试一次，仍不完整则保留条目更多的一次并标记
+async fn search_github_repositories(host: &GitHubHost, query: &str, options: SearchOptions) -> Result<SearchResults, String> {
+    let first = request_search(host, query, options).await?;
+    if !first.incomplete_results {
+        return Ok(into_search_results(first, options));
+    }
+    log::warn!("GitHub 搜索结果不完整，重试一次: {}", query);
+    let best = match request_search(host, query, options).await {
+        Ok(retry) if !retry.incomplete_results || retry.items.len() > first.items.len() => retry,
+        _ => first,
+    };
+    Ok(into_search_results(best, options))
 }
 
-async fn search_github_repositories(query: &str) -> Result<Vec<TrendingRepo>, String> {
-    let client = reqwest::Client::new();
+async fn request_search(host: &GitHubHost, query: &str, options: SearchOptions) -> Result<GithubSearchResponse, String> {
+    let client = crate::github::http_client();
     let mut headers = HeaderMap::new();
     headers.insert(USER_AGENT, HeaderValue::from_static("github-capture-app"));
 
     let url = format!(
-        "https://api.github.com/search/repositories?q={}&sort=stars&order=desc&per_page=20",
-        urlencoding::encode(query)
+        "{}/search/repositories?q={}&sort={}&order={}&per_page={}&page={}",
+        host.api_base(),
+        urlencoding::encode(query),
+        options.sort,
+        options.order,
+        options.per_page,
+        options.page
     );
 
-    let res = client.get(&url)
-        .headers(headers)
-        .send()
+    let res = crate::github::send_with_retry(host.authorize(client.get(&url).headers(headers)))
         .await
-        .map_err(|e| format!("GitHub API 请求失败: {}", e))?;
+        .map_err(|e| tr(ErrorCode::GitHubRequestFailed, &[&e]))?;
 
     if !res.status().is_success() {
-        return Err(format!("GitHub API 错误: {}", res.status()));
-    }
-
-    let search_res: GithubSearchResponse = res.json().await.map_err(|e| format!("解析失败: {}", e))?;
-
-    let repos = search_res.items.into_iter().map(|item| {
-        let parts: Vec<&str> = item.full_name.split('/').collect();
-        TrendingRepo {
-            author: parts.get(0).unwrap_or(&"").to_string(),
-            name: parts.get(1).unwrap_or(&"").to_string(),
-            description: item.description.unwrap_or_default(),
-            language: item.language.unwrap_or_else(|| "Unknown".to_string()),
-            stars: format_number(item.stargazers_count),
-            forks: format_number(item.forks_count),
-            stars_today: "".to_string(),
-            url: item.html_url,
-            topic: "Search Result".to_string(),
-            built_by: Vec::new(),
-            topics: item.topics.unwrap_or_default(),
-            pushed_at: item.pushed_at.unwrap_or_default(),
-            license: item.license.map(|l| l.name).unwrap_or_else(|| "None".to_string()),
-        }
+        return Err(crate::github::status_error(&res));
+    }
+
+    res.json().await.map_err(|e| tr(ErrorCode::ParseFailed, &[&e]))
+}
+
+fn into_search_results(search_res: GithubSearchResponse, options: SearchOptions) -> SearchResults {
+    let items = search_res.items.into_iter().map(into_trending_repo).collect();
+    SearchResults {
+        items,
+        incomplete_results: search_res.incomplete_results,
+        total_count: search_res.total_count,
+        page: options.page,
+        per_page: options.per_page,
+    }
+}
+
+fn into_trending_repo(item: GithubRepoItem) -> TrendingRepo {
+    let parts: Vec<&str> = item.full_name.split('/').collect();
+    TrendingRepo {
+        author: parts.get(0).unwrap_or(&"").to_string(),
+        name: parts.get(1).unwrap_or(&"").to_string(),
+        description: item.description.unwrap_or_default(),
+        language: item.language.unwrap_or_else(|| "Unknown".to_string()),
+        stars: format_number(item.stargazers_count),
+        forks: format_number(item.forks_count),
+        stars_today: "".to_string(),
+        stars_count: Some(item.stargazers_count),
+        forks_count: Some(item.forks_count),
+        url: item.html_url,
+        topic: crate::trending::SEARCH_RESULT_TOPIC.to_string(),
+        built_by: Vec::new(),
+        topics: item.topics.unwrap_or_default(),
+        pushed_at: item.pushed_at.unwrap_or_default(),
+        license: item.license.map(|l| l.name).unwrap_or_else(|| "None".to_string()),
+        tagline: None,
+        note: None,
+        freshness_score: 0.0,
+        source: TrendingSource::GitHub,
+    }
+}
+
+/// 用搜索 API 近似趋势榜：取时间窗口内新建的仓库，按平均每日星标数排序
+///
+/// 窗口按 `since` 放宽（daily 7 天、weekly 30 天、monthly 90 天），避免新仓库星标过少。
+pub(crate) async fn fetch_trending_via_search(
+    host: &GitHubHost,
+    language: Option<&str>,
+    since: &str,
+) -> Result<Vec<TrendingRepo>, String> {
+    crate::offline::ensure_online()?;
+    let window_days = match since {
+        "weekly" => 30,
+        "monthly" => 90,
+        _ => 7,
+    };
+    let now = chrono::Utc::now();
+    let mut query = format!("created:>{}", (now - chrono::Duration::days(window_days)).format("%Y-%m-%d"));
+    if let Some(language) = language.filter(|l| !l.trim().is_empty()) {
+        query.push_str(&format!(" language:{}", language));
+    }
+
+    let response = request_search(host, &query, SearchOptions::default()).await?;
+    let mut ranked: Vec<(f64, TrendingRepo)> = response.items.into_iter().map(|item| {
+        let velocity = stars_per_day(item.stargazers_count, item.created_at.as_deref(), now);
+        let mut repo = into_trending_repo(item);
+        repo.stars_today = format!("{} stars/day", velocity.round() as u64);
+        repo.topic = crate::trending::get_topic(&repo.name, &repo.description, &repo.topics);
+        repo.source = TrendingSource::SearchApi;
+        (velocity, repo)
     }).collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(ranked.into_iter().map(|(_, repo)| repo).collect())
+}
 
-    Ok(repos)
+/// 自创建以来平均每天获得的星标数（不足一天按一天计）
+fn stars_per_day(stars: u64, created_at: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> f64 {
+    let age_days = created_at
+        .and_then(|c| chrono::DateTime::parse_from_rfc3339(c).ok())
+        .map(|created| (now - created.with_timezone(&chrono::Utc)).num_hours() as f64 / 24.0)
+        .unwrap_or(1.0)
+        .max(1.0);
+    stars as f64 / age_days
 }
 
-fn format_number(num: u64) -> String {
+pub(crate) fn format_number(num: u64) -> String {
     if num >= 1000 {
         format!("{:.1}k", num as f64 / 1000.0)
     } else {
         num.to_string()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_rewrite_query() {
+        assert_eq!(normalize_rewrite_query("  适合初学者的  Rust AI 项目 "), "适合初学者的 rust ai 项目");
+        assert_eq!(normalize_rewrite_query("Golang\tWeb\nFramework"), "golang web framework");
+    }
+
+    #[test]
+    fn test_rewrite_cache_model() {
+        let openai = ModelConfig::new(
+            "OpenAI".to_string(),
+            crate::models::ModelProvider::OpenAI,
+            "https://api.openai.com/v1/".to_string(),
+            "sk-abc".to_string(),
+            "gpt-4o-mini".to_string(),
+        );
+        let mut local = openai.clone();
+        local.api_base_url = "http://localhost:11434/v1".to_string();
+        assert_eq!(rewrite_cache_model(&openai), "https://api.openai.com/v1|gpt-4o-mini");
+        assert_ne!(rewrite_cache_model(&openai), rewrite_cache_model(&local));
+    }
+
+    #[test]
+    fn test_incomplete_results_flag() {
+        let json = r#"{
+            "incomplete_results": true,
+            "items": [{
+                "full_name": "owner/repo",
+                "description": null,
+                "stargazers_count": 1500,
+                "forks_count": 12,
+                "language": null,
+                "html_url": "https://github.com/owner/repo"
+            }]
+        }"#;
+        let results = into_search_results(serde_json::from_str(json).unwrap(), SearchOptions::default());
+        assert!(results.incomplete_results);
+        assert_eq!(results.items[0].name, "repo");
+        assert_eq!(results.items[0].stars, "1.5k");
+
+        let complete: GithubSearchResponse = serde_json::from_str(r#"{"items": []}"#).unwrap();
+        assert!(!complete.incomplete_results);
+    }
+
+    #[test]
+    fn test_search_page() {
+        assert_eq!(SearchOptions::new(None, None), SearchOptions::default());
+        let clamped = SearchOptions::new(Some(0), Some(500));
+        assert_eq!((clamped.page, clamped.per_page), (1, 100));
+        let clamped = SearchOptions::new(Some(3), Some(0));
+        assert_eq!((clamped.page, clamped.per_page), (3, 1));
+
+        let json = r#"{"total_count": 4321, "items": []}"#;
+        let results = into_search_results(serde_json::from_str(json).unwrap(), SearchOptions::new(Some(2), Some(50)));
+        assert_eq!((results.total_count, results.page, results.per_page), (4321, 2, 50));
+    }
+
+    #[test]
+    fn test_search_sort() {
+        let defaults = SearchOptions::default().with_sort(None, None).unwrap();
+        assert_eq!((defaults.sort, defaults.order), ("stars", "desc"));
+        let updated = SearchOptions::default().with_sort(Some("Updated"), Some("asc")).unwrap();
+        assert_eq!((updated.sort, updated.order), ("updated", "asc"));
+        assert!(SearchOptions::default().with_sort(Some("watchers"), None).is_err());
+
+        let params: SearchParams = serde_json::from_str(r#"{"page": 2, "sort": "forks"}"#).unwrap();
+        let options = SearchOptions::from_params(&params).unwrap();
+        assert_eq!((options.page, options.per_page, options.sort, options.order), (2, DEFAULT_PER_PAGE, "forks", "desc"));
+        assert!(SearchOptions::default().with_sort(None, Some("up")).is_err());
+    }
+
+    #[test]
+    fn test_stars_per_day() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-03-11T00:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        assert_eq!(stars_per_day(1000, Some("2024-03-01T00:00:00Z"), now), 100.0);
+        // 不足一天按一天计，缺少创建时间时同样按一天计
+        assert_eq!(stars_per_day(50, Some("2024-03-10T20:00:00Z"), now), 50.0);
+        assert_eq!(stars_per_day(50, None, now), 50.0);
+    }
+}
@@ -0,0 +1,92 @@
+//! Token 估算
+//!
+//! 各厂商的分词器不同，这里不引入具体的分词表，而是按字符类别偏保守地估算：
+//! ASCII 文本（英文、代码）约 3 个字符一个 token，中日韩等非 ASCII 字符每个按 1 个 token 计。
+//! 估算值通常略高于实际值，用于在发送前判断提示词是否会超出模型的上下文窗口。
+
+/// 估算时以 1/3 token 为单位计数
+const UNITS_PER_TOKEN: usize = 3;
+
+/// 单个字符占用的估算单位
+fn char_units(c: char) -> usize {
+    if c.is_ascii() { 1 } else { UNITS_PER_TOKEN }
+}
+
+/// 估算文本的 token 数
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().map(char_units).sum::<usize>().div_ceil(UNITS_PER_TOKEN)
+}
+
+/// 截断文本使估算的 token 数不超过 `max_tokens`，返回截断后的切片
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> &str {
+    let max_units = max_tokens * UNITS_PER_TOKEN;
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        units += char_units(c);
+        if units > max_units {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+/// 按顺序裁剪各段内容，使总 token 数不超过 `max_tokens`
+///
+/// 排在前面的段落先被裁剪（先截断，不够再清空），返回每段被裁掉的 token 数。
+pub fn fit_sections(sections: &mut [&mut String], max_tokens: usize) -> Vec<usize> {
+    let mut total: usize = sections.iter().map(|s| estimate_tokens(s)).sum();
+    let mut trimmed = vec![0; sections.len()];
+    for (section, trimmed) in sections.iter_mut().zip(trimmed.iter_mut()) {
+        if total <= max_tokens {
+            break;
+        }
+        let tokens = estimate_tokens(section);
+        let keep = tokens.saturating_sub(total - max_tokens);
+        let kept = truncate_to_tokens(section, keep).len();
+        section.truncate(kept);
+        let remaining = estimate_tokens(section);
+        *trimmed = tokens - remaining;
+        total -= *trimmed;
+    }
+    trimmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("fn main() {}"), 4);
+        assert_eq!(estimate_tokens("核心架构"), 4);
+        assert_eq!(estimate_tokens("Rust 实现"), 4);
+    }
+
+    #[test]
+    fn test_truncate_to_tokens() {
+        assert_eq!(truncate_to_tokens("abcdefgh", 2), "abcdef");
+        assert_eq!(truncate_to_tokens("核心架构", 2), "核心");
+        assert_eq!(truncate_to_tokens("short", 10), "short");
+        assert_eq!(truncate_to_tokens("abc", 0), "");
+    }
+
+    #[test]
+    fn test_fit_sections() {
+        let mut tree = "a".repeat(30);
+        let mut config = "b".repeat(30);
+        let mut readme = "c".repeat(30);
+
+        // 总计 30 tokens，上限 25：只截断第一段
+        let trimmed = fit_sections(&mut [&mut tree, &mut config, &mut readme], 25);
+        assert_eq!(trimmed, vec![5, 0, 0]);
+        assert_eq!(tree.len(), 15);
+
+        // 上限 12：第一段清空后继续截断第二段，README 保持不变
+        let trimmed = fit_sections(&mut [&mut tree, &mut config, &mut readme], 12);
+        assert_eq!(trimmed, vec![5, 8, 0]);
+        assert!(tree.is_empty());
+        assert_eq!(config.len(), 6);
+        assert_eq!(readme.len(), 30);
+    }
+}
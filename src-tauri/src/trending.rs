@@ -1,5 +1,36 @@
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+use crate::config::commands::ConfigManagerState;
+use crate::models::{RankingWeights, TopicRule};
+
+/// 趋势榜请求超时时间，避免挂起的连接一直阻塞
+const TRENDING_TIMEOUT: Duration = Duration::from_secs(20);
+/// 趋势榜请求遇到暂时性错误时的最大重试次数
+const MAX_TRENDING_RETRIES: u32 = 3;
+/// 首次重试前的等待时间，之后每次翻倍
+const TRENDING_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 趋势榜支持的 README 语言代码（ISO 639-1）
+const SPOKEN_LANGUAGE_CODES: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az",
+    "ba", "be", "bg", "bh", "bi", "bm", "bn", "bo", "br", "bs",
+    "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy",
+    "da", "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu",
+    "fa", "ff", "fi", "fj", "fo", "fr", "fy", "ga", "gd", "gl", "gn", "gu", "gv",
+    "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv",
+    "ka", "kg", "ki", "kj", "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky",
+    "la", "lb", "lg", "li", "ln", "lo", "lt", "lu", "lv",
+    "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my",
+    "na", "nb", "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny",
+    "oc", "oj", "om", "or", "os", "pa", "pi", "pl", "ps", "pt", "qu",
+    "rm", "rn", "ro", "ru", "rw",
+    "sa", "sc", "sd", "se", "sg", "si", "sk", "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw",
+    "ta", "te", "tg", "th", "ti", "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty",
+    "ug", "uk", "ur", "uz", "ve", "vi", "vo", "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
 
 #[derive(Debug, Serialize, Deserialize, Clone, sqlx::FromRow)]
 pub struct TrendingRepo {
@@ -10,6 +41,14 @@ pub struct TrendingRepo {
     pub stars: String,
     pub forks: String,
     pub stars_today: String,
+    /// 星标数（`stars` 为展示用的格式化文本，排序与筛选使用该字段）
+    #[serde(default)]
+    #[sqlx(default)]
+    pub stars_count: Option<u64>,
+    /// Fork 数
+    #[serde(default)]
+    #[sqlx(default)]
+    pub forks_count: Option<u64>,
     pub url: String,
     pub topic: String,
     #[sqlx(skip)]
@@ -20,40 +59,145 @@ pub struct TrendingRepo {
     pub pushed_at: String,
     #[sqlx(skip)]
     pub license: String,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub tagline: Option<String>,
+    /// 用户为收藏写的备注（仅收藏列表提供）
+    #[serde(default)]
+    #[sqlx(default)]
+    pub note: Option<String>,
+    /// 综合新鲜度评分（按排序权重计算，仅趋势榜提供）
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub freshness_score: f64,
+    /// 结果来源
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub source: TrendingSource,
 }
 
-fn get_topic(name: &str, desc: &str) -> String {
-    let content = format!("{} {}", name, desc).to_lowercase();
-    
-    if content.contains("ai") || content.contains("llm") || content.contains("gpt") || 
-       content.contains("model") || content.contains("inference") || content.contains("agent") ||
-       content.contains("rag") || content.contains("learning") || content.contains("llama") {
-        return "AI / LLM".to_string();
-    }
-    
-    if content.contains("web") || content.contains("react") || content.contains("vue") || 
-       content.contains("frontend") || content.contains("backend") || content.contains("nextjs") ||
-       content.contains("api") || content.contains("framework") {
-        return "Web / App".to_string();
-    }
+/// 趋势榜数据来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendingSource {
+    /// GitHub Trending 页面（默认）
+    #[default]
+    GitHub,
+    /// GitHub 搜索 API：近期新建仓库按平均每日星标排序，算法透明，可在页面抓取失效时交叉对比
+    SearchApi,
+}
+
+/// 趋势开发者（`github.com/trending/developers`）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrendingDeveloper {
+    pub username: String,
+    /// 显示名称，未设置时与用户名相同
+    pub name: String,
+    pub avatar_url: String,
+    /// 代表仓库（页面未展示时为空）
+    pub popular_repo_name: String,
+    pub popular_repo_desc: String,
+    pub url: String,
+}
 
-    if content.contains("cli") || content.contains("tool") || content.contains("utility") || 
-       content.contains("helper") || content.contains("automation") || content.contains("workflow") {
-        return "Tools / CLI".to_string();
+/// 搜索结果使用的通用主题标签（未经 `get_topic` 分类）
+pub const SEARCH_RESULT_TOPIC: &str = "Search Result";
+
+/// GitHub 仓库 topics 与分类的对应关系（按优先级排列）
+const TOPIC_CATEGORIES: &[(&str, &[&str])] = &[
+    ("AI / LLM", &[
+        "ai", "artificial-intelligence", "machine-learning", "deep-learning", "llm", "llms",
+        "large-language-models", "generative-ai", "gpt", "openai", "chatgpt", "nlp", "rag",
+        "ai-agents", "agents", "transformers", "pytorch", "tensorflow", "computer-vision",
+        "langchain", "ollama",
+    ]),
+    ("Web / App", &[
+        "web", "webapp", "web-framework", "react", "vue", "svelte", "angular", "nextjs", "nodejs",
+        "frontend", "backend", "api", "rest-api", "graphql", "tailwindcss",
+    ]),
+    ("Tools / CLI", &[
+        "cli", "command-line", "terminal", "tui", "developer-tools", "devtools", "automation",
+        "workflow", "productivity",
+    ]),
+    ("Systems / OS", &[
+        "operating-system", "os", "kernel", "linux", "embedded", "drivers", "hardware", "rtos",
+        "systems-programming",
+    ]),
+    ("Mobile", &[
+        "ios", "android", "mobile", "mobile-app", "flutter", "react-native", "swiftui",
+    ]),
+];
+
+/// 根据 GitHub topics 分类：命中最多的分类胜出，数量相同时按优先级；没有命中时返回 None
+fn classify_by_topics(topics: &[String]) -> Option<&'static str> {
+    let topics: Vec<String> = topics.iter().map(|t| t.trim().to_lowercase()).collect();
+    let mut best: Option<(&'static str, usize)> = None;
+    for (category, slugs) in TOPIC_CATEGORIES {
+        let hits = topics.iter().filter(|t| slugs.contains(&t.as_str())).count();
+        if hits > best.map_or(0, |(_, most)| most) {
+            best = Some((category, hits));
+        }
     }
+    best.map(|(category, _)| category)
+}
 
-    if content.contains("system") || content.contains("kernel") || content.contains("driver") || 
-       content.contains("hardware") || content.contains("linux") || content.contains("os") ||
-       content.contains("memory") || content.contains("cpu") {
-        return "Systems / OS".to_string();
+/// 当前生效的主题分类规则（启动时与更新设置时载入）
+static TOPIC_RULES: LazyLock<RwLock<Vec<TopicRule>>> =
+    LazyLock::new(|| RwLock::new(crate::models::default_topic_rules()));
+
+/// 载入主题分类规则
+pub fn load_topic_rules(rules: Vec<TopicRule>) {
+    if let Ok(mut current) = TOPIC_RULES.write() {
+        *current = rules;
     }
+}
 
-    if content.contains("ios") || content.contains("android") || content.contains("mobile") || 
-       content.contains("flutter") || content.contains("swift") || content.contains("kotlin") {
-        return "Mobile".to_string();
+/// 为仓库归类主题：优先使用仓库自身的 topics（仅搜索结果提供），否则按当前规则匹配名称与描述
+pub fn get_topic(name: &str, desc: &str, topics: &[String]) -> String {
+    let rules = TOPIC_RULES.read().map(|rules| rules.clone()).unwrap_or_default();
+    classify_topic(name, desc, topics, &rules)
+}
+
+/// 按给定规则归类主题，都未命中时归为 "General"
+fn classify_topic(name: &str, desc: &str, topics: &[String], rules: &[TopicRule]) -> String {
+    if let Some(category) = classify_by_topics(topics) {
+        return category.to_string();
     }
 
-    "General".to_string()
+    let content = format!("{} {}", name, desc).to_lowercase();
+    rules
+        .iter()
+        .find(|rule| rule.keywords.iter().any(|k| !k.is_empty() && content.contains(&k.to_lowercase())))
+        .map(|rule| rule.category.clone())
+        .unwrap_or_else(|| "General".to_string())
+}
+
+/// 获取当前的主题分类规则
+#[tauri::command]
+pub async fn get_topic_rules(
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<Vec<TopicRule>, String> {
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settings.topic_rules)
+}
+
+/// 更新主题分类规则，传入空列表恢复内置规则
+#[tauri::command]
+pub async fn set_topic_rules(
+    rules: Vec<TopicRule>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<Vec<TopicRule>, String> {
+    let updates = crate::models::AppSettingsUpdate {
+        topic_rules: Some(rules),
+        ..Default::default()
+    };
+    let settings = config_manager.lock().await
+        .update_app_settings(updates)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settings.topic_rules)
 }
 
 fn parse_github_number(s: &str) -> u64 {
@@ -64,73 +208,207 @@ fn parse_github_number(s: &str) -> u64 {
         .unwrap_or(0)
 }
 
-#[tauri::command]
-pub async fn get_trending(language: Option<String>, since: String) -> Result<Vec<TrendingRepo>, String> {
-    fetch_trending(language, &since).await
+/// 解析星标数文本（如 `12,345`、`1.2k`、`3m`），无法解析时返回 None
+pub(crate) fn parse_star_count(s: &str) -> Option<u64> {
+    let text = s.trim().replace(',', "").to_lowercase();
+    let (number, multiplier) = match text.chars().last()? {
+        'k' => (&text[..text.len() - 1], 1_000.0),
+        'm' => (&text[..text.len() - 1], 1_000_000.0),
+        _ => (text.as_str(), 1.0),
+    };
+    let value = number.trim().parse::<f64>().ok()?;
+    Some((value * multiplier).round() as u64)
 }
 
-pub async fn fetch_trending(language: Option<String>, since: &str) -> Result<Vec<TrendingRepo>, String> {
-    let url = match language {
-        Some(lang) => format!("https://github.com/trending/{}?since={}", lang, since),
-        None => format!("https://github.com/trending?since={}", since),
-    };
+/// 计算仓库的综合新鲜度评分：增速与总量取对数避免头部仓库压倒一切，
+/// 有描述、有语言标识各加固定分
+pub fn freshness_score(repo: &TrendingRepo, weights: &RankingWeights) -> f64 {
+    let stars_today = parse_github_number(&repo.stars_today) as f64;
+    let total_stars = parse_github_number(&repo.stars) as f64;
+    let has_description = !repo.description.trim().is_empty();
+    let has_language = !repo.language.trim().is_empty();
 
-    let response = reqwest::get(&url)
-        .await
-        .map_err(|e| e.to_string())?
-        .text()
+    let score = weights.stars_today * stars_today.ln_1p()
+        + weights.total_stars * total_stars.ln_1p()
+        + if has_description { weights.description } else { 0.0 }
+        + if has_language { weights.language } else { 0.0 };
+    (score * 100.0).round() / 100.0
+}
+
+/// 设置趋势榜新鲜度评分的权重
+#[tauri::command]
+pub async fn set_ranking_weights(
+    weights: RankingWeights,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<RankingWeights, String> {
+    let updates = crate::models::AppSettingsUpdate {
+        ranking_weights: Some(weights),
+        ..Default::default()
+    };
+    let settings = config_manager.lock().await
+        .update_app_settings(updates)
         .await
         .map_err(|e| e.to_string())?;
+    Ok(settings.ranking_weights)
+}
+
+/// 校验 README 语言代码，无效值记录警告后忽略（返回 None）
+fn spoken_language_code(code: Option<&str>) -> Option<&'static str> {
+    let code = code?.trim().to_lowercase();
+    if code.is_empty() {
+        return None;
+    }
+    let matched = SPOKEN_LANGUAGE_CODES.iter().copied().find(|c| *c == code);
+    if matched.is_none() {
+        log::warn!("忽略无效的 README 语言代码: {}", code);
+    }
+    matched
+}
+
+/// 第 `attempt` 次重试前的等待时间（指数退避）
+fn retry_delay(attempt: u32) -> Duration {
+    TRENDING_RETRY_BASE_DELAY * 2u32.pow(attempt)
+}
+
+/// 是否为值得重试的暂时性状态码（服务端错误与限流，不包括 404 等客户端错误）
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// 抓取趋势榜页面，连接失败、超时与服务端错误时按退避重试
+async fn fetch_trending_page(url: &str) -> Result<String, String> {
+    let client = crate::github::http_client();
+
+    let mut attempt = 0;
+    loop {
+        let retryable = match client.get(url).timeout(TRENDING_TIMEOUT).send().await {
+            Ok(response) if attempt < MAX_TRENDING_RETRIES && is_transient_status(response.status()) => {
+                format!("HTTP {}", response.status())
+            }
+            Ok(response) => return response.text().await.map_err(|e| e.to_string()),
+            Err(e) if attempt < MAX_TRENDING_RETRIES && (e.is_connect() || e.is_timeout()) => e.to_string(),
+            Err(e) => return Err(e.to_string()),
+        };
+        let delay = retry_delay(attempt);
+        attempt += 1;
+        log::warn!("获取趋势榜失败（{}），{} 毫秒后重试（第 {} 次）", retryable, delay.as_millis(), attempt);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// `get_trending` 的查询参数，除 `since` 外均可省略
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrendingQuery {
+    pub language: Option<String>,
+    /// 时间范围：daily、weekly 或 monthly
+    pub since: String,
+    pub exclude_hidden: Option<bool>,
+    pub source: Option<TrendingSource>,
+    pub spoken_language: Option<String>,
+    pub min_stars: Option<u64>,
+}
+
+#[tauri::command]
+pub async fn get_trending(
+    query: TrendingQuery,
+    db: tauri::State<'_, crate::db::DbState>,
+    config_manager: tauri::State<'_, ConfigManagerState>,
+) -> Result<Vec<TrendingRepo>, String> {
+    let TrendingQuery { language, since, exclude_hidden, source, spoken_language, min_stars } = query;
+    let settings = config_manager.lock().await
+        .get_app_settings()
+        .await
+        .unwrap_or_default();
+    let repos = match source.unwrap_or_default() {
+        TrendingSource::GitHub => fetch_trending(language, &since, spoken_language.as_deref()).await,
+        TrendingSource::SearchApi => {
+            let host = crate::github::GitHubHost::from_settings(&settings);
+            crate::search::fetch_trending_via_search(&host, language.as_deref(), &since).await
+        }
+    };
+    let mut repos = repos?;
+    if let Some(min_stars) = min_stars {
+        retain_min_stars(&mut repos, min_stars);
+    }
+    for repo in &mut repos {
+        repo.freshness_score = freshness_score(repo, &settings.ranking_weights);
+    }
+    if exclude_hidden.unwrap_or(false) {
+        crate::db::filter_hidden(db.inner(), repos).await
+    } else {
+        Ok(repos)
+    }
+}
+
+/// 只保留星标总数不少于 `min_stars` 的仓库，保持原有顺序；星标数未知的仓库一并过滤
+fn retain_min_stars(repos: &mut Vec<TrendingRepo>, min_stars: u64) {
+    repos.retain(|repo| repo.stars_count.is_some_and(|count| count >= min_stars));
+}
+
+/// 解析选择器列表，按顺序作为主选择器与备用选择器
+fn selectors(patterns: &[&str]) -> Vec<Selector> {
+    patterns.iter().map(|p| Selector::parse(p).unwrap()).collect()
+}
+
+/// 依次尝试各选择器，返回第一个非空的文本
+fn select_text(node: ElementRef, selectors: &[Selector]) -> String {
+    selectors
+        .iter()
+        .flat_map(|selector| node.select(selector))
+        .map(|n| n.text().collect::<Vec<_>>().join("").trim().to_string())
+        .find(|text| !text.is_empty())
+        .unwrap_or_default()
+}
 
-    let document = Html::parse_document(&response);
+/// 解析趋势榜页面
+///
+/// GitHub 调整页面结构时主选择器可能失效，因此每个字段都带有备用选择器；
+/// 若超过一半的仓库仍缺少星标数，说明抓取逻辑已失效，返回错误而不是展示残缺的列表。
+fn parse_trending_html(html: &str) -> Result<Vec<TrendingRepo>, String> {
+    let document = Html::parse_document(html);
     let repo_selector = Selector::parse("article.Box-row").unwrap();
-    let title_selector = Selector::parse("h2 a").unwrap();
-    let desc_selector = Selector::parse("p.col-9").unwrap();
+    let title_selectors = selectors(&["h2 a", "h1 a"]);
+    let desc_selectors = selectors(&["p.col-9", "p"]);
     let meta_selector = Selector::parse("div.f6.color-fg-muted").unwrap();
-    let lang_selector = Selector::parse("span[itemprop='programmingLanguage']").unwrap();
-    let stars_selector = Selector::parse("a.Link--muted:nth-of-type(1)").unwrap();
-    let forks_selector = Selector::parse("a.Link--muted:nth-of-type(2)").unwrap();
-    let stars_today_selector = Selector::parse("span.float-sm-right").unwrap();
+    let lang_selectors = selectors(&["span[itemprop='programmingLanguage']"]);
+    let stars_selectors = selectors(&["a.Link--muted:nth-of-type(1)", "a[href$='/stargazers']"]);
+    let forks_selectors = selectors(&["a.Link--muted:nth-of-type(2)", "a[href$='/forks']"]);
+    let stars_today_selectors = selectors(&["span.float-sm-right", "span.d-inline-block.float-sm-right"]);
     let built_by_selector = Selector::parse("span.d-inline-block.mr-3 img.avatar").unwrap();
 
     let mut repos = Vec::new();
 
     for repo_node in document.select(&repo_selector) {
-        let title_link = match repo_node.select(&title_selector).next() {
+        let title_link = match title_selectors.iter().find_map(|s| repo_node.select(s).next()) {
             Some(link) => link,
             None => continue, // 跳过无效节点
         };
+        let href = title_link.value().attr("href").unwrap_or("");
         let full_name = title_link.text().collect::<Vec<_>>().join("");
-        let parts: Vec<&str> = full_name.split('/').map(|s| s.trim()).collect();
-        
-        let author = parts.get(0).unwrap_or(&"").to_string();
-        let name = parts.get(1).unwrap_or(&"").to_string();
-        let url = format!("https://github.com{}", title_link.value().attr("href").unwrap_or(""));
-
-        let description = repo_node.select(&desc_selector)
-            .next()
-            .map(|n| n.text().collect::<Vec<_>>().join("").trim().to_string())
-            .unwrap_or_default();
+        let mut parts: Vec<&str> = full_name.split('/').map(|s| s.trim()).collect();
+        if parts.len() < 2 || parts.iter().any(|p| p.is_empty()) {
+            // 标题文本结构变化时，从链接地址中解析 owner/name
+            parts = href.split('/').filter(|s| !s.is_empty()).collect();
+        }
 
-        let meta_node = repo_node.select(&meta_selector).next();
-        
-        let language = meta_node.and_then(|m| m.select(&lang_selector).next())
-            .map(|n| n.text().collect::<Vec<_>>().join("").trim().to_string())
-            .unwrap_or_else(|| "Unknown".to_string());
+        let author = parts.first().unwrap_or(&"").to_string();
+        let name = parts.get(1).unwrap_or(&"").to_string();
+        let url = format!("https://github.com{}", href);
 
-        let stars = meta_node.and_then(|m| m.select(&stars_selector).next())
-            .map(|n| n.text().collect::<Vec<_>>().join("").trim().to_string())
-            .unwrap_or_default();
+        let description = select_text(repo_node, &desc_selectors);
 
-        let forks = meta_node.and_then(|m| m.select(&forks_selector).next())
-            .map(|n| n.text().collect::<Vec<_>>().join("").trim().to_string())
-            .unwrap_or_default();
+        // 统计信息所在的容器找不到时，退回到整个条目内查找
+        let meta_node = repo_node.select(&meta_selector).next().unwrap_or(repo_node);
 
-        let stars_today = meta_node.and_then(|m| m.select(&stars_today_selector).next())
-            .map(|n| n.text().collect::<Vec<_>>().join("").trim().to_string())
-            .unwrap_or_default();
+        let language = Some(select_text(meta_node, &lang_selectors))
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let stars = select_text(meta_node, &stars_selectors);
+        let forks = select_text(meta_node, &forks_selectors);
+        let stars_today = select_text(meta_node, &stars_today_selectors);
 
-        let topic = get_topic(&name, &description);
+        // 趋势榜页面不包含 topics，只能按名称与描述分类
+        let topic = get_topic(&name, &description, &[]);
         
         let built_by = repo_node.select(&built_by_selector)
             .map(|img| img.value().attr("src").unwrap_or_default().to_string())
@@ -142,6 +420,8 @@ pub async fn fetch_trending(language: Option<String>, since: &str) -> Result<Vec
             name,
             description,
             language,
+            stars_count: parse_star_count(&stars),
+            forks_count: parse_star_count(&forks),
             stars,
             forks,
             stars_today,
@@ -151,31 +431,499 @@ pub async fn fetch_trending(language: Option<String>, since: &str) -> Result<Vec
             topics: Vec::new(),
             pushed_at: "".to_string(),
             license: "".to_string(),
+            tagline: None,
+            note: None,
+            freshness_score: 0.0,
+            source: TrendingSource::GitHub,
         });
     }
 
+    let missing_stars = repos.iter().filter(|r| r.stars.is_empty()).count();
+    if missing_stars * 2 > repos.len() {
+        return Err(format!(
+            "趋势榜页面解析异常：{} 个仓库中有 {} 个缺少星标数，GitHub 页面结构可能已变更",
+            repos.len(),
+            missing_stars
+        ));
+    }
+
+    Ok(repos)
+}
+
+/// 抓取趋势榜，`spoken_language` 为 README 语言代码（如 `zh`），无效值会被忽略
+pub async fn fetch_trending(
+    language: Option<String>,
+    since: &str,
+    spoken_language: Option<&str>,
+) -> Result<Vec<TrendingRepo>, String> {
+    crate::offline::ensure_online()?;
+    let mut url = match language {
+        Some(lang) => format!("https://github.com/trending/{}?since={}", lang, since),
+        None => format!("https://github.com/trending?since={}", since),
+    };
+    if let Some(code) = spoken_language_code(spoken_language) {
+        url.push_str(&format!("&spoken_language_code={}", code));
+    }
+
+    let response = fetch_trending_page(&url).await?;
+    let mut repos = parse_trending_html(&response)?;
+
     // 排序逻辑：根据 stars_today (增速) 降序排，相同增速则按 stars (总量) 降序排
     repos.sort_by(|a, b| {
         let a_today = parse_github_number(&a.stars_today);
         let b_today = parse_github_number(&b.stars_today);
         
-        let a_total = parse_github_number(&a.stars);
-        let b_total = parse_github_number(&b.stars);
-
         // 优先比较增速，其次比较总量
-        b_today.cmp(&a_today).then_with(|| b_total.cmp(&a_total))
+        b_today.cmp(&a_today).then_with(|| b.stars_count.cmp(&a.stars_count))
     });
 
     Ok(repos)
 }
 
+/// 获取趋势开发者列表
+#[tauri::command]
+pub async fn get_trending_developers(
+    language: Option<String>,
+    since: String,
+) -> Result<Vec<TrendingDeveloper>, String> {
+    fetch_trending_developers(language, &since).await
+}
+
+async fn fetch_trending_developers(language: Option<String>, since: &str) -> Result<Vec<TrendingDeveloper>, String> {
+    crate::offline::ensure_online()?;
+    let url = match language {
+        Some(lang) => format!("https://github.com/trending/developers/{}?since={}", lang, since),
+        None => format!("https://github.com/trending/developers?since={}", since),
+    };
+
+    let response = fetch_trending_page(&url).await?;
+    Ok(parse_trending_developers_html(&response))
+}
+
+/// 解析趋势开发者页面
+fn parse_trending_developers_html(html: &str) -> Vec<TrendingDeveloper> {
+    let document = Html::parse_document(html);
+    let developer_selector = Selector::parse("article.Box-row").unwrap();
+    let name_selectors = selectors(&["h1.h3 a", "h1 a"]);
+    let username_selectors = selectors(&["p.f4 a", "p a"]);
+    let avatar_selector = Selector::parse("img.avatar-user").unwrap();
+    let repo_name_selectors = selectors(&["article h1.h4 a", "h1.h4 a"]);
+    let repo_desc_selectors = selectors(&["article div.f6.color-fg-muted.mt-1", "div.f6.color-fg-muted.mt-1"]);
+
+    let mut developers = Vec::new();
+
+    for node in document.select(&developer_selector) {
+        let name_link = match name_selectors.iter().find_map(|s| node.select(s).next()) {
+            Some(link) => link,
+            None => continue,
+        };
+        let name = name_link.text().collect::<Vec<_>>().join("").trim().to_string();
+        // 未设置显示名称时页面只展示用户名，此时从链接地址中获取
+        let username = Some(select_text(node, &username_selectors))
+            .filter(|u| !u.is_empty())
+            .unwrap_or_else(|| name_link.value().attr("href").unwrap_or("").trim_matches('/').to_string());
+        if username.is_empty() {
+            continue;
+        }
+
+        let avatar_url = node.select(&avatar_selector)
+            .next()
+            .and_then(|img| img.value().attr("src"))
+            .unwrap_or_default()
+            .to_string();
+
+        developers.push(TrendingDeveloper {
+            url: format!("https://github.com/{}", username),
+            name: if name.is_empty() { username.clone() } else { name },
+            username,
+            avatar_url,
+            popular_repo_name: select_text(node, &repo_name_selectors),
+            popular_repo_desc: select_text(node, &repo_desc_selectors),
+        });
+    }
+
+    developers
+}
+
+/// 合并趋势榜与搜索结果，按仓库地址去重
+#[tauri::command]
+pub fn merge_repo_feeds(trending: Vec<TrendingRepo>, search: Vec<TrendingRepo>) -> Vec<TrendingRepo> {
+    merge_repos(trending, search)
+}
+
+/// 按仓库地址（忽略大小写与末尾斜杠）合并两个列表，保持先出现的顺序
+///
+/// 重复的仓库保留信息更完整的记录，并用另一条记录补齐缺失的字段；
+/// 已分类的主题优先于搜索结果的通用标签。
+pub fn merge_repos(primary: Vec<TrendingRepo>, secondary: Vec<TrendingRepo>) -> Vec<TrendingRepo> {
+    let mut merged: Vec<TrendingRepo> = Vec::with_capacity(primary.len() + secondary.len());
+    for repo in primary.into_iter().chain(secondary) {
+        let key = repo_key(&repo.url);
+        match merged.iter().position(|existing| repo_key(&existing.url) == key) {
+            Some(index) => merged[index] = merge_pair(merged[index].clone(), repo),
+            None => merged.push(repo),
+        }
+    }
+    merged
+}
+
+fn repo_key(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_lowercase()
+}
+
+/// 记录中有效字段的数量，用于判断哪条记录更完整
+fn richness(repo: &TrendingRepo) -> usize {
+    [
+        !repo.description.trim().is_empty(),
+        !repo.language.is_empty() && repo.language != "Unknown",
+        !repo.stars.is_empty(),
+        !repo.forks.is_empty(),
+        !repo.stars_today.is_empty(),
+        repo.topic != SEARCH_RESULT_TOPIC,
+        !repo.built_by.is_empty(),
+        !repo.topics.is_empty(),
+        !repo.pushed_at.is_empty(),
+        !repo.license.is_empty() && repo.license != "None",
+        repo.tagline.is_some(),
+    ]
+    .iter()
+    .filter(|present| **present)
+    .count()
+}
+
+/// 合并同一仓库的两条记录，相同完整度时保留先出现的记录
+fn merge_pair(existing: TrendingRepo, incoming: TrendingRepo) -> TrendingRepo {
+    let (mut base, other) = if richness(&incoming) > richness(&existing) {
+        (incoming, existing)
+    } else {
+        (existing, incoming)
+    };
+
+    // `empty` 中的占位值（如 "Unknown"）同样视为缺失
+    fn fill(target: &mut String, source: String, empty: &[&str]) {
+        let missing = |value: &str| value.trim().is_empty() || empty.contains(&value);
+        if missing(target) && !missing(&source) {
+            *target = source;
+        }
+    }
+    fill(&mut base.description, other.description, &[]);
+    fill(&mut base.language, other.language, &["Unknown"]);
+    fill(&mut base.stars, other.stars, &[]);
+    fill(&mut base.forks, other.forks, &[]);
+    base.stars_count = base.stars_count.or(other.stars_count);
+    base.forks_count = base.forks_count.or(other.forks_count);
+    fill(&mut base.stars_today, other.stars_today, &[]);
+    fill(&mut base.topic, other.topic, &[SEARCH_RESULT_TOPIC]);
+    fill(&mut base.pushed_at, other.pushed_at, &[]);
+    fill(&mut base.license, other.license, &["None"]);
+    if base.built_by.is_empty() {
+        base.built_by = other.built_by;
+    }
+    if base.topics.is_empty() {
+        base.topics = other.topics;
+    }
+    if base.tagline.is_none() {
+        base.tagline = other.tagline;
+    }
+    base.freshness_score = base.freshness_score.max(other.freshness_score);
+    base
+}
+
+/// 热门主题统计
+#[derive(Debug, Serialize)]
+pub struct TopicFrequency {
+    pub topic: String,
+    pub count: usize,
+    /// 该主题下的示例仓库（author/name）
+    pub sample_repos: Vec<String>,
+}
+
+/// 每个主题最多返回的示例仓库数
+const TOPIC_SAMPLE_SIZE: usize = 3;
+
+/// 统计当前热门列表中各主题的出现频次
+#[tauri::command]
+pub async fn get_trending_topics(language: Option<String>, since: String) -> Result<Vec<TopicFrequency>, String> {
+    let repos = fetch_trending(language, &since, None).await?;
+    Ok(aggregate_topics(&repos))
+}
+
+/// 按主题聚合仓库，按数量降序排列（列表已按热度排序，示例仓库取最靠前的几个）
+fn aggregate_topics(repos: &[TrendingRepo]) -> Vec<TopicFrequency> {
+    let mut topics: Vec<TopicFrequency> = Vec::new();
+    for repo in repos {
+        let full_name = format!("{}/{}", repo.author, repo.name);
+        match topics.iter_mut().find(|t| t.topic == repo.topic) {
+            Some(entry) => {
+                entry.count += 1;
+                if entry.sample_repos.len() < TOPIC_SAMPLE_SIZE {
+                    entry.sample_repos.push(full_name);
+                }
+            }
+            None => topics.push(TopicFrequency {
+                topic: repo.topic.clone(),
+                count: 1,
+                sample_repos: vec![full_name],
+            }),
+        }
+    }
+    topics.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.topic.cmp(&b.topic)));
+    topics
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_star_count() {
+        assert_eq!(parse_star_count("12,345"), Some(12345));
+        assert_eq!(parse_star_count("1.2k"), Some(1200));
+        assert_eq!(parse_star_count(" 2.3K "), Some(2300));
+        assert_eq!(parse_star_count("3m"), Some(3_000_000));
+        assert_eq!(parse_star_count("999"), Some(999));
+        assert_eq!(parse_star_count(""), None);
+        assert_eq!(parse_star_count("n/a"), None);
+    }
+
+    #[test]
+    fn test_freshness_score() {
+        let weights = RankingWeights::default();
+        let mut bare = repo("bare", "General");
+        bare.stars = "1,000".to_string();
+        bare.stars_today = "10".to_string();
+        let mut described = bare.clone();
+        described.description = "A fast tool".to_string();
+        described.language = "Rust".to_string();
+        let mut hot = bare.clone();
+        hot.stars_today = "1,000".to_string();
+
+        assert!(freshness_score(&described, &weights) > freshness_score(&bare, &weights));
+        assert!(freshness_score(&hot, &weights) > freshness_score(&described, &weights));
+        assert_eq!(freshness_score(&repo("empty", "General"), &weights), 0.0);
+
+        let only_today = RankingWeights { stars_today: 1.0, total_stars: 0.0, description: 0.0, language: 0.0 };
+        assert_eq!(freshness_score(&described, &only_today), freshness_score(&bare, &only_today));
+    }
+
+    #[test]
+    fn test_retain_min_stars() {
+        let mut repos = vec![repo("a", "General"), repo("b", "General"), repo("c", "General"), repo("d", "General")];
+        repos[0].stars_count = Some(5_000);
+        repos[1].stars_count = Some(12);
+        repos[2].stars_count = Some(1_000);
+
+        retain_min_stars(&mut repos, 1_000);
+        let names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_get_topic_prefers_repo_topics() {
+        let topics = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        // 关键词匹配会因描述中的 "tool" 归为工具，topics 明确指向 AI
+        let ml = topics(&["Machine-Learning", "python"]);
+        assert_eq!(get_topic("runner", "A tool for experiments", &ml), "AI / LLM");
+
+        // 命中数更多的分类胜出
+        let mobile = topics(&["flutter", "android", "cli"]);
+        assert_eq!(get_topic("app", "", &mobile), "Mobile");
+
+        // 数量相同时按分类优先级
+        let tie = topics(&["cli", "react"]);
+        assert_eq!(get_topic("x", "", &tie), "Web / App");
+
+        // 没有可识别的 topics 时退回关键词匹配
+        assert_eq!(get_topic("kernel-patches", "", &topics(&["c", "awesome"])), "Systems / OS");
+        assert_eq!(get_topic("llama.cpp", "", &[]), "AI / LLM");
+        assert_eq!(get_topic("zzz", "", &[]), "General");
+    }
+
+    #[test]
+    fn test_classify_topic_with_rules() {
+        let rules = crate::models::default_topic_rules();
+        assert_eq!(classify_topic("claw", "开源的大模型智能体框架", &[], &rules), "AI / LLM");
+        assert_eq!(classify_topic("shot", "一个截图命令行工具", &[], &rules), "Tools / CLI");
+        assert_eq!(classify_topic("board", "基于 ESP32 的嵌入式开发板固件", &[], &rules), "Systems / OS");
+
+        // 自定义规则按顺序匹配，未命中时归为 General
+        let custom = vec![TopicRule { category: "Games".to_string(), keywords: vec!["Game".to_string(), "游戏".to_string()] }];
+        assert_eq!(classify_topic("engine", "A tiny game engine", &[], &custom), "Games");
+        assert_eq!(classify_topic("engine", "独立游戏合集", &[], &custom), "Games");
+        assert_eq!(classify_topic("kernel", "", &[], &custom), "General");
+    }
+
+    #[test]
+    fn test_spoken_language_code() {
+        assert_eq!(spoken_language_code(Some("zh")), Some("zh"));
+        assert_eq!(spoken_language_code(Some(" EN ")), Some("en"));
+        assert_eq!(spoken_language_code(Some("zh-CN")), None);
+        assert_eq!(spoken_language_code(Some("")), None);
+        assert_eq!(spoken_language_code(None), None);
+    }
+
+    #[test]
+    fn test_trending_retry_policy() {
+        assert_eq!(retry_delay(0), Duration::from_millis(500));
+        assert_eq!(retry_delay(2), Duration::from_millis(2000));
+        assert!(is_transient_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(is_transient_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_transient_status(reqwest::StatusCode::NOT_FOUND));
+    }
+
+    fn repo(name: &str, topic: &str) -> TrendingRepo {
+        TrendingRepo {
+            author: "owner".to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            language: String::new(),
+            stars: String::new(),
+            forks: String::new(),
+            stars_today: String::new(),
+            stars_count: None,
+            forks_count: None,
+            url: format!("https://github.com/owner/{}", name),
+            topic: topic.to_string(),
+            built_by: Vec::new(),
+            topics: Vec::new(),
+            pushed_at: String::new(),
+            license: String::new(),
+            tagline: None,
+            note: None,
+            freshness_score: 0.0,
+            source: TrendingSource::GitHub,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_topics() {
+        let repos = vec![
+            repo("a", "AI / LLM"),
+            repo("b", "Web / App"),
+            repo("c", "AI / LLM"),
+            repo("d", "AI / LLM"),
+            repo("e", "AI / LLM"),
+        ];
+        let topics = aggregate_topics(&repos);
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].topic, "AI / LLM");
+        assert_eq!(topics[0].count, 4);
+        assert_eq!(topics[0].sample_repos, vec!["owner/a", "owner/c", "owner/d"]);
+        assert_eq!(topics[1].topic, "Web / App");
+    }
+
+    #[test]
+    fn test_parse_trending_html() {
+        let repos = parse_trending_html(include_str!("testdata/trending.html")).unwrap();
+        assert_eq!(repos.len(), 3);
+
+        let uv = &repos[0];
+        assert_eq!((uv.author.as_str(), uv.name.as_str()), ("astral-sh", "uv"));
+        assert_eq!(uv.url, "https://github.com/astral-sh/uv");
+        assert_eq!(uv.language, "Rust");
+        assert_eq!((uv.stars.as_str(), uv.forks.as_str()), ("52,314", "1,520"));
+        assert_eq!((uv.stars_count, uv.forks_count), (Some(52_314), Some(1_520)));
+        assert_eq!(uv.stars_today, "312 stars today");
+        assert_eq!(uv.built_by.len(), 2);
+        assert!(uv.description.starts_with("An extremely fast Python package"));
+
+        // 没有描述与语言的仓库仍能解析出星标数
+        let dotfiles = &repos[2];
+        assert_eq!(dotfiles.language, "Unknown");
+        assert_eq!((dotfiles.stars.as_str(), dotfiles.forks.as_str()), ("845", "61"));
+    }
+
+    #[test]
+    fn test_parse_trending_html_fallbacks() {
+        // 统计容器的 class 变化后，按链接地址的备用选择器解析
+        let html = r#"<article class="Box-row">
+            <h2><a href="/owner/repo">owner / repo</a></h2>
+            <div class="stats"><a href="/owner/repo/stargazers">1,234</a><a href="/owner/repo/forks">56</a></div>
+        </article>"#;
+        let repos = parse_trending_html(html).unwrap();
+        assert_eq!((repos[0].stars.as_str(), repos[0].forks.as_str()), ("1,234", "56"));
+
+        // 星标数整体缺失时报错，而不是返回残缺的列表
+        let broken = r#"<article class="Box-row"><h2><a href="/a/b">a / b</a></h2></article>
+            <article class="Box-row"><h2><a href="/c/d">c / d</a></h2></article>"#;
+        assert!(parse_trending_html(broken).is_err());
+    }
+
+    #[test]
+    fn test_parse_trending_developers_html() {
+        let html = r#"
+        <article class="Box-row d-flex" id="pa-charliermarsh">
+          <div class="mx-3"><a href="/charliermarsh"><img class="rounded avatar-user" src="https://avatars.githubusercontent.com/u/1309177?s=96&amp;v=4" alt="@charliermarsh"></a></div>
+          <div class="col-md-6">
+            <h1 class="h3 lh-condensed"><a href="/charliermarsh">Charlie Marsh</a></h1>
+            <p class="f4 text-normal mb-1"><a class="Link--secondary" href="/charliermarsh">charliermarsh</a></p>
+          </div>
+          <div class="col-md-6">
+            <article>
+              <div class="f6 color-fg-muted text-uppercase mb-1">Popular repo</div>
+              <h1 class="h4 lh-condensed"><a href="/astral-sh/ruff">ruff</a></h1>
+              <div class="f6 color-fg-muted mt-1">An extremely fast Python linter.</div>
+            </article>
+          </div>
+        </article>
+        <article class="Box-row d-flex" id="pa-octocat">
+          <h1 class="h3 lh-condensed"><a href="/octocat">octocat</a></h1>
+        </article>"#;
+        let developers = parse_trending_developers_html(html);
+        assert_eq!(developers.len(), 2);
+
+        let charlie = &developers[0];
+        assert_eq!((charlie.username.as_str(), charlie.name.as_str()), ("charliermarsh", "Charlie Marsh"));
+        assert_eq!(charlie.url, "https://github.com/charliermarsh");
+        assert!(charlie.avatar_url.starts_with("https://avatars.githubusercontent.com/u/1309177"));
+        assert_eq!(charlie.popular_repo_name, "ruff");
+        assert_eq!(charlie.popular_repo_desc, "An extremely fast Python linter.");
+
+        // 没有显示名称与代表仓库
+        let octocat = &developers[1];
+        assert_eq!((octocat.username.as_str(), octocat.name.as_str()), ("octocat", "octocat"));
+        assert!(octocat.popular_repo_name.is_empty());
+    }
+
+    #[test]
+    fn test_merge_repos() {
+        let mut trending = repo("tool", "Tools / CLI");
+        trending.stars = "1,000".to_string();
+        trending.stars_today = "50 stars today".to_string();
+        trending.freshness_score = 3.5;
+
+        let mut searched = repo("tool", SEARCH_RESULT_TOPIC);
+        searched.url = "https://github.com/Owner/tool/".to_string();
+        searched.stars = "1,001".to_string();
+        searched.description = "A tool".to_string();
+        searched.language = "Rust".to_string();
+        searched.topics = vec!["cli".to_string()];
+        searched.pushed_at = "2024-01-01T00:00:00Z".to_string();
+        searched.license = "MIT License".to_string();
+
+        let merged = merge_repos(vec![trending.clone(), repo("other", "General")], vec![searched.clone()]);
+        assert_eq!(merged.len(), 2);
+        let tool = &merged[0];
+        // 搜索结果更完整，作为基础记录；分类主题与趋势数据从趋势榜补齐
+        assert_eq!(tool.stars, "1,001");
+        assert_eq!(tool.description, "A tool");
+        assert_eq!(tool.topic, "Tools / CLI");
+        assert_eq!(tool.stars_today, "50 stars today");
+        assert_eq!(tool.freshness_score, 3.5);
+
+        // 同等完整度时保留先出现的记录，通用标签不会覆盖已分类的主题
+        let generic = repo("tool", SEARCH_RESULT_TOPIC);
+        let merged = merge_repos(vec![generic], vec![repo("tool", "AI / LLM")]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].topic, "AI / LLM");
+        let merged = merge_repos(vec![repo("tool", "AI / LLM")], vec![repo("tool", SEARCH_RESULT_TOPIC)]);
+        assert_eq!(merged[0].topic, "AI / LLM");
+    }
+
     #[tokio::test]
     async fn test_fetch_trending() {
-        let result = fetch_trending(None, "daily").await;
+        let result = fetch_trending(None, "daily", None).await;
         assert!(result.is_ok());
         let repos = result.unwrap();
         assert!(!repos.is_empty());
@@ -1,5 +1,5 @@
 import { useState, useEffect, useRef, useMemo, useCallback, memo } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { invoke, isCommandError, errorMessage } from "./ipc";
 import { Channel } from "@tauri-apps/api/core";
 import { useTranslation } from "react-i18next";
 
@@ -11,11 +11,25 @@ interface TrendingRepo {
   stars: string;
   forks: string;
   stars_today: string;
+  stars_count?: number | null;
+  forks_count?: number | null;
   url: string;
   topic: string;
   topics?: string[];
   pushed_at?: string;
   license?: string;
+  tagline?: string | null;
+  note?: string | null;
+  freshness_score?: number;
+  source?: "GitHub" | "SearchApi";
+}
+
+interface SearchResults {
+  items: TrendingRepo[];
+  incomplete_results: boolean;
+  total_count: number;
+  page: number;
+  per_page: number;
 }
 
 const MarkdownView = ({ content }: { content: string }) => {
@@ -52,9 +66,29 @@ const MarkdownView = ({ content }: { content: string }) => {
 
 type StreamPayload =
   | { type: "Token", data: string }
+  | { type: "Reasoning", data: string }
   | { type: "Error", data: string }
+  | { type: "Reset", data: null }
+  | { type: "ContextSkipped", data: string[] }
+  | { type: "Usage", data: TokenUsage }
   | { type: "Done", data: null };
 
+// GitHub API 主配额（get_github_rate_limit）
+type GitHubRateLimit = {
+  remaining: number;
+  limit: number;
+  reset_at: number;
+};
+
+// 剩余配额低于该值时提示用户（深度模式一次总结会消耗多个请求）
+const RATE_LIMIT_WARNING = 10;
+
+type TokenUsage = {
+  prompt_tokens: number;
+  completion_tokens: number;
+  total_tokens: number;
+};
+
 type ModelProvider =
   | "OpenAI"
   | "Anthropic"
@@ -71,6 +105,16 @@ interface ModelConfig {
   api_key: string;
   default_model: string;
   enabled: boolean;
+  unsupported_params?: string[];
+  param_renames?: Record<string, string>;
+  max_tokens?: number;
+  temperature?: number;
+  max_retries?: number;
+  timeout_secs?: number;
+  proxy_url?: string;
+  stream_buffer_size?: number;
+  api_version?: string;
+  thinking_budget?: number;
   created_at: string;
   updated_at: string;
 }
@@ -91,6 +135,8 @@ const Sidebar = memo(({ activeTab, setActiveTab, onSearchClick }: any) => {
   const toggleLanguage = () => {
     const newLang = i18n.language === 'en' ? 'zh' : 'en';
     i18n.changeLanguage(newLang);
+    // 同步到后端，使错误信息使用相同语言
+    invoke("update_app_settings", { updates: { ui_language: newLang } }).catch(console.error);
   };
 
   return (
@@ -138,7 +184,10 @@ const Sidebar = memo(({ activeTab, setActiveTab, onSearchClick }: any) => {
   );
 });
 
-const RepoList = memo(({ repos, selectedRepo, onSelectRepo, selectedTopic, setSelectedTopic, selectedSince, setSelectedSince, summarizedUrls, isLoading, activeTab, onRefresh }: any) => {
+const FAVORITE_SORTS = ["created_at", "stars", "name"] as const;
+type FavoriteSort = typeof FAVORITE_SORTS[number];
+
+const RepoList = memo(({ repos, selectedRepo, onSelectRepo, selectedTopic, setSelectedTopic, selectedSince, setSelectedSince, summarizedUrls, isLoading, activeTab, onRefresh, favoriteSort, setFavoriteSort }: any) => {
   const { t } = useTranslation();
 
   return (
@@ -182,9 +231,22 @@ const RepoList = memo(({ repos, selectedRepo, onSelectRepo, selectedTopic, setSe
             <h3 className="text-sm font-black text-apple-text uppercase tracking-widest">
               {activeTab === "library" ? t('sidebar.library') : t('sidebar.search')}
             </h3>
-            <span className="text-[10px] text-apple-secondary bg-black/5 px-2 py-0.5 rounded-full font-bold">
-              {repos.length} {t('trending.projects')}
-            </span>
+            <div className="flex items-center space-x-2">
+              {activeTab === "library" && (
+                <select
+                  value={favoriteSort}
+                  onChange={e => setFavoriteSort(e.target.value as FavoriteSort)}
+                  className="text-[10px] font-bold text-apple-secondary bg-black/5 px-2 py-0.5 rounded-full outline-none"
+                >
+                  {FAVORITE_SORTS.map(sort => (
+                    <option key={sort} value={sort}>{t(`library.sort_${sort}`)}</option>
+                  ))}
+                </select>
+              )}
+              <span className="text-[10px] text-apple-secondary bg-black/5 px-2 py-0.5 rounded-full font-bold">
+                {repos.length} {t('trending.projects')}
+              </span>
+            </div>
           </div>
         )}
       </header>
@@ -267,6 +329,9 @@ const RepoList = memo(({ repos, selectedRepo, onSelectRepo, selectedTopic, setSe
 const InsightPanel = memo(({
   selectedRepo,
   insight,
+  reasoning,
+  usage,
+  rateLimit,
   isSummarizing,
   apiKey,
   onSummarize,
@@ -276,9 +341,15 @@ const InsightPanel = memo(({
   activeTab,
   selectedSince,
   isFavorite,
-  onToggleFavorite
+  onToggleFavorite,
+  onSaveNote
 }: any) => {
   const { t } = useTranslation();
+  const [note, setNote] = useState("");
+
+  useEffect(() => {
+    setNote(selectedRepo?.note ?? "");
+  }, [selectedRepo?.url]);
 
   return (
     <section className="flex-1 flex flex-col overflow-hidden bg-white">
@@ -349,6 +420,19 @@ const InsightPanel = memo(({
                 </a>
               </div>
             </div>
+            {/* 备注只在收藏列表中编辑，其他列表的仓库没有加载备注 */}
+            {activeTab === "library" && isFavorite && (
+              <div className="max-w-4xl mx-auto w-full mt-4">
+                <textarea
+                  value={note}
+                  onChange={e => setNote(e.target.value)}
+                  onBlur={() => note !== (selectedRepo.note ?? "") && onSaveNote(selectedRepo.url, note)}
+                  placeholder={t('insight.note_placeholder')}
+                  rows={2}
+                  className="w-full px-4 py-3 bg-white border border-apple-border rounded-2xl outline-none focus:ring-4 focus:ring-apple-accent/10 focus:border-apple-accent/40 text-[12px] text-apple-text resize-none transition-all"
+                />
+              </div>
+            )}
           </header>
           <div className="flex-1 p-10 overflow-y-auto">
             <div className="max-w-4xl mx-auto">
@@ -397,10 +481,27 @@ const InsightPanel = memo(({
                 </div>
               )}
 
+              {rateLimit && rateLimit.remaining <= RATE_LIMIT_WARNING && (
+                <div className="p-4 mb-6 bg-amber-50 text-amber-700 text-[11px] font-bold rounded-2xl border border-amber-100 font-sans">
+                  {t('insight.rate_limit_low', { remaining: rateLimit.remaining, limit: rateLimit.limit, reset: new Date(rateLimit.reset_at * 1000).toLocaleTimeString() })}
+                </div>
+              )}
+
               <article>
+                {reasoning && (
+                  <details className="mb-6 p-4 bg-apple-bg/20 rounded-2xl border border-apple-border/40" open={isSummarizing && !insight}>
+                    <summary className="text-[11px] font-bold text-apple-secondary cursor-pointer select-none">{t('insight.reasoning')}</summary>
+                    <p className="mt-3 text-[12px] text-apple-secondary leading-relaxed whitespace-pre-wrap">{reasoning}</p>
+                  </details>
+                )}
                 {insight ? (
                   <div className="text-[15px] leading-relaxed text-apple-text bg-apple-bg/5 p-8 rounded-3xl border border-apple-border/40 selection:bg-apple-accent/20">
                     <MarkdownView content={insight} />
+                    {!isSummarizing && usage && (
+                      <p className="mt-6 text-[10px] text-apple-secondary opacity-70 font-sans">
+                        {t('insight.usage', { prompt: usage.prompt_tokens, completion: usage.completion_tokens, total: usage.total_tokens })}
+                      </p>
+                    )}
                   </div>
                 ) : (
                   <div className="space-y-8">
@@ -412,7 +513,7 @@ const InsightPanel = memo(({
                     ) : (
                       <div className="flex flex-col items-center justify-center py-20 bg-apple-bg/20 rounded-3xl border border-dashed border-apple-border">
                         <p className="text-apple-text text-sm font-bold tracking-tight">{t('insight.click_to_generate')}</p>
-                        <button onClick={() => onSummarize(selectedRepo)} disabled={isSummarizing} className="mt-6 px-6 py-2.5 bg-apple-accent text-white text-[11px] font-extrabold rounded-full hover:shadow-lg transition-all disabled:opacity-50 font-sans">
+                        <button onClick={() => onSummarize(selectedRepo)} disabled={isSummarizing || rateLimit?.remaining === 0} className="mt-6 px-6 py-2.5 bg-apple-accent text-white text-[11px] font-extrabold rounded-full hover:shadow-lg transition-all disabled:opacity-50 font-sans">
                           {isSummarizing ? t('insight.analyzing') : t('insight.generate_btn')}
                         </button>
                       </div>
@@ -433,7 +534,7 @@ const InsightPanel = memo(({
 });
 
 function App() {
-  const { t } = useTranslation();
+  const { t, i18n } = useTranslation();
   const [activeTab, setActiveTab] = useState("trending");
   const [selectedTopic, setSelectedTopic] = useState("All");
   const [selectedSince, setSelectedSince] = useState("daily");
@@ -442,6 +543,9 @@ function App() {
   const [_error, setError] = useState<string | null>(null);
   const [apiKey, setApiKey] = useState("");
   const [insight, setInsight] = useState("");
+  const [reasoning, setReasoning] = useState("");
+  const [usage, setUsage] = useState<TokenUsage | null>(null);
+  const [githubRateLimit, setGithubRateLimit] = useState<GitHubRateLimit | null>(null);
   const [isSummarizing, setIsSummarizing] = useState(false);
   const [isLoadingTrending, setIsLoadingTrending] = useState(false);
 
@@ -456,6 +560,7 @@ function App() {
   // Search State
   const [searchQuery, setSearchQuery] = useState("");
   const [searchResults, setSearchResults] = useState<TrendingRepo[]>([]);
+  const [searchIncomplete, setSearchIncomplete] = useState(false);
   const [isSearching, setIsSearching] = useState(false);
   const [searchError, setSearchError] = useState<string | null>(null);
 
@@ -467,14 +572,20 @@ function App() {
   const [deepContextEnabled, setDeepContextEnabled] = useState(false);
   const [summarizedUrls, setSummarizedUrls] = useState<Set<string>>(new Set());
   const [favoriteRepos, setFavoriteRepos] = useState<TrendingRepo[]>([]);
+  const [favoriteSort, setFavoriteSort] = useState<FavoriteSort>("created_at");
   const [isFavorite, setIsFavorite] = useState(false);
 
   const insightRef = useRef("");
+  // 进行中的总结请求，切换仓库时取消以免继续消耗 token
+  const summaryRequestRef = useRef<string | null>(null);
   const searchInputRef = useRef<HTMLInputElement>(null);
   const isComposingRef = useRef(false);
 
   useEffect(() => {
     initStore();
+    checkStartupErrors();
+    // 后端错误信息跟随当前界面语言
+    invoke("update_app_settings", { updates: { ui_language: i18n.language } }).catch(console.error);
 
     // Global shortcut listener (⌘K / Ctrl+K)
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -494,7 +605,7 @@ function App() {
     } else if (activeTab === "library") {
       fetchFavorites();
     }
-  }, [activeTab, selectedSince]);
+  }, [activeTab, selectedSince, favoriteSort]);
 
   useEffect(() => {
     // 批量检查已有洞察的项目
@@ -509,6 +620,8 @@ function App() {
       // 切换项目时，尝试先加载缓存
       setInsight("");
       insightRef.current = "";
+      setReasoning("");
+      setUsage(null);
       checkCache(selectedRepo);
       checkFavoriteStatus(selectedRepo.url);
     }
@@ -526,6 +639,17 @@ function App() {
     }
   };
 
+  const checkStartupErrors = async () => {
+    try {
+      const errors: string[] = await invoke("get_startup_errors");
+      if (errors.length > 0) {
+        alert(errors.join("\n\n"));
+      }
+    } catch (e) {
+      console.error("Check startup errors failed:", e);
+    }
+  };
+
   const initStore = async () => {
     try {
       // 1. 尝试从配置管理器加载所有配置
@@ -560,6 +684,16 @@ function App() {
     }
   };
 
+  const handleDuplicateConfig = async (id: string) => {
+    try {
+      const copy = await invoke<ModelConfig>("duplicate_model_config", { configId: id });
+      setModelConfigs(prev => [...prev, copy]);
+    } catch (e: any) {
+      console.error("Duplicate config failed:", e);
+      alert(t('settings.duplicate_failed') + ": " + e);
+    }
+  };
+
   const handleDeleteConfig = async (id: string) => {
     try {
       await invoke("delete_model_config", { configId: id });
@@ -603,10 +737,13 @@ function App() {
     setIsTestingConnection(true);
     setTestResult(null);
     try {
-      await invoke("test_model_connection", { modelConfigId: id });
-      setTestResult({ success: true, message: t('settings.success') });
+      const report = await invoke<{ ok: boolean; latency_ms: number; model_echo: string | null }>("test_model_connection", { modelConfigId: id });
+      setTestResult(report.ok
+        ? { success: true, message: t('settings.success_latency', { latency: report.latency_ms, model: report.model_echo ?? "" }) }
+        : { success: false, message: t('settings.empty_reply') });
     } catch (e: any) {
-      setTestResult({ success: false, message: t('settings.failed') + ": " + e });
+      const label = isCommandError(e) && e.kind === "Auth" ? t('settings.auth_failed') : t('settings.failed');
+      setTestResult({ success: false, message: label + ": " + errorMessage(e) });
     } finally {
       setIsTestingConnection(false);
     }
@@ -617,8 +754,7 @@ function App() {
     setIsLoadingTrending(true);
     try {
       const result: TrendingRepo[] = await invoke("get_trending", {
-        language: null,
-        since: selectedSince
+        query: { language: null, since: selectedSince }
       });
       setRepos(result);
       if (result.length > 0) {
@@ -635,24 +771,32 @@ function App() {
   };
 
   // 执行搜索
+  const refreshRateLimit = () => {
+    invoke<GitHubRateLimit | null>("get_github_rate_limit")
+      .then(setGithubRateLimit)
+      .catch(console.error);
+  };
+
   const handleSearch = async () => {
     const finalQuery = aiRewriteEnabled && rewrittenQuery ? rewrittenQuery : searchQuery;
     if (!finalQuery.trim()) return;
     setIsSearching(true);
     setSearchError(null);
     try {
-      const result: TrendingRepo[] = await invoke("search_github", {
+      const result: SearchResults = await invoke("search_github", {
         query: finalQuery
       });
-      setSearchResults(result);
-      if (result.length > 0) {
-        setSelectedRepo(result[0]);
+      setSearchResults(result.items);
+      setSearchIncomplete(result.incomplete_results);
+      if (result.items.length > 0) {
+        setSelectedRepo(result.items[0]);
       }
     } catch (e: any) {
       console.error("Search failed:", e);
       setSearchError(e.toString());
     } finally {
       setIsSearching(false);
+      refreshRateLimit();
     }
   };
 
@@ -663,28 +807,35 @@ function App() {
       setIsRewriting(true);
       setRewriteError(null);
       setRewrittenQuery("");
+      // 改写失败（如超时）时提示原因，并回退到原始查询继续搜索
+      let query = searchQuery;
       try {
-        const rewritten: string = await invoke("ai_rewrite_query", {
+        query = await invoke<string>("ai_rewrite_query", {
           query: searchQuery,
           modelConfigId: activeConfigId,
           apiKey: !activeConfigId ? apiKey : undefined
         });
-        setRewrittenQuery(rewritten);
-        // 自动使用改写后的查询进行搜索
-        setIsSearching(true);
-        setSearchError(null);
-        const result: TrendingRepo[] = await invoke("search_github", {
-          query: rewritten
-        });
-        setSearchResults(result);
-        if (result.length > 0) {
-          setSelectedRepo(result[0]);
+        setRewrittenQuery(query);
+      } catch (e: any) {
+        console.error("Rewrite failed, using raw query:", e);
+        setRewriteError(errorMessage(e));
+      } finally {
+        setIsRewriting(false);
+      }
+      // 自动使用改写后的查询进行搜索
+      setIsSearching(true);
+      setSearchError(null);
+      try {
+        const result: SearchResults = await invoke("search_github", { query });
+        setSearchResults(result.items);
+        setSearchIncomplete(result.incomplete_results);
+        if (result.items.length > 0) {
+          setSelectedRepo(result.items[0]);
         }
       } catch (e: any) {
         console.error("Full search failed:", e);
-        setSearchError(e.toString());
+        setSearchError(errorMessage(e));
       } finally {
-        setIsRewriting(false);
         setIsSearching(false);
       }
     } else {
@@ -698,20 +849,41 @@ function App() {
   }, [repos, selectedTopic]);
 
   const handleSummarize = useCallback(async (repo: TrendingRepo, forceRefresh: boolean = false) => {
+    if (summaryRequestRef.current) {
+      invoke("cancel_summary", { requestId: summaryRequestRef.current }).catch(console.error);
+    }
+    const requestId = crypto.randomUUID();
+    summaryRequestRef.current = requestId;
     setInsight("");
     insightRef.current = "";
+    setReasoning("");
+    setUsage(null);
     setIsSummarizing(true);
 
     const onEvent = new Channel<StreamPayload>();
     onEvent.onmessage = (payload) => {
+      // 已被取消的旧请求可能仍有少量残留输出
+      if (summaryRequestRef.current !== requestId) return;
       if (payload.type === "Token") {
         insightRef.current += payload.data;
         setInsight(insightRef.current);
+      } else if (payload.type === "Reasoning") {
+        setReasoning(prev => prev + payload.data);
+      } else if (payload.type === "Reset") {
+        insightRef.current = "";
+        setInsight("");
+        setReasoning("");
+      } else if (payload.type === "ContextSkipped") {
+        console.warn("GitHub request budget exhausted, skipped context:", payload.data);
+      } else if (payload.type === "Usage") {
+        setUsage(payload.data);
       } else if (payload.type === "Done") {
         setIsSummarizing(false);
+        refreshRateLimit();
       } else if (payload.type === "Error") {
         setInsight(prev => prev + `\n\n[Error: ${payload.data}]`);
         setIsSummarizing(false);
+        refreshRateLimit();
       }
     };
 
@@ -724,16 +896,24 @@ function App() {
           language: repo.language,
           url: repo.url
         },
-        modelConfigId: activeConfigId,
-        apiKey: !activeConfigId ? apiKey : undefined,
-        deepContext: deepContextEnabled,
-        forceRefresh,
+        params: {
+          model_config_id: activeConfigId,
+          api_key: !activeConfigId ? apiKey : undefined,
+          deep_context: deepContextEnabled,
+          force_refresh: forceRefresh,
+          request_id: requestId
+        },
         onEvent
       });
     } catch (error) {
+      // 被新的总结取消时，界面已切换到新请求，无需处理
+      if (isCommandError(error) && error.kind === "Cancelled") return;
       console.error("Summarize failed:", error);
       setIsSummarizing(false);
     } finally {
+      if (summaryRequestRef.current === requestId) {
+        summaryRequestRef.current = null;
+      }
       // 成功生成后刷新洞察状态
       checkInsightsBatch([repo]);
     }
@@ -741,7 +921,7 @@ function App() {
 
   const fetchFavorites = async () => {
     try {
-      const result: TrendingRepo[] = await invoke("get_favorites");
+      const result: TrendingRepo[] = await invoke("get_favorites", { sort: favoriteSort });
       setFavoriteRepos(result);
     } catch (e) {
       console.error("Fetch favorites failed:", e);
@@ -781,6 +961,17 @@ function App() {
     }
   }, [activeTab]);
 
+  const handleSaveNote = useCallback(async (url: string, note: string) => {
+    try {
+      await invoke("set_favorite_note", { url, note });
+      const saved = note.trim() || null;
+      setFavoriteRepos(prev => prev.map(r => r.url === url ? { ...r, note: saved } : r));
+    } catch (e: any) {
+      console.error("Save note failed:", e);
+      alert(t('insight.note_failed') + ": " + e.toString());
+    }
+  }, []);
+
   const checkInsightsBatch = async (repoList: TrendingRepo[]) => {
     try {
       const existingUrls: string[] = await invoke("check_insights_batch", {
@@ -970,6 +1161,11 @@ function App() {
                       </span>
                     </div>
                   </div>
+                  {searchIncomplete && (
+                    <div className="text-[11px] text-amber-600 bg-amber-50 px-3 py-2 rounded-lg mb-2">
+                      {t('search.incomplete')}
+                    </div>
+                  )}
 
                   {searchResults.map((repo) => (
                     <div
@@ -1041,6 +1237,9 @@ function App() {
                 <InsightPanel
                   selectedRepo={selectedRepo}
                   insight={insight}
+                  reasoning={reasoning}
+                  usage={usage}
+                  rateLimit={githubRateLimit}
                   isSummarizing={isSummarizing}
                   apiKey={apiKey}
                   onSummarize={handleSummarize}
@@ -1051,6 +1250,7 @@ function App() {
                   selectedSince={selectedSince}
                   isFavorite={isFavorite}
                   onToggleFavorite={handleToggleFavorite}
+                  onSaveNote={handleSaveNote}
                 />
               )}
             </div>
@@ -1090,6 +1290,7 @@ function App() {
             activeConfigId={activeConfigId}
             onSetActive={handleSetActiveConfig}
             onDelete={handleDeleteConfig}
+            onDuplicate={handleDuplicateConfig}
             onAdd={() => {
               setEditingConfig({
                 name: "新配置",
@@ -1121,10 +1322,15 @@ function App() {
               isLoading={activeTab === "trending" && isLoadingTrending}
               activeTab={activeTab}
               onRefresh={fetchTrending}
+              favoriteSort={favoriteSort}
+              setFavoriteSort={setFavoriteSort}
             />
             <InsightPanel
               selectedRepo={selectedRepo}
               insight={insight}
+              reasoning={reasoning}
+              usage={usage}
+              rateLimit={githubRateLimit}
               isSummarizing={isSummarizing}
               apiKey={apiKey}
               onSummarize={handleSummarize}
@@ -1135,6 +1341,7 @@ function App() {
               selectedSince={selectedSince}
               isFavorite={isFavorite}
               onToggleFavorite={handleToggleFavorite}
+              onSaveNote={handleSaveNote}
             />
           </>
         )}
@@ -1158,7 +1365,7 @@ function App() {
 }
 
 // Additional UI components for Settings to reduce App body size
-const SettingsPanel = memo(({ modelConfigs, activeConfigId, onSetActive, onDelete, onAdd, onEdit }: any) => {
+const SettingsPanel = memo(({ modelConfigs, activeConfigId, onSetActive, onDelete, onDuplicate, onAdd, onEdit }: any) => {
   const { t } = useTranslation();
 
   return (
@@ -1204,6 +1411,7 @@ const SettingsPanel = memo(({ modelConfigs, activeConfigId, onSetActive, onDelet
                 <div className="mt-8 flex items-center justify-between">
                   <div className="flex space-x-2">
                     <button onClick={() => onEdit(config)} className="p-2 text-apple-secondary hover:text-apple-text hover:bg-apple-bg rounded-xl transition-all"><svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"><path d="M11 4H4a2 2 0 0 0-2 2v14a2 2 0 0 0 2 2h14a2 2 0 0 0 2-2v-7" /><path d="M18.5 2.5a2.121 2.121 0 0 1 3 3L12 15l-4 1 1-4 9.5-9.5z" /></svg></button>
+                    <button onClick={() => onDuplicate(config.id)} title={t('settings.duplicate')} className="p-2 text-apple-secondary hover:text-apple-text hover:bg-apple-bg rounded-xl transition-all"><svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"><rect x="9" y="9" width="13" height="13" rx="2" ry="2" /><path d="M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1" /></svg></button>
                     <button onClick={() => onDelete(config.id)} className="p-2 text-apple-secondary hover:text-red-500 hover:bg-red-50 rounded-xl transition-all"><svg width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round"><polyline points="3 6 5 6 21 6" /><path d="M19 6v14a2 2 0 0 1-2 2H7a2 2 0 0 1-2-2V6m3 0V4a2 2 0 0 1 2-2h4a2 2 0 0 1 2 2v2" /><line x1="10" y1="11" x2="10" y2="17" /><line x1="14" y1="11" x2="14" y2="17" /></svg></button>
                   </div>
                   <button onClick={() => onSetActive(config.id)} disabled={activeConfigId === config.id} className={`px-4 py-1.5 rounded-xl text-[11px] font-bold transition-all ${activeConfigId === config.id ? "bg-apple-accent/10 text-apple-accent cursor-default" : "bg-apple-bg text-apple-secondary hover:text-apple-text hover:shadow-sm"}`}>{activeConfigId === config.id ? t('settings.active') : t('settings.activate')}</button>
@@ -1241,12 +1449,14 @@ const ConfigModal = memo(({ editingConfig, setEditingConfig, onClose, onSave, on
           <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.config_name')}</label><input value={editingConfig.name || ""} onChange={e => setEditingConfig((prev: any) => ({ ...prev, name: e.target.value }))} placeholder="e.g.: DeepSeek-V3" className="w-full px-4 py-3 bg-apple-bg/50 border border-apple-border rounded-2xl outline-none focus:ring-4 focus:ring-apple-accent/10 focus:border-apple-accent/40 text-sm transition-all" /></div>
           <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.provider')}</label><div className="grid grid-cols-2 gap-2">{["OpenAI", "DeepSeek", "Anthropic", "Google", "AzureOpenAI"].map(p => (<button key={p} onClick={() => setEditingConfig((prev: any) => ({ ...prev, provider: p }))} className={`px-4 py-2.5 rounded-xl border text-[11px] font-bold transition-all ${editingConfig.provider === p ? "bg-apple-accent text-white border-apple-accent" : "bg-apple-bg border-apple-border/50 text-apple-secondary hover:border-apple-accent/30"}`}>{p}</button>))}</div></div>
           <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.api_url')}</label><input value={editingConfig.api_base_url || ""} onChange={e => setEditingConfig((prev: any) => ({ ...prev, api_base_url: e.target.value }))} placeholder="https://api.openai.com/v1" className="w-full px-4 py-3 bg-apple-bg/50 border border-apple-border rounded-2xl outline-none text-sm font-mono" /></div>
+          {editingConfig.provider === "AzureOpenAI" && <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.api_version')}</label><input value={editingConfig.api_version || ""} onChange={e => setEditingConfig((prev: any) => ({ ...prev, api_version: e.target.value }))} placeholder="2024-02-15-preview" className="w-full px-4 py-3 bg-apple-bg/50 border border-apple-border rounded-2xl outline-none text-sm font-mono" /></div>}
+          {editingConfig.provider === "Anthropic" && <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.thinking_budget')}</label><input type="number" min={0} value={editingConfig.thinking_budget || ""} onChange={e => setEditingConfig((prev: any) => ({ ...prev, thinking_budget: Number(e.target.value) || 0 }))} placeholder="1024" className="w-full px-4 py-3 bg-apple-bg/50 border border-apple-border rounded-2xl outline-none text-sm font-mono" /></div>}
           <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.model_name')}</label><input value={editingConfig.default_model || ""} onChange={e => setEditingConfig((prev: any) => ({ ...prev, default_model: e.target.value }))} placeholder="gpt-4o-mini" className="w-full px-4 py-3 bg-apple-bg/50 border border-apple-border rounded-2xl outline-none text-sm font-mono" /></div>
           <div className="space-y-1.5"><label className="text-[12px] font-bold text-apple-text flex items-center"><span className="w-1 h-3 bg-apple-accent rounded-full mr-2"></span>{t('settings.api_key')}</label><input type="password" value={editingConfig.api_key || ""} onChange={e => setEditingConfig((prev: any) => ({ ...prev, api_key: e.target.value }))} placeholder="sk-..." className="w-full px-4 py-3 bg-apple-bg/50 border border-apple-border rounded-2xl outline-none text-sm font-mono" /></div>
           {testResult && <div className={`p-4 rounded-2xl text-[11px] font-bold flex items-center ${testResult.success ? "bg-green-50 text-green-600 border border-green-100" : "bg-red-50 text-red-600 border border-red-100"}`}><span className="mr-2 text-sm">{testResult.success ? "✅" : "❌"}</span>{testResult.message}</div>}
         </div>
         <div className="p-8 bg-apple-bg/30 border-t border-apple-border/30 flex justify-between">
-          <button onClick={() => onTest(editingConfig.id || "temp")} disabled={isTestingConnection || !editingConfig.api_key} className="px-6 py-2.5 bg-white border border-apple-border/50 text-apple-text text-[12px] font-bold rounded-xl hover:bg-apple-bg transition-all flex items-center space-x-2 disabled:opacity-50 tracking-tight">{isTestingConnection ? <><div className="w-3 h-3 border-2 border-apple-accent/20 border-t-apple-accent rounded-full animate-spin" /><span>Testing...</span></> : <span>{t('settings.test')}</span>}</button>
+          <button onClick={() => onTest(editingConfig.id || "temp")} disabled={isTestingConnection || (!editingConfig.api_key && typeof editingConfig.provider === 'string')} className="px-6 py-2.5 bg-white border border-apple-border/50 text-apple-text text-[12px] font-bold rounded-xl hover:bg-apple-bg transition-all flex items-center space-x-2 disabled:opacity-50 tracking-tight">{isTestingConnection ? <><div className="w-3 h-3 border-2 border-apple-accent/20 border-t-apple-accent rounded-full animate-spin" /><span>Testing...</span></> : <span>{t('settings.test')}</span>}</button>
           <button onClick={() => editingConfig.id ? onUpdate(editingConfig.id, editingConfig) : onSave(editingConfig as ModelConfig)} className="px-10 py-2.5 bg-apple-accent text-white text-[12px] font-bold rounded-xl hover:bg-blue-700 shadow-md active:scale-95 transition-all">{t('settings.save')}</button>
         </div>
       </div>
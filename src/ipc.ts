@@ -0,0 +1,24 @@
+import { invoke as tauriInvoke, InvokeArgs } from "@tauri-apps/api/core";
+
+// 带分类的命令错误（summarize_repo、ai_rewrite_query、test_model_connection、list_models）
+export interface CommandError {
+  kind: "Auth" | "Network" | "Quota" | "Config" | "Parse" | "NotFound" | "Cancelled" | "Other";
+  message: string;
+}
+
+export const isCommandError = (e: unknown): e is CommandError =>
+  typeof e === "object" && e !== null && "kind" in e && "message" in e;
+
+export const errorMessage = (e: unknown): string => (isCommandError(e) ? e.message : String(e));
+
+// 统一的命令调用入口：命令失败时记录到最近错误（用户主动取消的除外），再原样抛出
+export async function invoke<T>(cmd: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (e) {
+    if (!(isCommandError(e) && e.kind === "Cancelled")) {
+      tauriInvoke("record_command_error", { command: cmd, message: errorMessage(e) }).catch(() => {});
+    }
+    throw e;
+  }
+}